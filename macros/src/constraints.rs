@@ -0,0 +1,427 @@
+use crate::{
+    openapi::{field_name, get_skip_mode, has_serde_flag},
+    parse::{Delimited, Paren},
+    util::{Errors, ItemImplExt},
+};
+use pmutil::{q, ToTokensExt};
+use syn::{
+    parse2, parse_quote_spanned, punctuated::Punctuated, spanned::Spanned, Attribute, Block, Data,
+    DeriveInput, Error, Expr, Field, Fields, ItemImpl, Lit, Meta, MetaNameValue, NestedMeta, Path,
+    Stmt, Token, Type,
+};
+
+/// Constraints from `#[schema(min = 1, max_length = 32, ...)]`.
+#[derive(Default)]
+pub struct Constraints {
+    min: Option<serde_json::Number>,
+    max: Option<serde_json::Number>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    pattern: Option<String>,
+    enum_values: Vec<String>,
+    min_items: Option<usize>,
+    max_items: Option<usize>,
+    format: Option<String>,
+}
+
+/// Returns true for `#[schema(validate)]`.
+pub fn is_validate(attrs: &[Attribute]) -> bool {
+    schema_configs(attrs)
+        .unwrap_or_default()
+        .iter()
+        .any(|meta| matches!(meta, Meta::Path(p) if p.is_ident("validate")))
+}
+
+fn schema_configs(attrs: &[Attribute]) -> Result<Vec<Meta>, Error> {
+    let mut configs = vec![];
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("schema")) {
+        configs.extend(
+            parse2::<Paren<Delimited<Meta>>>(attr.tokens.clone())?
+                .inner
+                .inner,
+        );
+    }
+    Ok(configs)
+}
+
+/// Checks `#[schema(..)]` on each field.
+pub fn check_field_attrs(input: &DeriveInput) -> Result<(), Error> {
+    let fields: Vec<&Field> = match &input.data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data) => data.variants.iter().flat_map(|v| &v.fields).collect(),
+        Data::Union(..) => vec![],
+    };
+
+    let mut errors = Errors::default();
+    for f in fields {
+        errors.collect(parse_constraints(&f.attrs));
+    }
+    errors.finish()
+}
+
+/// Parses `#[schema(..)]` of a field.
+pub fn parse_constraints(attrs: &[Attribute]) -> Result<Constraints, Error> {
+    let mut c = Constraints::default();
+    let mut errors = Errors::default();
+
+    for meta in schema_configs(attrs)? {
+        match meta {
+            Meta::NameValue(MetaNameValue { path, lit, .. }) => {
+                let name = path.dump().to_string();
+                match &*name {
+                    "min" => c.min = errors.collect(number(&name, &lit)),
+                    "max" => c.max = errors.collect(number(&name, &lit)),
+                    "min_length" => c.min_length = errors.collect(usize(&name, &lit)),
+                    "max_length" => c.max_length = errors.collect(usize(&name, &lit)),
+                    "min_items" => c.min_items = errors.collect(usize(&name, &lit)),
+                    "max_items" => c.max_items = errors.collect(usize(&name, &lit)),
+                    "pattern" => {
+                        c.pattern = errors.collect(string(&name, &lit).and_then(|pattern| {
+                            match regex::Regex::new(&pattern) {
+                                Ok(..) => Ok(pattern),
+                                Err(err) => Err(Error::new_spanned(
+                                    &lit,
+                                    format!(
+                                        "#[schema(pattern = \"...\")]: invalid pattern: {}",
+                                        err
+                                    ),
+                                )),
+                            }
+                        }))
+                    }
+                    "format" => c.format = errors.collect(string(&name, &lit)),
+                    // Handled by `#[derive(Schema)]`.
+                    "description" | "example" => {}
+                    _ => errors.push(unknown_option(&path)),
+                }
+            }
+            Meta::List(l) if l.path.is_ident("enum_values") => {
+                for v in l.nested {
+                    match v {
+                        NestedMeta::Lit(Lit::Str(s)) => c.enum_values.push(s.value()),
+                        _ => errors.push(Error::new_spanned(
+                            &v,
+                            "#[schema(enum_values(\"a\", \"b\"))]: values should be string \
+                             literals",
+                        )),
+                    }
+                }
+            }
+            Meta::List(l) => errors.push(unknown_option(&l.path)),
+            Meta::Path(p) => errors.push(unknown_option(&p)),
+        }
+    }
+
+    errors.finish()?;
+    Ok(c)
+}
+
+fn unknown_option(path: &Path) -> Error {
+    Error::new_spanned(
+        path,
+        format!(
+            "#[schema]: unknown option `{}` for a field. Expected one of min, max, min_length, \
+             max_length, min_items, max_items, pattern, format, enum_values, description, example",
+            path.dump()
+        ),
+    )
+}
+
+fn number(name: &str, lit: &Lit) -> Result<serde_json::Number, Error> {
+    let v = match lit {
+        Lit::Int(i) => Some(i.base10_digits().to_string()),
+        Lit::Float(f) => Some(f.base10_digits().to_string()),
+        // Negative numbers are not literals.
+        Lit::Str(s) => Some(s.value()),
+        _ => None,
+    };
+    v.and_then(|v| serde_json::from_str(&v).ok())
+        .ok_or_else(|| {
+            Error::new_spanned(
+                lit,
+                format!("#[schema({} = 1)]: value should be a number", name),
+            )
+        })
+}
+
+fn usize(name: &str, lit: &Lit) -> Result<usize, Error> {
+    match lit {
+        Lit::Int(i) => i.base10_parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        Error::new_spanned(
+            lit,
+            format!(
+                "#[schema({} = 1)]: value should be a non-negative integer",
+                name
+            ),
+        )
+    })
+}
+
+fn string(name: &str, lit: &Lit) -> Result<String, Error> {
+    match lit {
+        Lit::Str(s) => Ok(s.value()),
+        _ => Err(Error::new_spanned(
+            lit,
+            format!(
+                "#[schema({} = \"foo\")]: value should be a string literal",
+                name
+            ),
+        )),
+    }
+}
+
+/// `f64` expression for a constraint.
+fn quote_f64(v: &serde_json::Number) -> Expr {
+    syn::parse_str(&format!("{:?}f64", v.as_f64().unwrap())).unwrap()
+}
+
+impl Constraints {
+    /// Statements writing constraints to `s`, a `&mut rweb::openapi::Schema`.
+    pub fn quote_schema(&self) -> Block {
+        let mut block: Block = q!({ {} }).parse();
+
+        if let Some(v) = &self.min {
+            block.stmts.push(
+                q!(
+                    Vars {
+                        json: v.to_string()
+                    },
+                    {
+                        s.minimum = Some(rweb::rt::serde_json::from_str(json).unwrap());
+                    }
+                )
+                .parse(),
+            );
+        }
+        if let Some(v) = &self.max {
+            block.stmts.push(
+                q!(
+                    Vars {
+                        json: v.to_string()
+                    },
+                    {
+                        s.maximum = Some(rweb::rt::serde_json::from_str(json).unwrap());
+                    }
+                )
+                .parse(),
+            );
+        }
+        if let Some(v) = self.min_length {
+            block.stmts.push(
+                q!(Vars { v }, {
+                    s.min_length = Some(v);
+                })
+                .parse(),
+            );
+        }
+        if let Some(v) = self.max_length {
+            block.stmts.push(
+                q!(Vars { v }, {
+                    s.max_length = Some(v);
+                })
+                .parse(),
+            );
+        }
+        if let Some(v) = &self.pattern {
+            block.stmts.push(
+                q!(Vars { v }, {
+                    s.pattern = rweb::rt::Cow::Borrowed(v);
+                })
+                .parse(),
+            );
+        }
+        if !self.enum_values.is_empty() {
+            let values: Punctuated<pmutil::Quote, Token![,]> = self
+                .enum_values
+                .iter()
+                .map(|v| q!(Vars { v }, { rweb::rt::Cow::Borrowed(v) }))
+                .collect();
+            block.stmts.push(
+                q!(Vars { values }, {
+                    s.enum_values = vec![values];
+                })
+                .parse(),
+            );
+        }
+        if let Some(v) = self.min_items {
+            block.stmts.push(
+                q!(Vars { v }, {
+                    s.min_items = Some(v);
+                })
+                .parse(),
+            );
+        }
+        if let Some(v) = self.max_items {
+            block.stmts.push(
+                q!(Vars { v }, {
+                    s.max_items = Some(v);
+                })
+                .parse(),
+            );
+        }
+        if let Some(v) = &self.format {
+            block.stmts.push(
+                q!(Vars { v }, {
+                    s.format = rweb::rt::Cow::Borrowed(v);
+                })
+                .parse(),
+            );
+        }
+
+        block
+    }
+
+    /// Statements checking `value_expr`, a reference to a value of type `ty`.
+    fn quote_checks(&self, value_expr: &Expr, ty: &Type, name: &str) -> Vec<Stmt> {
+        let mut stmts: Vec<Stmt> = vec![];
+
+        let checks = vec![
+            ("check_min", self.min.as_ref().map(quote_f64)),
+            ("check_max", self.max.as_ref().map(quote_f64)),
+            (
+                "check_min_length",
+                self.min_length.map(|v| q!(Vars { v }, { v }).parse()),
+            ),
+            (
+                "check_max_length",
+                self.max_length.map(|v| q!(Vars { v }, { v }).parse()),
+            ),
+            (
+                "check_pattern",
+                self.pattern.as_ref().map(|v| q!(Vars { v }, { v }).parse()),
+            ),
+            ("check_enum_values", {
+                if self.enum_values.is_empty() {
+                    None
+                } else {
+                    let values: Punctuated<&String, Token![,]> = self.enum_values.iter().collect();
+                    Some(q!(Vars { values }, { &[values] }).parse())
+                }
+            }),
+            (
+                "check_min_items",
+                self.min_items.map(|v| q!(Vars { v }, { v }).parse()),
+            ),
+            (
+                "check_max_items",
+                self.max_items.map(|v| q!(Vars { v }, { v }).parse()),
+            ),
+        ];
+        for (check, arg) in checks {
+            let arg: Expr = match arg {
+                Some(arg) => arg,
+                None => continue,
+            };
+            // Spanned, like `value_expr`, so that a constraint on a field of a wrong
+            // type is reported at the field.
+            let span = ty.span();
+            let check = syn::Ident::new(check, span);
+            stmts.push(parse_quote_spanned!(span=>
+                rweb::rt::#check(#value_expr, #arg, path, #name, violations);
+            ));
+        }
+
+        // Constraints of the value itself.
+        stmts.push(
+            q!(
+                Vars {
+                    Type: ty,
+                    value_expr,
+                    name_str: name
+                },
+                {
+                    {
+                        use rweb::rt::{ViaFieldConstraints as _, ViaNoFieldConstraints as _};
+                        (&rweb::rt::Tag::<Type>::new())
+                            .check_constraints(value_expr, path, name_str, violations);
+                    }
+                }
+            )
+            .parse(),
+        );
+
+        stmts
+    }
+}
+
+/// Implements `rweb::validation::Constraints` for a struct with
+/// `#[schema(validate)]`.
+///
+/// Invalid `#[schema(..)]` on fields are reported by [check_field_attrs].
+pub fn derive_constraints(input: &DeriveInput) -> Result<ItemImpl, Error> {
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "#[schema(validate)] can only be used with structs",
+            ))
+        }
+    };
+
+    let mut body: Block = q!({ {} }).parse();
+    match &data.fields {
+        Fields::Named(..) => {
+            for f in &data.fields {
+                // The value is not from the request.
+                if get_skip_mode(&f.attrs).1 {
+                    continue;
+                }
+                let name = if has_serde_flag(&f.attrs, "flatten") {
+                    String::new()
+                } else {
+                    field_name(&input.attrs, f)
+                };
+                let ident = f.ident.as_ref().unwrap();
+                let value_expr = parse_quote_spanned!(f.ty.span()=> &self.#ident);
+                body.stmts.extend(
+                    parse_constraints(&f.attrs)
+                        .unwrap_or_default()
+                        .quote_checks(&value_expr, &f.ty, &name),
+                );
+            }
+        }
+        Fields::Unnamed(fields) => {
+            let newtype = fields.unnamed.len() == 1;
+            for (idx, f) in fields.unnamed.iter().enumerate() {
+                let name = if newtype {
+                    String::new()
+                } else {
+                    idx.to_string()
+                };
+                let idx = syn::Index::from(idx);
+                let value_expr = parse_quote_spanned!(f.ty.span()=> &self.#idx);
+                body.stmts.extend(
+                    parse_constraints(&f.attrs)
+                        .unwrap_or_default()
+                        .quote_checks(&value_expr, &f.ty, &name),
+                );
+            }
+        }
+        Fields::Unit => {}
+    }
+
+    Ok(q!(
+        Vars {
+            Type: &input.ident,
+            body,
+        },
+        {
+            impl rweb::validation::Constraints for Type {
+                #[allow(unused_variables)]
+                fn check_constraints(
+                    &self,
+                    path: &str,
+                    violations: &mut std::vec::Vec<rweb::validation::Violation>,
+                ) {
+                    body
+                }
+            }
+        }
+    )
+    .parse::<ItemImpl>()
+    .with_generics(input.generics.clone()))
+}
@@ -1,10 +1,14 @@
 extern crate proc_macro;
 use self::route::compile_route;
 use pmutil::{q, ToTokensExt};
+use quote::ToTokens;
 
+mod constraints;
+mod multipart;
 mod openapi;
 mod parse;
 mod path;
+mod response;
 mod route;
 mod router;
 mod util;
@@ -70,23 +74,73 @@ pub fn patch(
 ///
 /// # Note
 ///
-/// Currently router returns 404 error if there is a no matching rule.
+/// By default, router returns 404 error if there is a no matching rule. Use
+/// `fallback(handler)` to handle the rejection, where `handler` is an async
+/// function like the one passed to `warp::Filter::recover`.
 #[proc_macro_attribute]
 pub fn router(
     attr: proc_macro::TokenStream,
     item: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-    router::router(attr.into(), item.into()).dump().into()
+    match router::router(attr.into(), item.into()) {
+        Ok(item) => item.dump().into(),
+        Err(err) => err.to_compile_error().into(),
+    }
 }
 
-/// Implements Entity for the type.
+/// Implements Entity for the type, and `Constraints` if the type has
+/// `#[schema(validate)]`.
 ///
 /// See documentation of Entity for details and examples.
 #[proc_macro_derive(Schema, attributes(schema))]
 pub fn derive_schema(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    if !cfg!(feature = "openapi") {
-        return "".parse().unwrap();
+    let input = syn::parse::<syn::DeriveInput>(input).expect("failed to parse derive input");
+    let mut tokens = proc_macro2::TokenStream::new();
+    let mut errors = util::Errors::default();
+    // Constraints are checked even if openapi is disabled.
+    errors.collect(constraints::check_field_attrs(&input));
+    if constraints::is_validate(&input.attrs) {
+        if let Some(item) = errors.collect(constraints::derive_constraints(&input)) {
+            item.to_tokens(&mut tokens);
+        }
     }
+    if let Err(err) = errors.finish() {
+        tokens.extend(err.to_compile_error());
+    }
+    if cfg!(feature = "openapi") {
+        tokens.extend(openapi::derive_schema(input.clone()));
+
+        // Types with `#[derive(ResponseError)]` are documented by variant. Errors
+        // are reported by `#[derive(ResponseError)]`.
+        let mut errors = util::Errors::default();
+        let arms = response::response_arms(&input, &mut errors);
+        if errors.finish().is_ok() && !arms.is_empty() {
+            openapi::derive_response_entity(&input, &arms).to_tokens(&mut tokens);
+        }
+    }
+    tokens.into()
+}
+
+/// Implements `ResponseError` and `Reply` for the type. If the type also
+/// derives `Schema`, `ResponseEntity` is implemented by `#[derive(Schema)]`.
+///
+/// The status code of each variant is configured with
+/// `#[response(status = 404, description = "Item not found")]`. An attribute
+/// on an enum is used for variants without one.
+#[proc_macro_derive(ResponseError, attributes(response))]
+pub fn derive_response_error(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse::<syn::DeriveInput>(input).expect("failed to parse derive input");
+    match response::derive_response_error(input) {
+        Ok(item) => item.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_derive(Multipart, attributes(multipart))]
+pub fn derive_multipart(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse::<syn::DeriveInput>(input).expect("failed to parse derive input");
-    openapi::derive_schema(input).into()
+    match multipart::derive_multipart(input) {
+        Ok(item) => item.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
 }
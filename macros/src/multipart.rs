@@ -0,0 +1,334 @@
+use crate::util::{Errors, ItemImplExt};
+use pmutil::{q, ToTokensExt};
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::{
+    punctuated::Punctuated, Attribute, Block, Data, DeriveInput, Error, Expr, Field, Fields,
+    GenericArgument, ItemImpl, Lit, Meta, NestedMeta, PathArguments, Stmt, Token, Type,
+};
+
+/// Configuration from `#[multipart(max_size = 1024, rename = "name")]` on a
+/// field.
+struct FieldAttr {
+    max_size: Option<u64>,
+    rename: Option<String>,
+}
+
+/// Implements `MultipartForm` and `FromRequest` (and `Entity` if openapi is
+/// enabled) for the struct.
+pub fn derive_multipart(input: DeriveInput) -> Result<TokenStream, Error> {
+    let mut errors = Errors::default();
+
+    let max_length = errors
+        .collect(parse_attr(&input.attrs, &["max_length"]))
+        .and_then(|mut attr| attr.remove(0));
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "#[derive(Multipart)] requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "#[derive(Multipart)] can only be used with structs",
+            ))
+        }
+    };
+
+    let mut parsed: Vec<(&Field, String, FieldAttr)> = vec![];
+    for f in fields {
+        let attr = match errors.collect(parse_attr(&f.attrs, &["max_size", "rename"])) {
+            Some(mut v) => {
+                let rename = v.remove(1);
+                FieldAttr {
+                    max_size: v.remove(0).and_then(|lit| lit_u64(&mut errors, &lit)),
+                    rename: rename.and_then(|lit| match lit {
+                        Lit::Str(s) => Some(s.value()),
+                        lit => {
+                            errors.push(Error::new_spanned(
+                                lit,
+                                "#[multipart(rename = \"...\")]: value should be a string",
+                            ));
+                            None
+                        }
+                    }),
+                }
+            }
+            None => continue,
+        };
+        let name = attr.rename.clone().unwrap_or_else(|| {
+            f.ident
+                .as_ref()
+                .unwrap()
+                .to_string()
+                .trim_start_matches("r#")
+                .to_string()
+        });
+        parsed.push((f, name, attr));
+    }
+    let max_length = max_length.and_then(|lit| lit_u64(&mut errors, &lit));
+    errors.finish()?;
+
+    let mut lets: Vec<Stmt> = vec![];
+    let mut arms: Punctuated<pmutil::Quote, Token![,]> = Punctuated::new();
+    let mut inits: Punctuated<pmutil::Quote, Token![,]> = Punctuated::new();
+    let mut schemas: Punctuated<pmutil::Quote, Token![,]> = Punctuated::new();
+
+    for (idx, (f, name, attr)) in parsed.iter().enumerate() {
+        let ident = f.ident.as_ref().unwrap();
+        let var = syn::Ident::new(&format!("__field{}", idx), ident.span());
+        let (ty, required) = match option_inner(&f.ty) {
+            Some(ty) => (ty, false),
+            None => (&f.ty, true),
+        };
+        let max_size: Expr = match attr.max_size {
+            Some(v) => q!(Vars { v }, { Some(v) }).parse(),
+            None => q!({ None }).parse(),
+        };
+
+        lets.push(
+            q!(Vars { var: &var, ty }, {
+                let mut var: Option<ty> = None;
+            })
+            .parse(),
+        );
+        arms.push(q!(
+            Vars {
+                var: &var,
+                ty,
+                name_str: name,
+                max_size,
+            },
+            {
+                name_str => {
+                    var = Some(
+                        <ty as rweb::multipart::FromPart>::from_part(part, name_str, max_size)
+                            .await?,
+                    );
+                }
+            }
+        ));
+        inits.push(if required {
+            q!(
+                Vars {
+                    ident,
+                    var: &var,
+                    name_str: name
+                },
+                { ident: rweb::multipart::required(var, name_str)? }
+            )
+        } else {
+            q!(Vars { ident, var }, { ident: var })
+        });
+
+        let description = crate::openapi::extract_doc(&f.attrs).trim().to_string();
+        schemas.push(q!(
+            Vars {
+                ty,
+                name_str: name,
+                required,
+                description,
+            },
+            {
+                (
+                    name_str,
+                    <ty as rweb::multipart::FromPart>::describe(comp_d),
+                    required,
+                    description,
+                )
+            }
+        ));
+    }
+
+    let max_length_expr: Expr = match max_length {
+        Some(v) => q!(Vars { v }, { Some(v) }).parse(),
+        None => q!({ None }).parse(),
+    };
+
+    let mut body: Block = q!(Vars { arms, inits }, {
+        {
+            while let Some(part) = rweb::multipart::next_part(&mut form).await? {
+                let name = part.name().to_string();
+                match &*name {
+                    arms,
+                    _ => {}
+                }
+            }
+
+            Ok(Self { inits })
+        }
+    })
+    .parse();
+    // Variables for the fields are declared first.
+    body.stmts.splice(0..0, lets);
+
+    let from_form: ItemImpl = q!(
+        Vars {
+            Type: &input.ident,
+            max_length_expr,
+            body,
+        },
+        {
+            impl rweb::multipart::MultipartForm for Type {
+                fn max_length() -> Option<u64> {
+                    max_length_expr
+                }
+
+                fn from_form(
+                    mut form: rweb::multipart::FormData,
+                ) -> rweb::rt::BoxFuture<'static, Result<Self, rweb::Rejection>> {
+                    Box::pin(async move body)
+                }
+            }
+        }
+    )
+    .parse();
+
+    let mut items = vec![
+        from_form.with_generics(input.generics.clone()),
+        q!(Vars { Type: &input.ident }, {
+            impl rweb::FromRequest for Type {
+                type Filter = rweb::filters::BoxedFilter<(Self,)>;
+
+                fn is_body() -> bool {
+                    true
+                }
+
+                fn content_type() -> &'static str {
+                    "multipart/form-data"
+                }
+
+                fn new() -> Self::Filter {
+                    rweb::multipart::filter::<Self>()
+                }
+            }
+        })
+        .parse::<ItemImpl>()
+        .with_generics(input.generics.clone()),
+    ];
+
+    if cfg!(feature = "openapi") {
+        items.push(
+            q!(
+                Vars {
+                    Type: &input.ident,
+                    name_str: input.ident.to_string(),
+                    schemas,
+                },
+                {
+                    impl rweb::openapi::Entity for Type {
+                        fn type_name() -> rweb::rt::Cow<'static, str> {
+                            rweb::rt::Cow::Borrowed(name_str)
+                        }
+
+                        fn describe(
+                            comp_d: &mut rweb::openapi::ComponentDescriptor,
+                        ) -> rweb::openapi::ComponentOrInlineSchema {
+                            rweb::multipart::describe_form(vec![schemas])
+                        }
+                    }
+                }
+            )
+            .parse::<ItemImpl>()
+            .with_generics(input.generics.clone()),
+        );
+    }
+
+    let mut tokens = TokenStream::new();
+    for item in items {
+        item.to_tokens(&mut tokens);
+    }
+    Ok(tokens)
+}
+
+/// Returns `T` of `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(ty) if ty.qself.is_none() => &ty.path,
+        _ => return None,
+    };
+    let last = path.segments.last()?;
+    if last.ident != "Option" {
+        return None;
+    }
+    match &last.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn lit_u64(errors: &mut Errors, lit: &Lit) -> Option<u64> {
+    match lit {
+        Lit::Int(i) => match i.base10_parse::<u64>() {
+            Ok(v) => Some(v),
+            Err(err) => {
+                errors.push(err);
+                None
+            }
+        },
+        _ => {
+            errors.push(Error::new_spanned(
+                lit,
+                "#[multipart]: size should be an integer",
+            ));
+            None
+        }
+    }
+}
+
+/// Parses `#[multipart(key = value)]`, returning the value of each key in
+/// `keys`.
+fn parse_attr(attrs: &[Attribute], keys: &[&str]) -> Result<Vec<Option<Lit>>, Error> {
+    let mut values = vec![None; keys.len()];
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("multipart")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(l) => l,
+            meta => {
+                return Err(Error::new_spanned(
+                    meta,
+                    format!(
+                        "Correct usage: #[multipart({} = ...)]",
+                        keys.join(" = ..., ")
+                    ),
+                ))
+            }
+        };
+
+        for nested in &list.nested {
+            let v = match nested {
+                NestedMeta::Meta(Meta::NameValue(v)) => v,
+                _ => {
+                    return Err(Error::new_spanned(
+                        nested,
+                        format!(
+                            "Correct usage: #[multipart({} = ...)]",
+                            keys.join(" = ..., ")
+                        ),
+                    ))
+                }
+            };
+
+            match keys.iter().position(|key| v.path.is_ident(key)) {
+                Some(idx) => values[idx] = Some(v.lit.clone()),
+                None => {
+                    return Err(Error::new_spanned(
+                        &v.path,
+                        format!("Unknown config `{}` for #[multipart]", v.path.dump()),
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(values)
+}
@@ -1,6 +1,8 @@
 use crate::{
+    constraints::parse_constraints,
     openapi::case::RenameRule,
     parse::{Delimited, Paren},
+    response::ResponseAttr,
     route::EqStr,
     util::ItemImplExt,
 };
@@ -9,9 +11,9 @@ use proc_macro2::TokenStream;
 use syn::{
     parse2,
     punctuated::{Pair, Punctuated},
-    Attribute, Block, Data, DeriveInput, Expr, Field, FieldValue, Fields, GenericParam, ItemImpl,
-    Lit, Meta, MetaList, MetaNameValue, NestedMeta, Stmt, Token, TraitBound, TraitBoundModifier,
-    TypeParamBound,
+    Attribute, Block, Data, DeriveInput, Error, Expr, ExprPath, Field, FieldValue, Fields,
+    FieldsUnnamed, GenericParam, ItemImpl, Lit, Meta, MetaList, MetaNameValue, NestedMeta, Stmt,
+    Token, TraitBound, TraitBoundModifier, Type, TypeParamBound, Variant,
 };
 
 /// Extracts all `#[serde(..)]` attributes and flattens `Meta::List`s.
@@ -104,10 +106,8 @@ fn get_enum_tag_type(attrs: &[Attribute]) -> EnumTagType {
     // Don't panic on invalid serde tags since serde will report them properly itself
     for attr in get_serde_meta_attrs(attrs) {
         match attr {
-            Meta::Path(path) => {
-                if path.is_ident("untagged") {
-                    untagged = true;
-                }
+            Meta::Path(path) if path.is_ident("untagged") => {
+                untagged = true;
             }
             Meta::NameValue(MetaNameValue { path, lit, .. }) => {
                 if path.is_ident("tag") {
@@ -135,27 +135,61 @@ fn get_enum_tag_type(attrs: &[Attribute]) -> EnumTagType {
     }
 }
 
-fn get_skip_mode(attrs: &[Attribute]) -> (bool, bool) {
+pub(crate) fn get_skip_mode(attrs: &[Attribute]) -> (bool, bool) {
     let mut ser = false;
     let mut de = false;
     for attr in get_serde_meta_attrs(attrs) {
-        match attr {
-            Meta::Path(pa) => {
-                if pa.is_ident("skip") {
-                    return (true, true);
-                } else if pa.is_ident("skip_serializing") {
-                    ser = true
-                } else if pa.is_ident("skip_deserializing") {
-                    de = true
-                }
+        if let Meta::Path(pa) = attr {
+            if pa.is_ident("skip") {
+                return (true, true);
+            } else if pa.is_ident("skip_serializing") {
+                ser = true
+            } else if pa.is_ident("skip_deserializing") {
+                de = true
             }
-            _ => {}
         }
     }
     (ser, de)
 }
 
-fn field_name(type_attrs: &[Attribute], field: &Field) -> String {
+/// Search for `#[serde(default)]` and `#[serde(default = "path")]`.
+///
+/// Returns the expression used to create the default value.
+fn get_default(attrs: &[Attribute]) -> Result<Option<Expr>, Error> {
+    get_serde_meta_attrs(attrs)
+        .find_map(|attr| match attr {
+            Meta::Path(path) if path.is_ident("default") => {
+                Some(Ok(q!({ rweb::rt::Default::default() }).parse()))
+            }
+            Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(value),
+                ..
+            }) if path.is_ident("default") => Some(match value.parse::<ExprPath>() {
+                Ok(path) => Ok(q!(Vars { path }, { path() }).parse()),
+                Err(..) => Err(Error::new_spanned(
+                    &value,
+                    "#[serde(default = \"...\")]: expected a path to a function",
+                )),
+            }),
+            _ => None,
+        })
+        .transpose()
+}
+
+/// Returns true if `#[serde(name)]` exists.
+pub(crate) fn has_serde_flag(attrs: &[Attribute], name: &str) -> bool {
+    get_serde_meta_attrs(attrs).any(|attr| matches!(attr, Meta::Path(p) if p.is_ident(name)))
+}
+
+/// Search for `#[serde(skip_serializing_if = "path")]`.
+fn has_skip_serializing_if(attrs: &[Attribute]) -> bool {
+    get_serde_meta_attrs(attrs).any(|attr| {
+        matches!(attr, Meta::NameValue(MetaNameValue { path, .. }) if path.is_ident("skip_serializing_if"))
+    })
+}
+
+pub(crate) fn field_name(type_attrs: &[Attribute], field: &Field) -> String {
     if let Some(s) = get_rename(&field.attrs) {
         return s;
     }
@@ -174,7 +208,7 @@ macro_rules! invalid_schema_usage {
             "Invalid schema usage: {}
 Correct usage: #[schema(description = \"foo\", example = \"bar\")]",
             $act.dump()
-        );
+        )
     };
 }
 
@@ -211,6 +245,9 @@ fn extract_example(attrs: &Vec<Attribute>) -> Option<TokenStream> {
             {
                 match config {
                     Meta::NameValue(n) => process_nv(n),
+                    // Constraints
+                    Meta::Path(p) if p.is_ident("validate") => {}
+                    Meta::List(l) if l.path.is_ident("enum_values") => {}
                     Meta::List(l) => {
                         for el in l.nested {
                             match el {
@@ -245,7 +282,7 @@ fn extract_example(attrs: &Vec<Attribute>) -> Option<TokenStream> {
     }
 }
 
-fn extract_doc(attrs: &Vec<Attribute>) -> String {
+pub(crate) fn extract_doc(attrs: &Vec<Attribute>) -> String {
     let mut doc = None;
     let mut comments = String::new();
 
@@ -271,6 +308,9 @@ fn extract_doc(attrs: &Vec<Attribute>) -> String {
                 .inner
             {
                 match config {
+                    // Constraints
+                    Meta::Path(p) if p.is_ident("validate") => {}
+                    Meta::List(l) if l.path.is_ident("enum_values") => {}
                     Meta::List(l) => {
                         for tag in l.nested {
                             match tag {
@@ -300,10 +340,6 @@ fn extract_doc(attrs: &Vec<Attribute>) -> String {
 }
 
 fn handle_field(type_attrs: &[Attribute], f: &Field) -> Stmt {
-    let name_str = field_name(type_attrs, &*f);
-
-    let desc = extract_doc(&f.attrs);
-    let example_v = extract_example(&f.attrs);
     let (skip_ser, skip_de) = get_skip_mode(&f.attrs);
 
     // We don't require it to be `Entity`
@@ -311,12 +347,64 @@ fn handle_field(type_attrs: &[Attribute], f: &Field) -> Stmt {
         return q!({ {} }).parse();
     }
 
+    if has_serde_flag(&f.attrs, "flatten") {
+        return q!(Vars { Type: &f.ty }, {
+            {
+                let s = <Type as rweb::openapi::Entity>::describe(comp_d);
+                rweb::rt::flatten_schema(
+                    comp_d,
+                    &s,
+                    &mut fields,
+                    &mut required_fields,
+                    &mut additional_properties,
+                    &mut all_of,
+                );
+            }
+        })
+        .parse();
+    }
+
+    let name_str = field_name(type_attrs, f);
+
+    let desc = extract_doc(&f.attrs);
+    let example_v = extract_example(&f.attrs);
+    let default_expr = get_default(&f.attrs);
+    // Fields which may be absent from the serialized form.
+    let optional = !matches!(default_expr, Ok(None))
+        || !matches!(get_default(type_attrs), Ok(None))
+        || has_skip_serializing_if(&f.attrs);
+    // Errors are reported by `check_field_attrs`.
+    let constraints_block = parse_constraints(&f.attrs)
+        .unwrap_or_default()
+        .quote_schema();
+    let default_v: Expr = match default_expr {
+        Ok(Some(default_expr)) => q!(
+            Vars {
+                Type: &f.ty,
+                default_expr
+            },
+            {
+                {
+                    use rweb::rt::{ViaNoSerialize as _, ViaSerialize as _};
+                    let v: Type = default_expr;
+                    (&rweb::rt::tag(&v)).to_json(&v)
+                }
+            }
+        )
+        .parse(),
+        Ok(None) => q!({ None }).parse(),
+        Err(err) => parse2(err.to_compile_error()).unwrap(),
+    };
+
     q!(
         Vars {
             name_str,
             desc,
             Type: &f.ty,
             example_v: super::quote_option(example_v),
+            default_v,
+            constraints_block,
+            optional,
             skip_ser,
             skip_de,
         },
@@ -325,7 +413,7 @@ fn handle_field(type_attrs: &[Attribute], f: &Field) -> Stmt {
                 {
                     #[allow(unused_mut)]
                     let mut s = <Type as rweb::openapi::Entity>::describe(comp_d);
-                    if comp_d.get_unpack(&s).nullable != Some(true) {
+                    if !optional && comp_d.get_unpack(&s).nullable != Some(true) {
                         required_fields.push(rweb::rt::Cow::Borrowed(name_str));
                     }
                     if let rweb::openapi::ComponentOrInlineSchema::Inline(s) = &mut s {
@@ -337,6 +425,11 @@ fn handle_field(type_attrs: &[Attribute], f: &Field) -> Stmt {
                         if let Some(example) = example {
                             s.example = Some(example);
                         }
+                        let default = default_v;
+                        if let Some(default) = default {
+                            s.default = Some(default);
+                        }
+                        constraints_block
                         if skip_ser {
                             s.write_only = Some(true);
                         }
@@ -371,14 +464,33 @@ fn handle_fields(type_attrs: &[Attribute], fields: &Fields) -> Block {
         })
         .parse(),
     );
+    // Set by flattened maps.
+    block.stmts.push(
+        q!({
+            #[allow(unused_mut)]
+            let mut additional_properties: Option<
+                Box<rweb::openapi::ComponentOrInlineSchema>,
+            > = None;
+        })
+        .parse(),
+    );
+    // Flattened enums.
+    block.stmts.push(
+        q!({
+            #[allow(unused_mut)]
+            let mut all_of: std::vec::Vec<rweb::openapi::ComponentOrInlineSchema> =
+                std::vec::Vec::default();
+        })
+        .parse(),
+    );
 
     for f in fields {
         block.stmts.push(handle_field(type_attrs, f));
     }
 
-    block
-        .stmts
-        .push(Stmt::Expr(q!({ (fields, required_fields) }).parse()));
+    block.stmts.push(Stmt::Expr(
+        q!({ (fields, required_fields, additional_properties, all_of) }).parse(),
+    ));
 
     block
 }
@@ -411,6 +523,7 @@ fn extract_component(attrs: &[Attribute]) -> Option<String> {
                 .inner
             {
                 match config {
+                    Meta::Path(p) if p.is_ident("validate") => {}
                     Meta::List(l) => {
                         for el in l.nested {
                             match el {
@@ -438,19 +551,290 @@ fn extract_component(attrs: &[Attribute]) -> Option<String> {
     component
 }
 
+/// Describes a newtype as its inner type.
+fn newtype_schema(field: &Field, desc: &str) -> Expr {
+    // Errors are reported by `check_field_attrs`.
+    let constraints_block = parse_constraints(&field.attrs)
+        .unwrap_or_default()
+        .quote_schema();
+    q!(
+        Vars {
+            Type: &field.ty,
+            desc,
+            constraints_block
+        },
+        ({
+            #[allow(unused_mut)]
+            let mut s = <Type as rweb::openapi::Entity>::describe(comp_d);
+            if let rweb::openapi::ComponentOrInlineSchema::Inline(s) = &mut s {
+                let description = desc;
+                if !description.is_empty() {
+                    s.description = rweb::rt::Cow::Borrowed(description);
+                }
+                constraints_block
+            }
+
+            s
+        })
+    )
+    .parse()
+}
+
+/// Describes a newtype as its inner type, and other tuples as fixed-length
+/// arrays.
+///
+/// OpenAPI 3.0 has no `prefixItems`, so the positions of the fields are not
+/// documented.
+fn unnamed_schema(fields: &FieldsUnnamed, desc: &str) -> Expr {
+    if fields.unnamed.len() == 1 {
+        return newtype_schema(&fields.unnamed[0], desc);
+    }
+
+    let mut types: Vec<&Type> = vec![];
+    for f in &fields.unnamed {
+        if !types
+            .iter()
+            .any(|ty| ty.dump().to_string() == f.ty.dump().to_string())
+        {
+            types.push(&f.ty);
+        }
+    }
+    let items: Expr = if types.len() == 1 {
+        q!(Vars { Type: types[0] }, {
+            <Type as rweb::openapi::Entity>::describe(comp_d)
+        })
+        .parse()
+    } else {
+        let schemas: Punctuated<pmutil::Quote, Token![,]> = types
+            .into_iter()
+            .map(|ty| {
+                q!(Vars { Type: ty }, {
+                    <Type as rweb::openapi::Entity>::describe(comp_d)
+                })
+            })
+            .collect();
+        q!(Vars { schemas }, {
+            rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
+                one_of: vec![schemas],
+                ..rweb::rt::Default::default()
+            })
+        })
+        .parse()
+    };
+
+    q!(
+        Vars {
+            items_expr: items,
+            len: fields.unnamed.len(),
+            desc
+        },
+        ({
+            rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
+                schema_type: Some(rweb::openapi::Type::Array),
+                items: Some(Box::new(items_expr)),
+                min_items: Some(len),
+                max_items: Some(len),
+                description: rweb::rt::Cow::Borrowed(desc),
+                ..rweb::rt::Default::default()
+            })
+        })
+    )
+    .parse()
+}
+
+/// Returns the name of the variant and the schema of its content, which is
+/// [None] for unit variants.
+fn variant_schema(attrs: &[Attribute], v: &Variant) -> (String, Option<Expr>) {
+    let name = get_rename(&v.attrs)
+        .unwrap_or_else(|| get_rename_all(attrs).apply_to_variant(&v.ident.to_string()));
+    let desc = extract_doc(&v.attrs);
+    match v.fields {
+        Fields::Named(..) => (
+            name,
+            Some({
+                let fields_block = handle_fields(attrs, &v.fields);
+                q!(
+                    Vars { fields_block, desc },
+                    ({
+                        let (fields, fields_required, additional_properties, all_of) = fields_block;
+                        #[allow(unused_mut)]
+                        let mut s = rweb::openapi::Schema {
+                            schema_type: Some(rweb::openapi::Type::Object),
+                            properties: fields,
+                            required: fields_required,
+                            additional_properties,
+                            all_of,
+                            ..rweb::rt::Default::default()
+                        };
+                        let description = desc;
+                        if !description.is_empty() {
+                            s.description = rweb::rt::Cow::Borrowed(description);
+                        }
+
+                        rweb::openapi::ComponentOrInlineSchema::Inline(s)
+                    })
+                )
+                .parse()
+            }),
+        ),
+        Fields::Unnamed(ref f) if f.unnamed.is_empty() => (name, None),
+        Fields::Unnamed(ref f) => (name, Some(unnamed_schema(f, &desc))),
+        Fields::Unit => (name, None),
+    }
+}
+
+/// Describes a variant according to the representation of the enum.
+fn tagged_variant(ett: &EnumTagType, name: String, schema: Option<Expr>) -> pmutil::Quote {
+    match ett {
+        EnumTagType::External => {
+            if let Some(schema) = schema {
+                q!(Vars { name, schema }, {
+                    rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
+                        schema_type: Some(rweb::openapi::Type::Object),
+                        properties: rweb::rt::indexmap![rweb::rt::Cow::Borrowed(name) => schema],
+                        required: vec![rweb::rt::Cow::Borrowed(name)],
+                        ..Default::default()
+                    })
+                })
+            } else {
+                q!(Vars { name }, {
+                    rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
+                        schema_type: Some(rweb::openapi::Type::String),
+                        enum_values: vec![rweb::rt::Cow::Borrowed(name)],
+                        ..Default::default()
+                    })
+                })
+            }
+        }
+        EnumTagType::Internal { tag } => {
+            if let Some(schema) = schema {
+                q!(
+                    Vars {
+                        tag: &tag,
+                        name,
+                        s: schema
+                    },
+                    {
+                        rweb::openapi::ComponentOrInlineSchema::Inline(match s {
+                            rweb::openapi::ComponentOrInlineSchema::Inline(mut schema)
+                                if schema.schema_type == Some(rweb::openapi::Type::Object) =>
+                            {
+                                if schema
+                                    .properties
+                                    .insert(
+                                        rweb::rt::Cow::Borrowed(tag),
+                                        rweb::openapi::ComponentOrInlineSchema::Inline(
+                                            rweb::openapi::Schema {
+                                                schema_type: Some(rweb::openapi::Type::String),
+                                                enum_values: vec![rweb::rt::Cow::Borrowed(name)],
+                                                ..Default::default()
+                                            },
+                                        ),
+                                    )
+                                    .is_some()
+                                {
+                                    panic!("Enum internal repr tag property interferes with property of enum variant");
+                                }
+                                schema.required.push(rweb::rt::Cow::Borrowed(tag));
+                                schema
+                            }
+                            schema => rweb::openapi::Schema {
+                                all_of: vec![
+                                    rweb::openapi::ComponentOrInlineSchema::Inline(
+                                        rweb::openapi::Schema {
+                                            schema_type: Some(rweb::openapi::Type::Object),
+                                            properties: rweb::rt::indexmap![rweb::rt::Cow::Borrowed(tag) => rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
+                                                schema_type: Some(rweb::openapi::Type::String),
+                                                enum_values: vec![rweb::rt::Cow::Borrowed(name)],
+                                                ..Default::default()
+                                            })],
+                                            required: vec![rweb::rt::Cow::Borrowed(tag)],
+                                            ..Default::default()
+                                        },
+                                    ),
+                                    schema,
+                                ],
+                                ..Default::default()
+                            },
+                        })
+                    }
+                )
+            } else {
+                q!(Vars { tag: &tag, name }, {
+                    rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
+                        schema_type: Some(rweb::openapi::Type::Object),
+                        properties: rweb::rt::indexmap![rweb::rt::Cow::Borrowed(tag) => rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
+                            schema_type: Some(rweb::openapi::Type::String),
+                            enum_values: vec![rweb::rt::Cow::Borrowed(name)],
+                            ..Default::default()
+                        })],
+                        required: vec![rweb::rt::Cow::Borrowed(tag)],
+                        ..Default::default()
+                    })
+                })
+            }
+        }
+        EnumTagType::Adjacent { tag, content } => {
+            if let Some(schema) = schema {
+                q!(
+                    Vars {
+                        tag: &tag,
+                        content: &content,
+                        name,
+                        schema
+                    },
+                    {
+                        rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
+                            schema_type: Some(rweb::openapi::Type::Object),
+                            properties: rweb::rt::indexmap![rweb::rt::Cow::Borrowed(tag) => rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
+                    schema_type: Some(rweb::openapi::Type::String),
+                    enum_values: vec![rweb::rt::Cow::Borrowed(name)],
+                    ..Default::default()
+                }), rweb::rt::Cow::Borrowed(content) => schema],
+                            required: vec![
+                                rweb::rt::Cow::Borrowed(tag),
+                                rweb::rt::Cow::Borrowed(content),
+                            ],
+                            ..Default::default()
+                        })
+                    }
+                )
+            } else {
+                q!(Vars { tag: &tag, name }, {
+                    rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
+                        schema_type: Some(rweb::openapi::Type::Object),
+                        properties: rweb::rt::indexmap![rweb::rt::Cow::Borrowed(tag) => rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
+                            schema_type: Some(rweb::openapi::Type::String),
+                            enum_values: vec![rweb::rt::Cow::Borrowed(name)],
+                            ..Default::default()
+                        })],
+                        required: vec![rweb::rt::Cow::Borrowed(tag)],
+                        ..Default::default()
+                    })
+                })
+            }
+        }
+        EnumTagType::None => {
+            let schema = schema
+                .expect("Schema generation for unit variant in untagged enum is not supported");
+            q!(Vars { schema }, { schema })
+        }
+    }
+}
+
 pub fn derive_schema(input: DeriveInput) -> TokenStream {
     let DeriveInput {
-        mut attrs,
+        attrs,
         mut data,
         ident,
         generics,
         ..
     } = input;
 
-    let desc = extract_doc(&mut attrs);
+    let desc = extract_doc(&attrs);
 
     let component = extract_component(&attrs);
-    let example = extract_example(&mut attrs);
+    let example = extract_example(&attrs);
 
     let mut block: Block = q!({ {} }).parse();
     let mut final_statement: Option<Expr> = None;
@@ -462,43 +846,33 @@ pub fn derive_schema(input: DeriveInput) -> TokenStream {
     match data {
         Data::Struct(ref mut data) => {
             match data.fields {
+                _ if has_serde_flag(&attrs, "transparent") => {
+                    let field = data
+                        .fields
+                        .iter()
+                        .find(|f| get_skip_mode(&f.attrs) != (true, true));
+                    match field {
+                        Some(field) => final_statement = Some(newtype_schema(field, &desc)),
+                        None => {
+                            panic!("#[serde(transparent)] requires a field which is not skipped")
+                        }
+                    }
+                }
                 Fields::Named(_) => {
-                    let fields_block = handle_fields(&attrs, &mut data.fields);
+                    let fields_block = handle_fields(&attrs, &data.fields);
                     block.stmts.push(
                         q!(Vars { fields_block }, {
-                            let (fields, required_fields) = fields_block;
+                            let (fields, required_fields, additional_properties, all_of) =
+                                fields_block;
                         })
                         .parse(),
                     );
                     fields.push(q!({ properties: fields }).parse());
                     fields.push(q!({ required: required_fields }).parse());
+                    fields.push(q!({ additional_properties: additional_properties }).parse());
+                    fields.push(q!({ all_of: all_of }).parse());
                 }
-                Fields::Unnamed(ref n) if n.unnamed.len() == 1 => {
-                    final_statement = Some(
-                        q!(
-                            Vars {
-                                Type: &n.unnamed.first().unwrap().ty,
-                                desc: &desc
-                            },
-                            ({
-                                #[allow(unused_mut)]
-                                let mut s = <Type as rweb::openapi::Entity>::describe(comp_d);
-                                if let rweb::openapi::ComponentOrInlineSchema::Inline(s) = &mut s {
-                                    let description = desc;
-                                    if !description.is_empty() {
-                                        s.description = rweb::rt::Cow::Borrowed(description);
-                                    }
-                                }
-
-                                s
-                            })
-                        )
-                        .parse(),
-                    )
-                }
-                Fields::Unnamed(..) => {
-                    panic!("Schema generation for tuple structs is currently not supported")
-                }
+                Fields::Unnamed(ref n) => final_statement = Some(unnamed_schema(n, &desc)),
                 Fields::Unit => fields.push(q!({ nullable: Some(true) }).parse()),
             }
 
@@ -551,157 +925,15 @@ pub fn derive_schema(input: DeriveInput) -> TokenStream {
                     EnumTagType::None => panic!("Schema generation for unit-like enums with untagged representation is not supported")
                 }
             } else {
-                let variants: Vec<(String, Option<Expr>)> = data.variants.iter().filter_map(|v| {
-                    let name = get_rename(&v.attrs).unwrap_or_else(|| get_rename_all(&attrs).apply_to_variant(&v.ident.to_string()));
-                    let desc = extract_doc(&v.attrs);
-                    match v.fields {
-                        Fields::Named(..) => Some((name, Some({
-                            let fields_block = handle_fields(&attrs, &v.fields);
-                            q!(
-                                Vars { fields_block, desc },
-                                ({
-                                    let (fields, fields_required) = fields_block;
-                                    #[allow(unused_mut)]
-                                    let mut s = rweb::openapi::Schema {
-                                        schema_type: Some(rweb::openapi::Type::Object),
-                                        properties: fields,
-                                        required: fields_required,
-                                        ..rweb::rt::Default::default()
-                                    };
-                                    let description = desc;
-                                    if !description.is_empty() {
-                                        s.description =
-                                            rweb::rt::Cow::Borrowed(description);
-                                    }
-
-                                    rweb::openapi::ComponentOrInlineSchema::Inline(s)
-                                })
-                            )
-                            .parse()
-                        }))),
-                        Fields::Unnamed(ref f) if f.unnamed.is_empty() => Some((name, None)),
-                        Fields::Unnamed(ref f) if f.unnamed.len() == 1 => Some((name, Some(
-                            q!(
-                                Vars {
-                                    Type: &f.unnamed.first().unwrap().ty,
-                                    desc
-                                },
-                                ({
-                                    #[allow(unused_mut)]
-                                    let mut s =
-                                        <Type as rweb::openapi::Entity>::describe(comp_d);
-                                    if let rweb::openapi::ComponentOrInlineSchema::Inline(
-                                        s,
-                                    ) = &mut s
-                                    {
-                                        let description = desc;
-                                        if !description.is_empty() {
-                                            s.description =
-                                                rweb::rt::Cow::Borrowed(description);
-                                        }
-                                    }
-
-                                    s
-                                })
-                            )
-                            .parse()
-                        ))),
-                        Fields::Unnamed(..) => panic!("Schema generation for tuple enum variants is currently not supported"),
-                        Fields::Unit => Some((name, None)),
-                    }
-                }).collect();
-
-                match ett {
-                    EnumTagType::External => {
-                        let variants: Punctuated<pmutil::Quote, Token![,]> = variants.into_iter().map(|(name, schema)| if let Some(schema) = schema {
-                            q!(Vars { name, schema }, { rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
-                                schema_type: Some(rweb::openapi::Type::Object),
-                                properties: rweb::rt::indexmap![rweb::rt::Cow::Borrowed(name) => schema],
-                                required: vec![rweb::rt::Cow::Borrowed(name)],
-                                ..Default::default()
-                            })})
-                        } else {
-                            q!(Vars { name }, { rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
-                                schema_type: Some(rweb::openapi::Type::String),
-                                enum_values: vec![rweb::rt::Cow::Borrowed(name)],
-                                ..Default::default()
-                            })})
-                        }).collect();
-                        fields.push(q!(Vars { variants }, { one_of: vec![variants] }).parse());
-                    }
-                    EnumTagType::Internal { tag } => {
-                        let variants: Punctuated<pmutil::Quote, Token![,]> = variants.into_iter().map(|(name, schema)| if let Some(schema) = schema {
-                            q!(Vars { tag: &tag, name, s: schema }, { rweb::openapi::ComponentOrInlineSchema::Inline(match s {
-                                rweb::openapi::ComponentOrInlineSchema::Inline(mut schema) if schema.schema_type == Some(rweb::openapi::Type::Object) => {
-                                    if schema.properties.insert(rweb::rt::Cow::Borrowed(tag), rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
-                                        schema_type: Some(rweb::openapi::Type::String),
-                                        enum_values: vec![rweb::rt::Cow::Borrowed(name)],
-                                        ..Default::default()
-                                    })).is_some() {
-                                        panic!("Enum internal repr tag property interferes with property of enum variant");
-                                    }
-                                    schema.required.push(rweb::rt::Cow::Borrowed(tag));
-                                    schema
-                                }
-                                schema => rweb::openapi::Schema {
-                                    all_of: vec![rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
-                                        schema_type: Some(rweb::openapi::Type::Object),
-                                        properties: rweb::rt::indexmap![rweb::rt::Cow::Borrowed(tag) => rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
-                                            schema_type: Some(rweb::openapi::Type::String),
-                                            enum_values: vec![rweb::rt::Cow::Borrowed(name)],
-                                            ..Default::default()
-                                        })],
-                                        required: vec![rweb::rt::Cow::Borrowed(tag)],
-                                        ..Default::default()
-                                    }), schema],
-                                    ..Default::default()
-                                }
-                            })})
-                        } else {
-                            q!(Vars { tag: &tag, name }, { rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
-                                schema_type: Some(rweb::openapi::Type::Object),
-                                properties: rweb::rt::indexmap![rweb::rt::Cow::Borrowed(tag) => rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
-                                    schema_type: Some(rweb::openapi::Type::String),
-                                    enum_values: vec![rweb::rt::Cow::Borrowed(name)],
-                                    ..Default::default()
-                                })],
-                                required: vec![rweb::rt::Cow::Borrowed(tag)],
-                                ..Default::default()
-                            })})
-                        }).collect();
-                        fields.push(q!(Vars { variants }, { one_of: vec![variants] }).parse());
-                    }
-                    EnumTagType::Adjacent { tag, content } => {
-                        let variants: Punctuated<pmutil::Quote, Token![,]> = variants.into_iter().map(|(name, schema)| if let Some(schema) = schema {
-                            q!(Vars { tag: &tag, content: &content, name, schema }, { rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
-                                schema_type: Some(rweb::openapi::Type::Object),
-                                properties: rweb::rt::indexmap![rweb::rt::Cow::Borrowed(tag) => rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
-                                    schema_type: Some(rweb::openapi::Type::String),
-                                    enum_values: vec![rweb::rt::Cow::Borrowed(name)],
-                                    ..Default::default()
-                                }), rweb::rt::Cow::Borrowed(content) => schema],
-                                required: vec![rweb::rt::Cow::Borrowed(tag), rweb::rt::Cow::Borrowed(content)],
-                                ..Default::default()
-                            })})
-                        } else {
-                            q!(Vars { tag: &tag, name }, { rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
-                                schema_type: Some(rweb::openapi::Type::Object),
-                                properties: rweb::rt::indexmap![rweb::rt::Cow::Borrowed(tag) => rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
-                                    schema_type: Some(rweb::openapi::Type::String),
-                                    enum_values: vec![rweb::rt::Cow::Borrowed(name)],
-                                    ..Default::default()
-                                })],
-                                required: vec![rweb::rt::Cow::Borrowed(tag)],
-                                ..Default::default()
-                            })})
-                        }).collect();
-                        fields.push(q!(Vars { variants }, { one_of: vec![variants] }).parse());
-                    }
-                    EnumTagType::None => {
-                        let variants: Punctuated<Expr, Token![,]> = variants.into_iter().map(|(_, schema)| schema.expect("Schema generation for unit variant in untagged enum is not supported")).collect();
-                        fields.push(q!(Vars { variants }, { one_of: vec![variants] }).parse());
-                    }
-                }
+                let variants: Punctuated<pmutil::Quote, Token![,]> = data
+                    .variants
+                    .iter()
+                    .map(|v| {
+                        let (name, schema) = variant_schema(&attrs, v);
+                        tagged_variant(&ett, name, schema)
+                    })
+                    .collect();
+                fields.push(q!(Vars { variants }, { one_of: vec![variants] }).parse());
             }
         }
         Data::Union(_) => unimplemented!("#[derive(Schema)] for union"),
@@ -718,16 +950,19 @@ pub fn derive_schema(input: DeriveInput) -> TokenStream {
         .parse(),
     ));
     let block: Expr = final_statement.unwrap_or_else(|| {
-        if component.is_some() {
-            q!(Vars { block }, {
-                comp_d.describe_component(&Self::type_name(), |comp_d| block)
-            })
-        } else {
-            q!(Vars { block }, {
-                rweb::openapi::ComponentOrInlineSchema::Inline(block)
-            })
+        {
+            if component.is_some() {
+                q!(Vars { block }, {
+                    comp_d.describe_component(&Self::type_name(), |comp_d| block)
+                })
+            } else {
+                q!(Vars { block }, {
+                    rweb::openapi::ComponentOrInlineSchema::Inline(block)
+                })
+            }
         }
-    }.parse());
+        .parse()
+    });
 
     let typename = component.clone().unwrap_or_else(|| ident.to_string());
     let typename: Expr = if generics.params.is_empty() {
@@ -810,3 +1045,144 @@ pub fn derive_schema(input: DeriveInput) -> TokenStream {
 
     item.dump()
 }
+
+/// Implements `ResponseEntity` for types deriving both `Schema` and
+/// `ResponseError`, with a response for each status code and the schemas of
+/// the variants which use it.
+pub fn derive_response_entity(
+    input: &DeriveInput,
+    arms: &[(Option<&Variant>, ResponseAttr)],
+) -> ItemImpl {
+    let ett = match input.data {
+        Data::Enum(..) => Some(get_enum_tag_type(&input.attrs)),
+        _ => None,
+    };
+
+    let mut statuses: Vec<u16> = vec![];
+    for (_, attr) in arms {
+        if !statuses.contains(&attr.status) {
+            statuses.push(attr.status);
+        }
+    }
+
+    let mut block: Block = q!({ {} }).parse();
+    block.stmts.push(
+        q!({
+            let mut responses = rweb::openapi::Responses::new();
+        })
+        .parse(),
+    );
+
+    for status in statuses {
+        let mut descriptions: Vec<String> = vec![];
+        let mut schemas: Vec<pmutil::Quote> = vec![];
+        for (v, attr) in arms.iter().filter(|(_, attr)| attr.status == status) {
+            let desc = match (&attr.description, v) {
+                (Some(desc), _) => desc.clone(),
+                (None, Some(v)) => extract_doc(&v.attrs).trim().to_string(),
+                (None, None) => String::new(),
+            };
+            if !desc.is_empty() && !descriptions.contains(&desc) {
+                descriptions.push(desc);
+            }
+
+            schemas.push(match (v, &ett) {
+                (Some(v), Some(ett)) => {
+                    let (name, schema) = variant_schema(&input.attrs, v);
+                    tagged_variant(ett, name, schema)
+                }
+                _ => q!({ <Self as rweb::openapi::Entity>::describe(comp_d) }),
+            });
+        }
+
+        let schema = if schemas.len() == 1 {
+            schemas.pop().unwrap()
+        } else {
+            let schemas: Punctuated<pmutil::Quote, Token![,]> = schemas.into_iter().collect();
+            q!(Vars { schemas }, {
+                rweb::openapi::ComponentOrInlineSchema::Inline(rweb::openapi::Schema {
+                    one_of: vec![schemas],
+                    ..rweb::rt::Default::default()
+                })
+            })
+        };
+
+        let description = if descriptions.is_empty() {
+            q!(Vars { status }, {
+                rweb::rt::StatusCode::from_u16(status)
+                    .unwrap()
+                    .canonical_reason()
+                    .unwrap_or_default()
+            })
+        } else {
+            let description = descriptions.join("\n\n");
+            q!(Vars { description }, { description })
+        };
+
+        block.stmts.push(
+            q!(
+                Vars {
+                    code: status.to_string(),
+                    desc_expr: description,
+                    schema_expr: schema
+                },
+                {
+                    responses.insert(
+                        rweb::rt::Cow::Borrowed(code),
+                        rweb::openapi::Response {
+                            description: rweb::rt::Cow::Borrowed(desc_expr),
+                            content: rweb::rt::indexmap![
+                                rweb::rt::Cow::Borrowed("application/json") => rweb::openapi::MediaType {
+                                    schema: Some(schema_expr),
+                                    ..rweb::rt::Default::default()
+                                }
+                            ],
+                            ..rweb::rt::Default::default()
+                        },
+                    );
+                }
+            )
+            .parse(),
+        );
+    }
+    block.stmts.push(Stmt::Expr(q!({ responses }).parse()));
+
+    q!(
+        Vars {
+            Type: &input.ident,
+            block
+        },
+        {
+            impl rweb::openapi::ResponseEntity for Type {
+                fn describe_responses(
+                    comp_d: &mut rweb::openapi::ComponentDescriptor,
+                ) -> rweb::openapi::Responses {
+                    block
+                }
+            }
+        }
+    )
+    .parse::<ItemImpl>()
+    .with_generics(input.generics.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn should_parse_default_path() {
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[serde(default = "default_name")])];
+        assert!(matches!(get_default(&attrs), Ok(Some(..))));
+    }
+
+    #[test]
+    fn should_fail_on_invalid_default_path() {
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[serde(default = "not a path")])];
+        match get_default(&attrs) {
+            Err(err) => assert!(err.to_string().contains("expected a path")),
+            Ok(..) => panic!("invalid path should be rejected"),
+        }
+    }
+}
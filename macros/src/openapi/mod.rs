@@ -4,25 +4,29 @@
 //! # Rules
 //!
 //!  - We abuse `Parameter.ref_path` to store type name.
+//!  - We abuse `Location::FormData` to mark cookie parameters. See [COOKIE].
 
-pub use self::derive::derive_schema;
+pub use self::derive::{derive_response_entity, derive_schema};
+pub(crate) use self::derive::{extract_doc, field_name, get_skip_mode, has_serde_flag};
 use crate::{
     parse::{Delimited, Paren},
-    path::find_ty,
+    path::{find_ty, parse_segment, Segment},
     route::EqStr,
+    util::Errors,
 };
 use pmutil::{q, Quote, ToTokensExt};
 use proc_macro2::TokenStream;
 use quote::ToTokens;
 use rweb_openapi::v3_0::{
-    Location, MediaType, ObjectOrReference, Operation, Parameter, ParameterRepresentation,
-    Response, Schema,
+    Header, Link, Location, MediaType, MediaTypeExample, ObjectOrReference, Operation, Parameter,
+    ParameterExamples, ParameterRepresentation, Response, Schema, SecurityRequirement,
 };
 use std::borrow::Cow;
 use syn::{
     parse2,
     punctuated::{Pair, Punctuated},
-    Attribute, Expr, Lit, Meta, NestedMeta, Signature, Token,
+    Attribute, Error, Expr, FnArg, Lit, Meta, MetaNameValue, NestedMeta, Pat, PatType, Signature,
+    Token,
 };
 
 mod case;
@@ -38,7 +42,7 @@ macro_rules! quote_str_indexmap {
                 Default::default(),
             )
         })
-        .collect();
+        .collect()
     };
 }
 
@@ -62,6 +66,7 @@ pub fn quote_op(op: Operation) -> Expr {
 
     let responses_v: Punctuated<Quote, Token![,]> =
         quote_str_indexmap!(op.responses, quote_response);
+    let security_v = quote_security(&op.security);
 
     q!(
         Vars {
@@ -71,6 +76,7 @@ pub fn quote_op(op: Operation) -> Expr {
             description_v: op.description,
             params_v,
             responses_v,
+            security_v,
         },
         {
             rweb::openapi::Operation {
@@ -80,6 +86,7 @@ pub fn quote_op(op: Operation) -> Expr {
                 operation_id: rweb::rt::Cow::Borrowed(id_v),
                 parameters: vec![params_v],
                 responses: rweb::rt::indexmap! {responses_v},
+                security: security_v,
                 ..Default::default()
             }
         }
@@ -98,21 +105,43 @@ where
     }
 }
 
-fn quote_parameter(param: &ObjectOrReference<Parameter>) -> Expr {
+pub fn quote_parameter(param: &ObjectOrReference<Parameter>) -> Expr {
     let param = match param {
         ObjectOrReference::Ref { .. } => unreachable!("quote_parameter(ObjectOrReference::Ref)"),
         ObjectOrReference::Object(param) => param,
     };
 
     let required_v = quote_option(param.required);
+    let deprecated_v = quote_option(param.deprecated);
+    let example_v = quote_option(match &param.example {
+        Some(ParameterExamples::One {
+            example: Some(example),
+        }) => Some(q!(
+            Vars {
+                json: example.to_string()
+            },
+            {
+                rweb::openapi::ParameterExamples::One {
+                    example: Some(rweb::rt::serde_json::from_str(json).unwrap()),
+                }
+            }
+        )),
+        _ => None,
+    });
+    let mut pattern = None;
 
     let ty = match &param.representation {
         Some(ParameterRepresentation::Simple {
             schema: ObjectOrReference::Ref { ref_path },
         }) => ref_path.parse::<TokenStream>(),
         Some(ParameterRepresentation::Simple {
-            schema: ObjectOrReference::Object(Schema { ref_path, .. }),
-        }) if !ref_path.is_empty() => ref_path.parse::<TokenStream>(),
+            schema: ObjectOrReference::Object(s),
+        }) if !s.ref_path.is_empty() => {
+            if !s.pattern.is_empty() {
+                pattern = Some(&*s.pattern);
+            }
+            s.ref_path.parse::<TokenStream>()
+        }
         Some(ParameterRepresentation::Simple {
             schema: ObjectOrReference::Object(_),
         }) => panic!("Inline parameter schemas are currently not supported"),
@@ -122,20 +151,48 @@ fn quote_parameter(param: &ObjectOrReference<Parameter>) -> Expr {
         None => panic!("Schema should contain a (rust) path to the type"),
     }
     .expect("failed to lex path to the type of parameter?");
+
+    let schema_v = match pattern {
+        Some(pattern_v) => q!(
+            Vars {
+                Type: &ty,
+                pattern_v
+            },
+            {
+                {
+                    let mut s = <Type as rweb::openapi::Entity>::describe(__collector.components());
+                    if let rweb::openapi::ComponentOrInlineSchema::Inline(s) = &mut s {
+                        s.pattern = rweb::rt::Cow::Borrowed(pattern_v);
+                    }
+                    s
+                }
+            }
+        ),
+        None => q!(Vars { Type: &ty }, {
+            <Type as rweb::openapi::Entity>::describe(__collector.components())
+        }),
+    };
+
     q!(
         Vars {
-            Type: ty,
+            schema_v,
             name_v: &param.name,
             location_v: quote_location(param.location),
+            description_v: &param.description,
             required_v,
+            deprecated_v,
+            example_v,
         },
         {
             rweb::openapi::ObjectOrReference::Object(rweb::openapi::Parameter {
                 name: rweb::rt::Cow::Borrowed(name_v),
                 location: location_v,
+                description: rweb::rt::Cow::Borrowed(description_v),
                 required: required_v,
+                deprecated: deprecated_v,
+                example: example_v,
                 representation: Some(rweb::openapi::ParameterRepresentation::Simple {
-                    schema: <Type as rweb::openapi::Entity>::describe(__collector.components()),
+                    schema: schema_v,
                 }),
                 ..Default::default()
             })
@@ -145,13 +202,20 @@ fn quote_parameter(param: &ObjectOrReference<Parameter>) -> Expr {
 }
 
 fn quote_response(r: &Response) -> Expr {
+    let headers_v: Punctuated<Quote, Token![,]> = quote_str_indexmap!(r.headers, quote_header);
+    let links_v: Punctuated<Quote, Token![,]> = quote_str_indexmap!(r.links, quote_link);
+
     if let Some(irim) = r.content.get("rweb/intermediate") {
         if let Some(ObjectOrReference::Ref { ref_path }) = &irim.schema {
             let aschema_v: TokenStream = ref_path.parse().unwrap();
+            let examples_v = quote_option(irim.examples.as_ref().map(quote_examples));
             return q!(
                 Vars {
                     aschema_v,
-                    description_v: &r.description
+                    description_v: &r.description,
+                    headers_v,
+                    links_v,
+                    examples_v,
                 },
                 {
                     (|| {
@@ -164,6 +228,14 @@ fn quote_response(r: &Response) -> Expr {
                             .map(|(_, r)| r)
                             .unwrap_or_else(|| Default::default());
                         resp.description = rweb::rt::Cow::Borrowed(description_v);
+                        resp.headers.extend(rweb::rt::indexmap! {headers_v});
+                        resp.links.extend(rweb::rt::indexmap! {links_v});
+                        let examples: Option<rweb::openapi::MediaTypeExample> = examples_v;
+                        if examples.is_some() {
+                            for media in resp.content.values_mut() {
+                                media.examples = examples.clone();
+                            }
+                        }
                         resp
                     })()
                 }
@@ -171,33 +243,107 @@ fn quote_response(r: &Response) -> Expr {
             .parse();
         }
     }
-    //TODO headers, links
     let content_v: Punctuated<Quote, Token![,]> = quote_str_indexmap!(r.content, quote_mediatype);
     q!(
         Vars {
             description_v: &r.description,
-            content_v
+            headers_v,
+            content_v,
+            links_v,
         },
         {
             rweb::openapi::Response {
                 description: rweb::rt::Cow::Borrowed(description_v),
+                headers: rweb::rt::indexmap! {headers_v},
                 content: rweb::rt::indexmap! {content_v},
-                ..Default::default()
+                links: rweb::rt::indexmap! {links_v},
             }
         }
     )
     .parse()
 }
 
+/// Quotes a header, whose type is stored in `schema.ref_path`.
+fn quote_header(h: &ObjectOrReference<Header>) -> Quote {
+    let h = match h {
+        ObjectOrReference::Ref { .. } => unreachable!("quote_header(ObjectOrReference::Ref)"),
+        ObjectOrReference::Object(h) => h,
+    };
+    let ty = h
+        .schema
+        .as_ref()
+        .map(|s| &*s.ref_path)
+        .unwrap_or_default()
+        .parse::<TokenStream>()
+        .expect("failed to lex path to type");
+
+    q!(
+        Vars {
+            Type: ty,
+            description_v: &h.description,
+        },
+        {
+            rweb::openapi::ObjectOrReference::Object(rweb::openapi::Header {
+                required: Some(true),
+                schema: Some(<Type as rweb::openapi::Entity>::describe(
+                    __collector.components(),
+                )),
+                description: rweb::rt::Cow::Borrowed(description_v),
+                ..Default::default()
+            })
+        }
+    )
+}
+
+/// Links and examples are quoted as json, as they are plain values.
+fn quote_link(l: &ObjectOrReference<Link>) -> Quote {
+    let mut value = serde_json::to_value(l).unwrap();
+    link_defaults(&mut value);
+    q!(
+        Vars {
+            json: value.to_string(),
+        },
+        { rweb::rt::serde_json::from_str(json).unwrap() }
+    )
+}
+
+/// Fields of [Link] which are not serialized if empty are required to
+/// deserialize it.
+fn link_defaults(value: &mut serde_json::Value) {
+    if let Some(value) = value.as_object_mut() {
+        value.entry("description").or_insert_with(|| "".into());
+        value
+            .entry("parameters")
+            .or_insert_with(|| serde_json::Map::new().into());
+    }
+}
+
+fn quote_examples(e: &MediaTypeExample) -> Quote {
+    q!(
+        Vars {
+            json: serde_json::to_string(e).unwrap(),
+        },
+        { rweb::rt::serde_json::from_str(json).unwrap() }
+    )
+}
+
 fn quote_mediatype(m: &MediaType) -> Expr {
-    //TODO examples, encoding
+    // Encodings only apply to request bodies.
     let schema_v = quote_option(m.schema.as_ref().map(quote_schema_or_ref));
-    q!(Vars { schema_v }, {
-        rweb::openapi::MediaType {
-            schema: schema_v,
-            ..Default::default()
+    let examples_v = quote_option(m.examples.as_ref().map(quote_examples));
+    q!(
+        Vars {
+            schema_v,
+            examples_v
+        },
+        {
+            rweb::openapi::MediaType {
+                schema: schema_v,
+                examples: examples_v,
+                ..Default::default()
+            }
         }
-    })
+    )
     .parse()
 }
 
@@ -212,38 +358,117 @@ fn quote_schema_or_ref(ros: &ObjectOrReference<Schema>) -> TokenStream {
         .expect("failed to lex path to type")
 }
 
+/// `rweb-openapi` 0.6 does not support cookie parameters, and `formData` is not
+/// a location of openapi 3, so it is used for them.
+const COOKIE: Location = Location::FormData;
+
 fn quote_location(l: Location) -> Quote {
     match l {
         Location::Query => q!({ rweb::openapi::Location::Query }),
         Location::Header => q!({ rweb::openapi::Location::Header }),
         Location::Path => q!({ rweb::openapi::Location::Path }),
-        Location::FormData => q!({ rweb::openapi::Location::FormData }),
+        COOKIE => q!({ rweb::openapi::Location::Cookie }),
     }
 }
 
-pub fn parse(path: &str, sig: &Signature, attrs: &mut Vec<Attribute>) -> Operation {
+pub fn parse(path: &str, sig: &Signature, attrs: &mut Vec<Attribute>) -> Result<Operation, Error> {
     let mut op = Operation::default();
     let mut has_description = false;
+    let mut errors = Errors::default();
 
+    let mut path_params = vec![];
     for segment in path.split('/').filter(|s| !s.is_empty()) {
-        if !segment.starts_with('{') {
-            continue;
-        }
+        let (var, pattern, description) = match parse_segment(segment) {
+            Ok(Segment::Param { name, pattern }) => (name, pattern, ""),
+            Ok(Segment::Tail { name }) => (name, None, "Remaining path, which may contain `/`."),
+            // Errors are reported by `path::compile`.
+            _ => continue,
+        };
 
-        let var = &segment[1..segment.len() - 1];
         if let Some(ty) = find_ty(sig, var) {
-            op.parameters.push(ObjectOrReference::Object(Parameter {
+            path_params.push(var);
+            let mut param = Parameter {
                 name: Cow::Owned(var.to_string()),
                 location: Location::Path,
+                description: Cow::Borrowed(description),
                 required: Some(true),
                 representation: Some(ParameterRepresentation::Simple {
                     schema: ObjectOrReference::Object(Schema {
                         ref_path: Cow::Owned(ty.dump().to_string()),
+                        pattern: pattern.map(Cow::Owned).unwrap_or_default(),
                         ..Default::default()
                     }),
                 }),
                 ..Default::default()
-            }));
+            };
+            if let Some(pat) = find_input(sig, var) {
+                errors.collect(describe_param(&mut param, &pat.attrs));
+            }
+            op.parameters.push(ObjectOrReference::Object(param));
+        }
+    }
+
+    for arg in &sig.inputs {
+        let pat = match arg {
+            FnArg::Typed(pat) => pat,
+            _ => continue,
+        };
+        if matches!(&*pat.pat, Pat::Ident(i) if path_params.iter().any(|name| i.ident == name)) {
+            continue;
+        }
+
+        if pat.attrs.iter().any(|attr| attr.path.is_ident("query")) {
+            // The raw query string is documented as a free-form object.
+            let name = match &*pat.pat {
+                Pat::Ident(i) => i.ident.to_string(),
+                _ => String::from("query"),
+            };
+            let mut param = Parameter {
+                name: Cow::Owned(name),
+                location: Location::Query,
+                description: Cow::Borrowed("Query string."),
+                required: Some(true),
+                representation: Some(ParameterRepresentation::Simple {
+                    schema: ObjectOrReference::Object(Schema {
+                        ref_path: Cow::Borrowed(
+                            "std::collections::HashMap<std::string::String, std::string::String>",
+                        ),
+                        ..Default::default()
+                    }),
+                }),
+                ..Default::default()
+            };
+            errors.collect(describe_param(&mut param, &pat.attrs));
+            op.parameters.push(ObjectOrReference::Object(param));
+        } else if let Some(attr) = pat
+            .attrs
+            .iter()
+            .find(|attr| attr.path.is_ident("header") || attr.path.is_ident("cookie"))
+        {
+            // Errors are reported by `param::compile`.
+            let name = match parse2::<EqStr>(attr.tokens.clone()) {
+                Ok(name) => name.value.value(),
+                Err(..) => continue,
+            };
+            let location = if attr.path.is_ident("cookie") {
+                COOKIE
+            } else {
+                Location::Header
+            };
+            let mut param = Parameter {
+                name: Cow::Owned(name),
+                location,
+                required: Some(true),
+                representation: Some(ParameterRepresentation::Simple {
+                    schema: ObjectOrReference::Object(Schema {
+                        ref_path: Cow::Owned(pat.ty.dump().to_string()),
+                        ..Default::default()
+                    }),
+                }),
+                ..Default::default()
+            };
+            errors.collect(describe_param(&mut param, &pat.attrs));
+            op.parameters.push(ObjectOrReference::Object(param));
         }
     }
 
@@ -251,23 +476,33 @@ pub fn parse(path: &str, sig: &Signature, attrs: &mut Vec<Attribute>) -> Operati
         if attr.path.is_ident("openapi") {
             // tags("foo", "bar", "baz)
 
-            let configs = parse2::<Paren<Delimited<Meta>>>(attr.tokens.clone())
-                .expect("openapi config is invalid")
-                .inner
-                .inner;
+            let configs = match parse2::<Paren<Delimited<Meta>>>(attr.tokens.clone()) {
+                Ok(configs) => configs.inner.inner,
+                Err(..) => {
+                    errors.push(Error::new_spanned(attr, "openapi config is invalid"));
+                    return false;
+                }
+            };
 
             for config in configs {
                 if config.path().is_ident("id") {
-                    assert!(
-                        op.operation_id.is_empty(),
-                        "#[openapi]: Duplicate operation id detected"
-                    );
+                    if !op.operation_id.is_empty() {
+                        errors.push(Error::new_spanned(
+                            &config,
+                            "#[openapi]: Duplicate operation id detected",
+                        ));
+                        continue;
+                    }
                     match config {
                         Meta::NameValue(v) => match v.lit {
                             Lit::Str(s) => op.operation_id = Cow::Owned(s.value()),
-                            _ => panic!("#[openapi]: invalid operation id"),
+                            l => errors
+                                .push(Error::new_spanned(l, "#[openapi]: invalid operation id")),
                         },
-                        _ => panic!("Correct usage: #[openapi(id = \"foo\")]"),
+                        _ => errors.push(Error::new_spanned(
+                            config,
+                            "Correct usage: #[openapi(id = \"foo\")]",
+                        )),
                     }
                 } else if config.path().is_ident("description") {
                     match config {
@@ -276,112 +511,67 @@ pub fn parse(path: &str, sig: &Signature, attrs: &mut Vec<Attribute>) -> Operati
                                 op.description = Cow::Owned(s.value());
                                 has_description = true;
                             }
-                            _ => panic!("#[openapi]: invalid operation summary"),
+                            l => errors.push(Error::new_spanned(
+                                l,
+                                "#[openapi]: invalid operation description",
+                            )),
                         },
-                        _ => panic!("Correct usage: #[openapi(summary = \"foo\")]"),
+                        _ => errors.push(Error::new_spanned(
+                            config,
+                            "Correct usage: #[openapi(description = \"foo\")]",
+                        )),
                     }
                 } else if config.path().is_ident("summary") {
                     match config {
                         Meta::NameValue(v) => match v.lit {
                             Lit::Str(s) => op.summary = Cow::Owned(s.value()),
-                            _ => panic!("#[openapi]: invalid operation summary"),
+                            l => errors.push(Error::new_spanned(
+                                l,
+                                "#[openapi]: invalid operation summary",
+                            )),
                         },
-                        _ => panic!("Correct usage: #[openapi(summary = \"foo\")]"),
+                        _ => errors.push(Error::new_spanned(
+                            config,
+                            "Correct usage: #[openapi(summary = \"foo\")]",
+                        )),
                     }
                 } else if config.path().is_ident("tags") {
                     match config {
                         Meta::List(l) => {
                             for tag in l.nested {
                                 match tag {
-                                    NestedMeta::Lit(v) => match v {
-                                        Lit::Str(s) => op.tags.push(Cow::Owned(s.value())),
-                                        _ => panic!("#[openapi]: tag should be a string literal"),
-                                    },
-                                    _ => panic!("Correct usage: #[openapi(tags(\"foo\" ,\"bar\")]"),
+                                    NestedMeta::Lit(Lit::Str(s)) => {
+                                        op.tags.push(Cow::Owned(s.value()))
+                                    }
+                                    NestedMeta::Lit(v) => errors.push(Error::new_spanned(
+                                        v,
+                                        "#[openapi]: tag should be a string literal",
+                                    )),
+                                    _ => errors.push(Error::new_spanned(
+                                        tag,
+                                        "Correct usage: #[openapi(tags(\"foo\" ,\"bar\")]",
+                                    )),
                                 }
                             }
                         }
-                        _ => panic!("Correct usage: #[openapi(tags(\"foo\" ,\"bar\")]"),
+                        _ => errors.push(Error::new_spanned(
+                            config,
+                            "Correct usage: #[openapi(tags(\"foo\" ,\"bar\")]",
+                        )),
                     }
-                } else if config.path().is_ident("response") {
-                    macro_rules! invalid_usage {
-                        () => {
-                            panic!(
-                                "Correct usage: #[openapi(response(code = \"409\", description = \
-         \"foo already exists\")]"
-                            )
-                        };
+                } else if config.path().is_ident("security") {
+                    if let Err(err) = parse_security(&mut op, config) {
+                        errors.push(err);
                     }
-                    let mut code: Option<String> = None;
-                    let mut description: Option<String> = None;
-                    let mut schema: Option<String> = None;
-                    match config {
-                        Meta::List(l) => {
-                            for tag in l.nested {
-                                match tag {
-                                    NestedMeta::Meta(Meta::NameValue(v)) => match v.lit {
-                                        Lit::Str(s) => {
-                                            if v.path.is_ident("code") {
-                                                code = Some(s.value())
-                                            } else if v.path.is_ident("description") {
-                                                description = Some(s.value())
-                                            } else if v.path.is_ident("schema") {
-                                                schema = Some(s.value())
-                                            } else {
-                                                invalid_usage!()
-                                            }
-                                        }
-                                        Lit::Int(i) => {
-                                            if i.base10_parse::<u16>().is_ok() {
-                                                code = Some(i.to_string())
-                                            } else {
-                                                invalid_usage!()
-                                            }
-                                        }
-                                        _ => invalid_usage!(),
-                                    },
-                                    _ => invalid_usage!(),
-                                }
-                            }
-                            match (code, description) {
-                                (Some(c), Some(d)) => {
-                                    match op.responses.get_mut(&Cow::Owned(c.clone())) {
-                                        Some(resp) => {
-                                            resp.description = Cow::Owned(d);
-                                        }
-                                        None => {
-                                            op.responses.insert(
-                                                Cow::Owned(c.clone()),
-                                                Response {
-                                                    description: Cow::Owned(d),
-                                                    ..Default::default()
-                                                },
-                                            );
-                                        }
-                                    };
-                                    if let Some(s) = schema {
-                                        op.responses
-                                            .get_mut(&Cow::Owned(c.clone()))
-                                            .unwrap()
-                                            .content
-                                            .insert(
-                                                Cow::Borrowed("rweb/intermediate"),
-                                                MediaType {
-                                                    schema: Some(ObjectOrReference::Ref {
-                                                        ref_path: Cow::Owned(s),
-                                                    }),
-                                                    ..Default::default()
-                                                },
-                                            );
-                                    }
-                                }
-                                _ => invalid_usage!(),
-                            }
-                        }
-                        _ => invalid_usage!(),
+                } else if config.path().is_ident("response") {
+                    if let Err(err) = parse_response(&mut op, config) {
+                        errors.push(err);
                     }
                 } else {
-                    panic!("Unknown openapi config `{}`", config.dump())
+                    errors.push(Error::new_spanned(
+                        config.path(),
+                        format!("Unknown openapi config `{}`", config.path().dump()),
+                    ));
                 }
             }
 
@@ -389,13 +579,17 @@ pub fn parse(path: &str, sig: &Signature, attrs: &mut Vec<Attribute>) -> Operati
         }
 
         if attr.path.is_ident("doc") && !has_description {
-            let s: EqStr = parse2(attr.tokens.clone()).expect("failed to parse comments");
+            let s: EqStr = match parse2(attr.tokens.clone()) {
+                Ok(s) => s,
+                // Not a `/// comment`, e.g. `#[doc(hidden)]`.
+                Err(..) => return true,
+            };
             if !op.description.is_empty() {
                 op.description.to_mut().push(' ');
             }
             op.description
                 .to_mut()
-                .push_str(&s.value.value().trim_start());
+                .push_str(s.value.value().trim_start());
             // Preserve comments
             return true;
         }
@@ -403,5 +597,364 @@ pub fn parse(path: &str, sig: &Signature, attrs: &mut Vec<Attribute>) -> Operati
         true
     });
 
-    op
+    errors.finish()?;
+
+    Ok(op)
+}
+
+/// Finds the parameter named `name`.
+fn find_input<'a>(sig: &'a Signature, name: &str) -> Option<&'a PatType> {
+    sig.inputs.iter().find_map(|arg| match arg {
+        FnArg::Typed(pat) => match &*pat.pat {
+            Pat::Ident(i) if i.ident == name => Some(pat),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// Describes a parameter using its doc comments and
+/// `#[param(description = "foo", example = 1, deprecated)]`.
+fn describe_param(param: &mut Parameter, attrs: &[Attribute]) -> Result<(), Error> {
+    let doc = attrs
+        .iter()
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(s),
+                ..
+            })) if path.is_ident("doc") => Some(s.value().trim().to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if !doc.is_empty() {
+        param.description = Cow::Owned(doc);
+    }
+
+    let attr = match attrs.iter().find(|attr| attr.path.is_ident("param")) {
+        Some(attr) => attr,
+        None => return Ok(()),
+    };
+    let invalid_usage = |span: &dyn ToTokens| {
+        Error::new_spanned(
+            span,
+            "Correct usage: #[param(description = \"foo\", example = \"bar\", deprecated)]",
+        )
+    };
+
+    let configs = match attr.parse_meta()? {
+        Meta::List(l) => l.nested,
+        meta => return Err(invalid_usage(&meta)),
+    };
+    for config in configs {
+        match config {
+            NestedMeta::Meta(Meta::Path(p)) if p.is_ident("deprecated") => {
+                param.deprecated = Some(true);
+            }
+            NestedMeta::Meta(Meta::NameValue(v)) if v.path.is_ident("description") => match v.lit {
+                Lit::Str(s) => param.description = Cow::Owned(s.value()),
+                lit => return Err(invalid_usage(&lit)),
+            },
+            NestedMeta::Meta(Meta::NameValue(v)) if v.path.is_ident("example") => {
+                let example = match &v.lit {
+                    Lit::Str(s) => serde_json::Value::from(s.value()),
+                    Lit::Int(i) => serde_json::Value::from(i.base10_parse::<i64>()?),
+                    Lit::Float(f) => serde_json::Value::from(f.base10_parse::<f64>()?),
+                    Lit::Bool(b) => serde_json::Value::from(b.value),
+                    lit => return Err(invalid_usage(lit)),
+                };
+                param.example = Some(ParameterExamples::One {
+                    example: Some(example),
+                });
+            }
+            config => return Err(invalid_usage(&config)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Handles `#[openapi(security("oauth", scopes("read"), "api_key"))]`.
+///
+/// Each `security(...)` adds a requirement, where all listed schemes are
+/// required. `scopes(...)` applies to the preceding scheme.
+fn parse_security(op: &mut Operation, config: Meta) -> Result<(), Error> {
+    let invalid_usage = |span: &dyn ToTokens| {
+        Error::new_spanned(
+            span,
+            "Correct usage: #[openapi(security(\"scheme\", scopes(\"read\", \"write\")))]",
+        )
+    };
+
+    let l = match config {
+        Meta::List(l) => l,
+        _ => return Err(invalid_usage(&config)),
+    };
+
+    let mut requirement = SecurityRequirement::new();
+    let mut last: Option<Cow<'static, str>> = None;
+    for item in l.nested {
+        match item {
+            NestedMeta::Lit(Lit::Str(s)) => {
+                let name: Cow<'static, str> = Cow::Owned(s.value());
+                requirement.insert(name.clone(), vec![]);
+                last = Some(name);
+            }
+            NestedMeta::Meta(Meta::List(scopes)) if scopes.path.is_ident("scopes") => {
+                let name = match &last {
+                    Some(name) => name,
+                    None => {
+                        return Err(Error::new_spanned(
+                            scopes,
+                            "#[openapi(security)]: scopes should follow a scheme",
+                        ))
+                    }
+                };
+                for scope in scopes.nested {
+                    match scope {
+                        NestedMeta::Lit(Lit::Str(s)) => requirement
+                            .get_mut(name)
+                            .unwrap()
+                            .push(Cow::Owned(s.value())),
+                        scope => return Err(invalid_usage(&scope)),
+                    }
+                }
+            }
+            item => return Err(invalid_usage(&item)),
+        }
+    }
+
+    op.security.push(requirement);
+    Ok(())
+}
+
+/// Quotes security requirements as `vec![indexmap!{ ... }]`.
+pub fn quote_security(security: &[SecurityRequirement]) -> Quote {
+    let requirements: Punctuated<Quote, Token![,]> = security
+        .iter()
+        .map(|requirement| {
+            let schemes: Punctuated<Quote, Token![,]> = requirement
+                .iter()
+                .map(|(name, scopes)| {
+                    let scopes: Punctuated<Quote, Token![,]> = scopes
+                        .iter()
+                        .map(|scope| q!(Vars { scope }, { rweb::rt::Cow::Borrowed(scope) }))
+                        .collect();
+                    q!(Vars { name, scopes }, {
+                        rweb::rt::Cow::Borrowed(name) => vec![scopes]
+                    })
+                })
+                .collect();
+            q!(Vars { schemes }, {
+                rweb::rt::indexmap! {schemes}
+            })
+        })
+        .collect();
+
+    q!(Vars { requirements }, { vec![requirements] })
+}
+
+/// Handles `#[openapi(response(code = "409", description = "foo"))]`, with
+/// optional `schema = "Type"`, `headers(name = "Type")`, `example = "json"`
+/// and `links(name(operation_id = "id", parameters(name = "$expr")))`.
+fn parse_response(op: &mut Operation, config: Meta) -> Result<(), Error> {
+    let invalid_usage = |span: &dyn ToTokens| {
+        Error::new_spanned(
+            span,
+            "Correct usage: #[openapi(response(code = \"409\", description = \"foo already \
+             exists\", headers(location = \"String\"), example = \"{}\"))]",
+        )
+    };
+
+    let mut code: Option<String> = None;
+    let mut description: Option<String> = None;
+    let mut schema: Option<String> = None;
+    let mut example: Option<serde_json::Value> = None;
+    let mut headers = vec![];
+    let mut links = vec![];
+    let l = match config {
+        Meta::List(l) => l,
+        _ => return Err(invalid_usage(&config)),
+    };
+
+    for tag in &l.nested {
+        match tag {
+            NestedMeta::Meta(Meta::NameValue(v)) if v.path.is_ident("example") => {
+                example = Some(match &v.lit {
+                    // Json is used as is, and other strings are examples of text.
+                    Lit::Str(s) => serde_json::from_str(&s.value())
+                        .unwrap_or_else(|_| serde_json::Value::from(s.value())),
+                    Lit::Int(i) => serde_json::Value::from(i.base10_parse::<i64>()?),
+                    Lit::Float(f) => serde_json::Value::from(f.base10_parse::<f64>()?),
+                    Lit::Bool(b) => serde_json::Value::from(b.value),
+                    lit => return Err(invalid_usage(lit)),
+                });
+            }
+            NestedMeta::Meta(Meta::NameValue(v)) => match &v.lit {
+                Lit::Str(s) => {
+                    if v.path.is_ident("code") {
+                        code = Some(s.value())
+                    } else if v.path.is_ident("description") {
+                        description = Some(s.value())
+                    } else if v.path.is_ident("schema") {
+                        schema = Some(s.value())
+                    } else {
+                        return Err(invalid_usage(&v.path));
+                    }
+                }
+                Lit::Int(i) if v.path.is_ident("code") => {
+                    if i.base10_parse::<u16>().is_ok() {
+                        code = Some(i.to_string())
+                    } else {
+                        return Err(invalid_usage(i));
+                    }
+                }
+                lit => return Err(invalid_usage(lit)),
+            },
+            NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("headers") => {
+                for header in &list.nested {
+                    match header {
+                        NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                            path,
+                            lit: Lit::Str(ty),
+                            ..
+                        })) if path.get_ident().is_some() => {
+                            // Header names contain `-`, which is not allowed in identifiers.
+                            let name = path.get_ident().unwrap().to_string().replace('_', "-");
+                            headers.push((name, ty.value()));
+                        }
+                        _ => return Err(invalid_usage(header)),
+                    }
+                }
+            }
+            NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("links") => {
+                for link in &list.nested {
+                    links.push(parse_link(link)?);
+                }
+            }
+            _ => return Err(invalid_usage(tag)),
+        }
+    }
+
+    let (c, d) = match (code, description) {
+        (Some(c), Some(d)) => (c, d),
+        _ => return Err(invalid_usage(&l)),
+    };
+
+    let resp = op.responses.entry(Cow::Owned(c)).or_default();
+    resp.description = Cow::Owned(d);
+
+    for (name, ty) in headers {
+        resp.headers.insert(
+            Cow::Owned(name),
+            ObjectOrReference::Object(Header {
+                schema: Some(Schema {
+                    ref_path: Cow::Owned(ty),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+        );
+    }
+    for (name, link) in links {
+        resp.links.insert(Cow::Owned(name), link);
+    }
+
+    let examples = example.map(|example| MediaTypeExample::Example { example });
+    if let Some(s) = schema {
+        resp.content.insert(
+            Cow::Borrowed("rweb/intermediate"),
+            MediaType {
+                schema: Some(ObjectOrReference::Ref {
+                    ref_path: Cow::Owned(s),
+                }),
+                examples,
+                ..Default::default()
+            },
+        );
+    } else if examples.is_some() {
+        // Applied to the media types of the response type, if any.
+        resp.content.insert(
+            Cow::Borrowed("*/*"),
+            MediaType {
+                examples,
+                ..Default::default()
+            },
+        );
+    }
+
+    Ok(())
+}
+
+/// Handles `get_user(operation_id = "users.get", parameters(id = "$response.body#/id"))`
+/// in `links(...)`, with optional `description = "foo"`.
+fn parse_link(link: &NestedMeta) -> Result<(String, ObjectOrReference<Link>), Error> {
+    let invalid_usage = |span: &dyn ToTokens| {
+        Error::new_spanned(
+            span,
+            "Correct usage: links(get_user(operation_id = \"users.get\", parameters(id = \
+             \"$response.body#/id\")))",
+        )
+    };
+
+    let list = match link {
+        NestedMeta::Meta(Meta::List(list)) if list.path.get_ident().is_some() => list,
+        _ => return Err(invalid_usage(link)),
+    };
+
+    let mut value = serde_json::Map::new();
+    for config in &list.nested {
+        match config {
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(s),
+                ..
+            })) => {
+                let key = if path.is_ident("operation_id") {
+                    "operationId"
+                } else if path.is_ident("operation_ref") {
+                    "operationRef"
+                } else if path.is_ident("description") {
+                    "description"
+                } else {
+                    return Err(invalid_usage(path));
+                };
+                value.insert(key.into(), s.value().into());
+            }
+            NestedMeta::Meta(Meta::List(params)) if params.path.is_ident("parameters") => {
+                let mut parameters = serde_json::Map::new();
+                for param in &params.nested {
+                    match param {
+                        NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                            path,
+                            lit: Lit::Str(s),
+                            ..
+                        })) if path.get_ident().is_some() => {
+                            parameters
+                                .insert(path.get_ident().unwrap().to_string(), s.value().into());
+                        }
+                        _ => return Err(invalid_usage(param)),
+                    }
+                }
+                value.insert("parameters".into(), parameters.into());
+            }
+            _ => return Err(invalid_usage(config)),
+        }
+    }
+
+    if value.contains_key("operationId") == value.contains_key("operationRef") {
+        return Err(Error::new_spanned(
+            list,
+            "A link should have one of `operation_id` and `operation_ref`",
+        ));
+    }
+
+    let mut value = value.into();
+    link_defaults(&mut value);
+    let link = serde_json::from_value(value).map_err(|err| Error::new_spanned(list, err))?;
+    Ok((
+        list.path.get_ident().unwrap().to_string(),
+        ObjectOrReference::Object(link),
+    ))
 }
@@ -2,29 +2,9 @@ use syn::{
     parenthesized,
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
-    LitStr, Token,
+    Token,
 };
 
-pub(crate) struct KeyValue<K = LitStr, V = LitStr> {
-    pub key: K,
-    _eq: Token![=],
-    pub value: V,
-}
-
-impl<K, V> Parse for KeyValue<K, V>
-where
-    K: Parse,
-    V: Parse,
-{
-    fn parse(input: ParseStream) -> syn::parse::Result<Self> {
-        Ok(KeyValue {
-            key: input.parse()?,
-            _eq: input.parse()?,
-            value: input.parse()?,
-        })
-    }
-}
-
 /// A node wrapped with paren.
 pub(crate) struct Paren<T> {
     pub inner: T,
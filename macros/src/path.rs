@@ -1,9 +1,200 @@
-use pmutil::q;
-use proc_macro2::TokenStream;
+use crate::util::Errors;
+use pmutil::{q, Quote};
+use proc_macro2::{Literal, Span, TokenStream};
 use syn::{
-    parse_quote::parse, punctuated::Punctuated, Expr, FnArg, LitStr, Pat, Signature, Token, Type,
+    parse2, punctuated::Punctuated, Attribute, Error, Expr, FnArg, LitStr, Pat, PatType, Signature,
+    Token, Type,
 };
 
+/// A segment of a route path, like `users` or `{id:[0-9]+}`.
+pub enum Segment<'a> {
+    Static(&'a str),
+    Param {
+        name: &'a str,
+        /// Regular expression which matches the whole segment.
+        pattern: Option<String>,
+    },
+    /// A catch-all parameter like `{*rest}`, which captures the remaining path.
+    Tail {
+        name: &'a str,
+    },
+}
+
+/// Parses a segment of a path.
+///
+/// A path parameter may have a constraint like `{id:[0-9]+}` or `{id:int}`,
+/// and the last one may capture the remaining path like `{*rest}`.
+pub fn parse_segment(segment: &str) -> Result<Segment<'_>, String> {
+    if !segment.starts_with('{') {
+        return Ok(Segment::Static(segment));
+    }
+    if !segment.ends_with('}') {
+        return Err(format!(
+            "path segment `{}` is missing a closing `}}`",
+            segment
+        ));
+    }
+
+    // Example if {word} we only want to extract `word` here
+    let inner = &segment[1..segment.len() - 1];
+    if let Some(name) = inner.strip_prefix('*') {
+        if name.contains(':') {
+            return Err(format!(
+                "catch-all path parameter `{}` cannot have a constraint",
+                segment
+            ));
+        }
+        return Ok(Segment::Tail { name });
+    }
+
+    let (name, constraint) = match inner.find(':') {
+        Some(idx) => (&inner[..idx], Some(&inner[idx + 1..])),
+        None => (inner, None),
+    };
+
+    let pattern = match constraint {
+        Some(c) => {
+            let pattern = format!("^(?:{})$", named_constraint(c).unwrap_or(c));
+            if let Err(err) = regex::Regex::new(&pattern) {
+                return Err(format!(
+                    "invalid constraint for path parameter `{}`: {}",
+                    name, err
+                ));
+            }
+            Some(pattern)
+        }
+        None => None,
+    };
+
+    Ok(Segment::Param { name, pattern })
+}
+
+/// Regular expressions for constraints like `{id:int}`.
+fn named_constraint(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "int" => "-?[0-9]+",
+        "uint" => "[0-9]+",
+        "alpha" => "[a-zA-Z]+",
+        "alnum" => "[a-zA-Z0-9]+",
+        "uuid" => "[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}",
+        _ => return None,
+    })
+}
+
+/// Splits `path` on `/`, except in braces, so `/{id:[^/]+}` has a single
+/// parameter. Like [str::split], empty segments are kept.
+pub fn split_path(path: &str) -> Vec<&str> {
+    let mut segments = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (idx, c) in path.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            '/' if depth == 0 => {
+                segments.push(&path[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    segments.push(&path[start..]);
+    segments
+}
+
+/// Removes constraints from path parameters, so `/users/{id:int}` becomes
+/// `/users/{id}` and `/files/{*path}` becomes `/files/{path}`.
+pub fn strip_constraints(path: &str) -> String {
+    split_path(path)
+        .into_iter()
+        .map(|segment| match parse_segment(segment) {
+            Ok(Segment::Param { name, .. }) | Ok(Segment::Tail { name }) => {
+                format!("{{{}}}", name)
+            }
+            _ => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Returns true for `#[router_param]`, which marks a path parameter declared
+/// by an enclosing router.
+pub fn is_router_param(attr: &Attribute) -> bool {
+    attr.path.is_ident("router_param")
+}
+
+/// Arguments of `sig` marked with `#[router_param]`.
+pub fn router_params(sig: &Signature) -> Vec<&PatType> {
+    sig.inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat) if pat.attrs.iter().any(is_router_param) => Some(pat),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Name of a path parameter spelled at type level. See `rweb::rt::Ch`.
+pub fn name_type(name: &str) -> Type {
+    let chars: Punctuated<Quote, Token![,]> = name
+        .chars()
+        .map(|c| {
+            let c = Literal::character(c);
+            q!(Vars { c }, { rweb::rt::Ch<c> })
+        })
+        .collect();
+    q!(Vars { chars }, { (chars,) }).parse()
+}
+
+/// Type of the list of `#[router_param]` arguments, which is checked by the
+/// enclosing router. See `rweb::rt::UsesRouterParam`.
+///
+/// Arguments which are not a simple identifier are skipped, as they are
+/// reported by the caller.
+pub fn uses_router_params(params: &[&PatType]) -> Type {
+    let mut list = q!({ () });
+    for pat in params.iter().rev() {
+        let name = match &*pat.pat {
+            Pat::Ident(i) => i.ident.to_string(),
+            _ => continue,
+        };
+        list = q!(
+            Vars {
+                Name: name_type(&name),
+                ty: &pat.ty,
+                list,
+            },
+            { (rweb::rt::UsesRouterParam<Name, ty>, list) }
+        );
+    }
+    list.parse()
+}
+
+/// Segments of `path`, with named constraints expanded, as a
+/// `&[rweb::rt::RouteSegment]`. Used to match requests at runtime.
+pub fn route_segments(path: &str) -> Expr {
+    let segments: Punctuated<Quote, Token![,]> = split_path(path)
+        .into_iter()
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| match parse_segment(segment) {
+            Ok(Segment::Param {
+                pattern: Some(pattern),
+                ..
+            }) => q!(Vars { pattern }, {
+                rweb::rt::RouteSegment::Param(Some(pattern))
+            }),
+            Ok(Segment::Param { pattern: None, .. }) => {
+                q!({ rweb::rt::RouteSegment::Param(None) })
+            }
+            Ok(Segment::Tail { .. }) => q!({ rweb::rt::RouteSegment::Tail }),
+            _ => q!(Vars { segment }, {
+                rweb::rt::RouteSegment::Static(segment)
+            }),
+        })
+        .collect();
+    q!(Vars { segments }, { &[segments] }).parse()
+}
+
 pub fn find_ty<'a>(sig: &'a Signature, name: &str) -> Option<&'a Type> {
     sig.inputs
         .iter()
@@ -25,66 +216,110 @@ pub fn compile(
     path: TokenStream,
     sig: Option<&Signature>,
     end: bool,
-) -> (Expr, Vec<(String, usize)>) {
-    let path: LitStr = parse(path);
-    let path = path.value();
-    assert!(path.starts_with('/'), "Path should start with /");
-    assert!(
-        !path.contains("//"),
-        "A path containing `//` doesn't make sense"
-    );
+) -> Result<(Expr, Vec<(String, usize)>), Error> {
+    let lit: LitStr = parse2(path)?;
+    let path = lit.value();
+    if !path.starts_with('/') {
+        return Err(Error::new(lit.span(), "Path should start with /"));
+    }
+    if path.contains("//") {
+        return Err(Error::new(
+            lit.span(),
+            "A path containing `//` doesn't make sense",
+        ));
+    }
 
+    let mut errors = Errors::default();
     let mut exprs: Punctuated<Expr, Token![.]> = Default::default();
     // Set base values
     exprs.extend(base);
     let mut vars = vec![];
 
+    // Path parameters of enclosing routers come first.
+    let mut router_param_idx = 0usize;
+    for (idx, arg) in sig.iter().flat_map(|sig| sig.inputs.iter()).enumerate() {
+        let pat = match arg {
+            FnArg::Typed(pat) if pat.attrs.iter().any(is_router_param) => pat,
+            _ => continue,
+        };
+        let name = match &*pat.pat {
+            Pat::Ident(i) => i.ident.to_string(),
+            _ => {
+                errors.push(Error::new_spanned(
+                    &pat.pat,
+                    "#[router_param] can only be used with a simple identifier pattern",
+                ));
+                continue;
+            }
+        };
+
+        // Offsets are computed by the enclosing router.
+        let expr = q!(
+            Vars {
+                ty: &pat.ty,
+                router_param_idx,
+            },
+            { rweb::rt::router_param::<ty>(__router_param_offsets[router_param_idx]) }
+        );
+        router_param_idx += 1;
+        if exprs.is_empty() {
+            exprs.push(q!(Vars { expr }, { expr }).parse());
+        } else {
+            exprs.push(q!(Vars { expr }, { and(expr) }).parse());
+        }
+        vars.push((name, idx));
+    }
+
     // Filter empty segments before iterating over them.
     // Mainly it will come from the required path in the beginning / but could also
     // come from the end / Example: #[get("/{word}")] or #[get("/{word}/")] with
     // the `/` before and after `{word}`
-    let segments: Vec<&str> = path
-        .split('/')
+    let segments: Vec<&str> = split_path(&path)
         .into_iter()
         .filter(|x| !x.is_empty())
         .collect();
-    for segment in segments {
-        let expr = if segment.starts_with('{') {
-            // Example if {word} we only want to extract `word` here
-            let name = &segment[1..segment.len() - 1];
-            if let Some(sig) = sig {
-                let ty = sig
-                    .inputs
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(idx, arg)| match arg {
-                        FnArg::Typed(ty) => match *ty.pat {
-                            // Here if we find a Pat::Ident we get i: &PatIdent and i.ident is the
-                            // parameter in the route fn.
-                            // I.e dyn_reply(word: String), this would be named: `word` and we
-                            // compare it to the segment name mentioned above.
-                            // If they match:
-                            //      We uses it and adds to our variables.
-                            // else
-                            //      We will panic later.
-                            Pat::Ident(ref i) if i.ident == name => {
-                                vars.push((name.to_string(), idx));
-                                Some(&ty.ty)
-                            }
-                            _ => None,
-                        },
-
-                        _ => None,
-                    })
-                    .next()
-                    .unwrap_or_else(|| panic!("failed to find parameter named `{}`", name));
-
-                q!(Vars { ty }, { rweb::filters::path::param::<ty>() })
-            } else {
-                panic!("path parameters are not allowed here (currently)")
+    let last = segments.len().saturating_sub(1);
+    for (idx, segment) in segments.iter().copied().enumerate() {
+        let span = segment_span(&lit, &path, segment);
+        let expr = match parse_segment(segment) {
+            Ok(Segment::Static(segment)) => {
+                q!(Vars { segment }, { rweb::filters::path::path(segment) })
+            }
+            Ok(Segment::Param { name, pattern }) => {
+                match find_param(span, segment, name, sig, &mut vars) {
+                    Ok(ty) => match pattern {
+                        Some(pattern) => q!(Vars { ty, pattern }, {
+                            rweb::rt::constrained_param::<ty>(pattern)
+                        }),
+                        None => q!(Vars { ty }, { rweb::filters::path::param::<ty>() }),
+                    },
+                    Err(err) => {
+                        errors.push(err);
+                        continue;
+                    }
+                }
+            }
+            Ok(Segment::Tail { .. }) if idx != last => {
+                errors.push(Error::new(
+                    span,
+                    format!(
+                        "catch-all path parameter `{}` should be the last segment",
+                        segment
+                    ),
+                ));
+                continue;
+            }
+            Ok(Segment::Tail { name }) => match find_param(span, segment, name, sig, &mut vars) {
+                Ok(ty) => q!(Vars { ty }, { rweb::rt::tail_param::<ty>() }),
+                Err(err) => {
+                    errors.push(err);
+                    continue;
+                }
+            },
+            Err(msg) => {
+                errors.push(Error::new(span, msg));
+                continue;
             }
-        } else {
-            q!(Vars { segment }, { rweb::filters::path::path(segment) })
         };
 
         if exprs.is_empty() {
@@ -94,12 +329,91 @@ pub fn compile(
         }
     }
 
+    errors.finish()?;
+
     if end {
         exprs.push(q!({ and(rweb::filters::path::end()) }).parse());
     }
 
-    (q!(Vars { exprs }, { exprs }).parse(), vars)
+    Ok((q!(Vars { exprs }, { exprs }).parse(), vars))
+}
+
+/// Span of `segment`, a part of `path`, in `lit`.
+///
+/// Falls back to the span of the whole literal if the compiler cannot point
+/// into it, or if the literal is written with escapes.
+fn segment_span(lit: &LitStr, path: &str, segment: &str) -> Span {
+    let token = lit.token();
+    let repr = token.to_string();
+    if repr.len() != path.len() + 2 || &repr[1..repr.len() - 1] != path {
+        return lit.span();
+    }
+
+    // `segment` is a slice of `path`.
+    let start = 1 + (segment.as_ptr() as usize - path.as_ptr() as usize);
+    token
+        .subspan(start..start + segment.len())
+        .unwrap_or_else(|| lit.span())
+}
+
+/// Finds the type of the path parameter `name` and records its position.
+fn find_param<'a>(
+    span: Span,
+    segment: &str,
+    name: &str,
+    sig: Option<&'a Signature>,
+    vars: &mut Vec<(String, usize)>,
+) -> Result<&'a Type, Error> {
+    let sig = match sig {
+        Some(sig) => sig,
+        None => {
+            return Err(Error::new(
+                span,
+                format!(
+                    "path parameter `{}` is not allowed here (currently)",
+                    segment
+                ),
+            ))
+        }
+    };
+
+    let ty = sig
+        .inputs
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, arg)| match arg {
+            FnArg::Typed(ty) => match *ty.pat {
+                // Here if we find a Pat::Ident we get i: &PatIdent and i.ident is the
+                // parameter in the route fn.
+                // I.e dyn_reply(word: String), this would be named: `word` and we
+                // compare it to the segment name mentioned above.
+                // If they match:
+                //      We uses it and adds to our variables.
+                // else
+                //      We will report an error below.
+                Pat::Ident(ref i) if i.ident == name => {
+                    vars.push((name.to_string(), idx));
+                    Some(&*ty.ty)
+                }
+                _ => None,
+            },
+
+            _ => None,
+        })
+        .next();
+
+    match ty {
+        Some(ty) => Ok(ty),
+        None => Err(Error::new(
+            span,
+            format!(
+                "failed to find parameter named `{}` (used by path segment `{}`)",
+                name, segment
+            ),
+        )),
+    }
 }
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -108,18 +422,68 @@ mod tests {
     #[test]
     fn should_work() {
         let path = quote!("/ping");
-        compile(None, path, None, false);
+        compile(None, path, None, false).unwrap();
     }
     #[test]
-    #[should_panic(expected = "Path should start with /")]
-    fn should_panic_if_path_doesnt_start_with_slash() {
+    fn should_fail_if_path_doesnt_start_with_slash() {
         let path = quote! {"{word}"};
-        compile(None, path, None, false);
+        let err = compile(None, path, None, false).err().unwrap();
+        assert_eq!(err.to_string(), "Path should start with /");
     }
     #[test]
-    #[should_panic(expected = "A path containing `//` doesn't make sense")]
-    fn should_panic_if_path_contains_slash_slash() {
+    fn should_fail_if_path_contains_slash_slash() {
         let path = quote! {"/{word}//"};
-        compile(None, path, None, false);
+        let err = compile(None, path, None, false).err().unwrap();
+        assert_eq!(err.to_string(), "A path containing `//` doesn't make sense");
+    }
+    #[test]
+    fn should_fail_on_invalid_constraint() {
+        let path = quote! {"/{id:[0-9}"};
+        let sig: Signature = syn::parse_quote!(fn handler(id: String));
+        let err = compile(None, path, Some(&sig), false).err().unwrap();
+        assert!(err
+            .to_string()
+            .starts_with("invalid constraint for path parameter `id`"));
+    }
+    #[test]
+    fn should_strip_constraints() {
+        assert_eq!(
+            strip_constraints("/users/{id:int}/files/{name:[a-z]{3}}/raw"),
+            "/users/{id}/files/{name}/raw"
+        );
+    }
+    #[test]
+    fn should_fail_if_tail_is_not_last() {
+        let path = quote! {"/{*rest}/raw"};
+        let sig: Signature = syn::parse_quote!(fn handler(rest: String));
+        let err = compile(None, path, Some(&sig), false).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "catch-all path parameter `{*rest}` should be the last segment"
+        );
+    }
+    #[test]
+    fn should_strip_tail() {
+        assert_eq!(strip_constraints("/files/{*path}"), "/files/{path}");
+    }
+    #[test]
+    fn should_split_path() {
+        assert_eq!(
+            split_path("/users/{id:[^/]+}/{*rest}"),
+            vec!["", "users", "{id:[^/]+}", "{*rest}"]
+        );
+    }
+    #[test]
+    fn should_allow_slash_in_constraint() {
+        let path = quote! {"/{id:[^/]+}"};
+        let sig: Signature = syn::parse_quote!(fn handler(id: String));
+        compile(None, path, Some(&sig), false).unwrap();
+    }
+    #[test]
+    fn should_report_every_unknown_parameter() {
+        let path = quote! {"/{a}/{b}"};
+        let sig: Signature = syn::parse_quote!(fn handler());
+        let err = compile(None, path, Some(&sig), false).err().unwrap();
+        assert_eq!(err.into_iter().count(), 2);
     }
 }
@@ -0,0 +1,215 @@
+use crate::util::{Errors, ItemImplExt};
+use pmutil::{q, ToTokensExt};
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::{
+    punctuated::{Pair, Punctuated},
+    Attribute, Data, DeriveInput, Error, Expr, ItemImpl, Lit, Meta, NestedMeta, Token, Variant,
+};
+
+/// Configuration from `#[response(status = 404, description = "...")]`.
+#[derive(Clone)]
+pub struct ResponseAttr {
+    pub status: u16,
+    pub description: Option<String>,
+}
+
+/// Implements `ResponseError` and `Reply` for the type.
+///
+/// `ResponseEntity` is implemented by `#[derive(Schema)]` instead, because it
+/// requires `Entity`.
+pub fn derive_response_error(input: DeriveInput) -> Result<TokenStream, Error> {
+    let mut errors = Errors::default();
+    let arms = response_arms(&input, &mut errors);
+    errors.finish()?;
+
+    let mut statuses: Vec<u16> = vec![];
+    for (_, attr) in &arms {
+        if !statuses.contains(&attr.status) {
+            statuses.push(attr.status);
+        }
+    }
+
+    let status_expr: Expr = match &input.data {
+        Data::Enum(..) => {
+            let arms: Punctuated<pmutil::Quote, Token![,]> = arms
+                .iter()
+                .map(|(v, attr)| {
+                    let v = &v.unwrap().ident;
+                    q!(Vars { v, status: attr.status }, {
+                        Self::v { .. } => rweb::rt::StatusCode::from_u16(status).unwrap()
+                    })
+                })
+                .collect();
+            q!(Vars { arms }, {
+                match *self {
+                    arms
+                }
+            })
+            .parse()
+        }
+        _ => q!(
+            Vars {
+                status: statuses[0]
+            },
+            { rweb::rt::StatusCode::from_u16(status).unwrap() }
+        )
+        .parse(),
+    };
+    let statuses: Punctuated<pmutil::Quote, Token![,]> = statuses
+        .iter()
+        .map(|status| {
+            Pair::Punctuated(
+                q!(Vars { status }, {
+                    rweb::rt::StatusCode::from_u16(status).unwrap()
+                }),
+                Default::default(),
+            )
+        })
+        .collect();
+
+    let items = vec![
+        q!(
+            Vars {
+                Type: &input.ident,
+                status_expr,
+                statuses,
+            },
+            {
+                impl rweb::ResponseError for Type {
+                    fn status_code(&self) -> rweb::rt::StatusCode {
+                        status_expr
+                    }
+
+                    fn status_codes() -> Vec<rweb::rt::StatusCode> {
+                        vec![statuses]
+                    }
+                }
+            }
+        )
+        .parse::<ItemImpl>()
+        .with_generics(input.generics.clone()),
+        q!(Vars { Type: &input.ident }, {
+            impl rweb::Reply for Type {
+                fn into_response(self) -> rweb::reply::Response {
+                    rweb::ResponseError::error_response(&self)
+                }
+            }
+        })
+        .parse::<ItemImpl>()
+        .with_generics(input.generics.clone()),
+    ];
+
+    let mut tokens = TokenStream::new();
+    for item in items {
+        item.to_tokens(&mut tokens);
+    }
+    Ok(tokens)
+}
+
+/// Parses `#[response(..)]` on the type and its variants.
+///
+/// Returns each arm of `status_code` with the configuration of the variant.
+pub fn response_arms<'a>(
+    input: &'a DeriveInput,
+    errors: &mut Errors,
+) -> Vec<(Option<&'a Variant>, ResponseAttr)> {
+    let default = errors.collect(parse_response_attr(&input.attrs)).flatten();
+
+    // Each arm of `status_code` with the configuration of the variant.
+    let mut arms: Vec<(Option<&Variant>, ResponseAttr)> = vec![];
+    match &input.data {
+        Data::Struct(..) => match &default {
+            Some(attr) => arms.push((None, attr.clone())),
+            None => errors.push(Error::new_spanned(
+                &input.ident,
+                "#[derive(ResponseError)] requires #[response(status = ...)] on the struct",
+            )),
+        },
+        Data::Enum(data) => {
+            for v in &data.variants {
+                let attr = match errors.collect(parse_response_attr(&v.attrs)) {
+                    Some(attr) => attr,
+                    None => continue,
+                };
+                match attr.or_else(|| default.clone()) {
+                    Some(attr) => arms.push((Some(v), attr)),
+                    None => errors.push(Error::new_spanned(
+                        &v.ident,
+                        "#[derive(ResponseError)] requires #[response(status = ...)] on each \
+                         variant",
+                    )),
+                }
+            }
+        }
+        Data::Union(..) => errors.push(Error::new_spanned(
+            &input.ident,
+            "#[derive(ResponseError)] cannot be used with unions",
+        )),
+    }
+
+    arms
+}
+
+/// Parses `#[response(status = 404, description = "Item not found")]`.
+fn parse_response_attr(attrs: &[Attribute]) -> Result<Option<ResponseAttr>, Error> {
+    let attr = match attrs.iter().find(|attr| attr.path.is_ident("response")) {
+        Some(attr) => attr,
+        None => return Ok(None),
+    };
+    let invalid_usage = |span: &dyn ToTokens| {
+        Error::new_spanned(
+            span,
+            "Correct usage: #[response(status = 404, description = \"Item not found\")]",
+        )
+    };
+
+    let list = match attr.parse_meta()? {
+        Meta::List(l) => l,
+        meta => return Err(invalid_usage(&meta)),
+    };
+
+    let mut status = None;
+    let mut description = None;
+    for nested in &list.nested {
+        let v = match nested {
+            NestedMeta::Meta(Meta::NameValue(v)) => v,
+            _ => return Err(invalid_usage(nested)),
+        };
+
+        if v.path.is_ident("status") {
+            let parsed = match &v.lit {
+                Lit::Int(i) => i.base10_parse::<u16>().ok(),
+                Lit::Str(s) => s.value().parse::<u16>().ok(),
+                _ => None,
+            };
+            match parsed {
+                Some(code) if (100..1000).contains(&code) => status = Some(code),
+                _ => {
+                    return Err(Error::new_spanned(
+                        &v.lit,
+                        "#[response]: status should be a number between 100 and 999",
+                    ))
+                }
+            }
+        } else if v.path.is_ident("description") {
+            match &v.lit {
+                Lit::Str(s) => description = Some(s.value()),
+                lit => return Err(invalid_usage(lit)),
+            }
+        } else {
+            return Err(Error::new_spanned(
+                &v.path,
+                format!("Unknown config `{}` for #[response]", v.path.dump()),
+            ));
+        }
+    }
+
+    match status {
+        Some(status) => Ok(Some(ResponseAttr {
+            status,
+            description,
+        })),
+        None => Err(invalid_usage(attr)),
+    }
+}
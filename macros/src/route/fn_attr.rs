@@ -1,17 +1,33 @@
 use super::ParenTwoValue;
-use crate::parse::{Delimited, Paren};
+use crate::{
+    parse::{Delimited, Paren},
+    util::Errors,
+};
 use pmutil::{q, ToTokensExt};
-use syn::{parse2, Attribute, Expr, Meta, MetaNameValue};
+use syn::{parse2, Attribute, Error, Expr, Lit, Meta, MetaNameValue};
 
 /// Handle attributes on fn item like `#[header(ContentType =
 /// "application/json")]`
-pub fn compile_fn_attrs(mut base: Expr, attrs: &mut Vec<Attribute>, emitted_map: bool) -> Expr {
+pub fn compile_fn_attrs(
+    mut base: Expr,
+    attrs: &mut Vec<Attribute>,
+    emitted_map: bool,
+) -> Result<Expr, Error> {
+    let mut errors = Errors::default();
+
     attrs.retain(|attr| {
         if attr.path.is_ident("header") {
-            let t: ParenTwoValue = parse2(attr.tokens.clone()).expect(
-                "failed to parser header. Please provide it like #[header(\"ContentType\", \
-                 \"application/json\")]",
-            );
+            let t: ParenTwoValue = match parse2(attr.tokens.clone()) {
+                Ok(t) => t,
+                Err(..) => {
+                    errors.push(Error::new_spanned(
+                        attr,
+                        "failed to parser header. Please provide it like #[header(\"ContentType\", \
+                         \"application/json\")]",
+                    ));
+                    return false;
+                }
+            };
 
             base = q!(
                 Vars {
@@ -26,22 +42,33 @@ pub fn compile_fn_attrs(mut base: Expr, attrs: &mut Vec<Attribute>, emitted_map:
         }
 
         if attr.path.is_ident("body_size") {
-            let meta = parse2::<Paren<MetaNameValue>>(attr.tokens.clone())
-                .expect("Correct usage: #[body_size(max = \"8192\")]")
-                .inner;
+            let meta = match parse2::<Paren<MetaNameValue>>(attr.tokens.clone()) {
+                Ok(meta) => meta.inner,
+                Err(..) => {
+                    errors.push(Error::new_spanned(
+                        attr,
+                        "Correct usage: #[body_size(max = \"8192\")]",
+                    ));
+                    return false;
+                }
+            };
 
             if meta.path.is_ident("max") {
-                let v = meta.lit.dump().to_string();
-                let mut value = &*v;
-                if value.starts_with('"') {
-                    value = &value[1..value.len() - 1];
-                }
-                let tts: proc_macro2::TokenStream = value.parse().unwrap_or_else(|err| {
-                    panic!(
-                        "#[body_size]: failed to parse value of max as number: {:?}",
-                        err
-                    )
-                });
+                let tts = match &meta.lit {
+                    Lit::Str(s) => s.value().parse::<proc_macro2::TokenStream>().ok(),
+                    Lit::Int(i) => Some(i.dump()),
+                    _ => None,
+                };
+                let tts = match tts {
+                    Some(tts) => tts,
+                    None => {
+                        errors.push(Error::new_spanned(
+                            &meta.lit,
+                            "#[body_size]: failed to parse value of max as number",
+                        ));
+                        return false;
+                    }
+                };
 
                 base = q!(
                     Vars {
@@ -54,7 +81,14 @@ pub fn compile_fn_attrs(mut base: Expr, attrs: &mut Vec<Attribute>, emitted_map:
                 return false;
             }
 
-            panic!("Unknown configuration {} for #[body_size]", meta.dump())
+            errors.push(Error::new_spanned(
+                &meta.path,
+                format!(
+                    "Unknown configuration {} for #[body_size]",
+                    meta.path.dump()
+                ),
+            ));
+            return false;
         }
 
         if attr.path.is_ident("cors") && emitted_map {
@@ -65,16 +99,22 @@ pub fn compile_fn_attrs(mut base: Expr, attrs: &mut Vec<Attribute>, emitted_map:
                  all methods.\nNote: you can omit headers to use the default behavior.\nNote: you \
                  can omit max_age to use the default value";
 
-            let configs = parse2::<Paren<Delimited<Meta>>>(attr.tokens.clone())
-                .expect(correct_usage)
-                .inner
-                .inner;
+            let configs = match parse2::<Paren<Delimited<Meta>>>(attr.tokens.clone()) {
+                Ok(configs) => configs.inner.inner,
+                Err(..) => {
+                    errors.push(Error::new_spanned(attr, correct_usage));
+                    return false;
+                }
+            };
 
             let mut cors_expr: Expr = q!({ rweb::filters::cors::cors() }).parse();
 
             for config in configs {
                 match config {
-                    Meta::Path(p) => unimplemented!("Path meta: {}\n{}", p.dump(), correct_usage),
+                    Meta::Path(p) => errors.push(Error::new_spanned(
+                        &p,
+                        format!("Unknown config: `{}`\n{}", p.dump(), correct_usage),
+                    )),
                     Meta::List(l) => {
                         if l.path.is_ident("origins") {
                             for origin in l.nested {
@@ -109,7 +149,10 @@ pub fn compile_fn_attrs(mut base: Expr, attrs: &mut Vec<Attribute>, emitted_map:
                                 .parse();
                             }
                         } else {
-                            panic!("Unknown config: `{}`\n{}", l.dump(), correct_usage)
+                            errors.push(Error::new_spanned(
+                                &l.path,
+                                format!("Unknown config: `{}`\n{}", l.path.dump(), correct_usage),
+                            ))
                         }
                     }
                     Meta::NameValue(n) => {
@@ -123,7 +166,10 @@ pub fn compile_fn_attrs(mut base: Expr, attrs: &mut Vec<Attribute>, emitted_map:
                             )
                             .parse();
                         } else {
-                            panic!("Unknown config: `{}`\n{}", n.dump(), correct_usage)
+                            errors.push(Error::new_spanned(
+                                &n.path,
+                                format!("Unknown config: `{}`\n{}", n.path.dump(), correct_usage),
+                            ))
                         }
                     }
                 }
@@ -144,5 +190,7 @@ pub fn compile_fn_attrs(mut base: Expr, attrs: &mut Vec<Attribute>, emitted_map:
         true
     });
 
-    base
+    errors.finish()?;
+
+    Ok(base)
 }
@@ -1,12 +1,15 @@
+use crate::util::Errors;
 use pmutil::{q, Quote, ToTokensExt};
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream};
 use syn::{
     parenthesized,
     parse::{Parse, ParseStream},
-    parse_quote::parse,
+    parse2,
     punctuated::Punctuated,
     visit::Visit,
-    Block, Expr, ItemFn, LitStr, ReturnType, Signature, Token, Type, TypeImplTrait, Visibility,
+    visit_mut::VisitMut,
+    Block, Error, Expr, ItemFn, LitStr, PathArguments, ReturnType, Signature, Token, Type,
+    TypeImplTrait, TypeInfer, Visibility,
 };
 
 pub mod fn_attr;
@@ -51,31 +54,58 @@ pub fn compile_route(
     path: TokenStream,
     f: TokenStream,
 ) -> proc_macro::TokenStream {
-    let mut f: ItemFn = parse(f);
+    match compile(method, path, f) {
+        Ok(item) => item.dump().into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn compile(method: Option<Quote>, path: TokenStream, f: TokenStream) -> Result<ItemFn, Error> {
+    let mut f: ItemFn = parse2(f)?;
     let sig = &f.sig;
+    let router_params = crate::path::router_params(sig);
     let mut data_inputs: Punctuated<_, Token![,]> = Default::default();
-
-    // Apply method filter
-    let expr: Expr = if let Some(ref method) = method {
-        q!(
+    let mut errors = Errors::default();
+
+    let expr: Expr = q!({ rweb::filters::any::any() }).parse();
+    let (mut expr, vars) = errors
+        .collect(crate::path::compile(
+            Some(expr.clone()),
+            path.clone(),
+            Some(sig),
+            true,
+        ))
+        .unwrap_or((expr, vec![]));
+
+    // Apply method filter after the path, so a request for another path is
+    // rejected as not found.
+    if let Some(ref method) = method {
+        expr = q!(
             Vars {
+                expr,
                 http_method: method,
             },
-            { rweb::filters::method::http_method() }
+            { expr.and(rweb::filters::method::http_method()) }
         )
-        .parse()
-    } else {
-        q!({ rweb::filters::any() }).parse()
-    };
-
-    let (mut expr, vars) = crate::path::compile(Some(expr), path.clone(), Some(sig), true);
-    let path: LitStr = parse(path);
+        .parse();
+    }
+    let path: LitStr = parse2(path)?;
     let path = path.value();
 
     let (handler_fn, from_req_types) = {
-        let (e, inputs, from_req_types) =
-            param::compile(expr, &f.sig, &mut data_inputs, vars, true);
-        expr = e;
+        let (inputs, from_req_types) = match errors.collect(param::compile(
+            expr.clone(),
+            &f.sig,
+            &mut data_inputs,
+            vars,
+            true,
+        )) {
+            Some((e, inputs, from_req_types)) => {
+                expr = e;
+                (inputs, from_req_types)
+            }
+            None => (f.sig.inputs.clone(), vec![]),
+        };
         (
             ItemFn {
                 attrs: Default::default(),
@@ -95,9 +125,54 @@ pub fn compile_route(
     let should_use_impl_trait =
         sig.asyncness.is_some() || f.attrs.iter().any(|attr| attr.path.is_ident("cors"));
 
-    let expr = fn_attr::compile_fn_attrs(expr, &mut f.attrs, false);
+    let expr = errors
+        .collect(fn_attr::compile_fn_attrs(expr.clone(), &mut f.attrs, false))
+        .unwrap_or(expr);
+
+    let returns_result = errors.collect(returns_result(&sig.output)).unwrap_or(false);
+    let expr = if returns_result {
+        // Errors implementing `ResponseError` are converted into responses.
+        //
+        // The handler is passed by path so that parameters disabled by `#[cfg]` are
+        // handled by the compiler.
+        let mut ty = match &sig.output {
+            ReturnType::Type(_, ty) => (**ty).clone(),
+            ReturnType::Default => unreachable!(),
+        };
+        replace_impl_trait(&mut ty);
+
+        if sig.asyncness.is_some() {
+            q!(
+                Vars {
+                    handler: &sig.ident,
+                    expr,
+                    Ret: ty,
+                },
+                {
+                    expr.then(handler).and_then(|res: Ret| async move {
+                        use rweb::rt::{ViaReply as _, ViaResponseError as _};
 
-    let expr = if sig.asyncness.is_some() {
+                        (&rweb::rt::tag(&res)).try_reply(res)
+                    })
+                }
+            )
+        } else {
+            q!(
+                Vars {
+                    handler: &sig.ident,
+                    expr,
+                    Ret: ty,
+                },
+                {
+                    expr.map(handler).map(|res: Ret| {
+                        use rweb::rt::{ViaReply as _, ViaResponseError as _};
+
+                        (&rweb::rt::tag(&res)).reply(res)
+                    })
+                }
+            )
+        }
+    } else if sig.asyncness.is_some() {
         q!(
             Vars {
                 handler: &sig.ident,
@@ -116,19 +191,46 @@ pub fn compile_route(
     }
     .parse::<Expr>();
 
-    let mut expr = fn_attr::compile_fn_attrs(expr, &mut f.attrs, true);
+    let mut expr = errors
+        .collect(fn_attr::compile_fn_attrs(expr.clone(), &mut f.attrs, true))
+        .unwrap_or(expr);
 
-    let ret = if should_use_impl_trait {
-        q!((impl rweb::Reply)).dump()
-    } else {
-        match sig.output {
-            ReturnType::Default => panic!("http handler should return type"),
-            ReturnType::Type(_, ref ty) => ty.dump(),
+    if let Some(ref method) = method {
+        // Let an enclosing router know the method of this route.
+        let method = Ident::new(&method.dump().to_string().to_uppercase(), Span::call_site());
+        expr = q!(
+            Vars {
+                expr,
+                method,
+                segments: crate::path::route_segments(&path),
+            },
+            {
+                {
+                    rweb::rt::register_route(segments, rweb::rt::Method::method);
+                    expr
+                }
+            }
+        )
+        .parse();
+    }
+
+    let ret = match sig.output {
+        ReturnType::Default => {
+            errors.push(Error::new_spanned(
+                &sig.ident,
+                "http handler should have a return type",
+            ));
+            q!((impl rweb::Reply)).dump()
         }
+        _ if should_use_impl_trait => q!((impl rweb::Reply)).dump(),
+        _ if returns_result => q!((rweb::reply::Response)).dump(),
+        ReturnType::Type(_, ref ty) => ty.dump(),
     };
 
     if cfg!(feature = "openapi") {
-        let op = crate::openapi::parse(&path, sig, &mut f.attrs);
+        let op = errors
+            .collect(crate::openapi::parse(&path, sig, &mut f.attrs))
+            .unwrap_or_default();
         let op = crate::openapi::quote_op(op);
 
         let mut op_body: Block = q!(Vars { op }, {
@@ -139,9 +241,11 @@ pub fn compile_route(
         })
         .parse();
 
-        for from_req in from_req_types {
+        for (from_req, cfgs) in from_req_types {
+            let cfgs: TokenStream = cfgs.iter().map(|attr| attr.dump()).collect();
             op_body.stmts.push(
-                q!(Vars { Type: &from_req }, {
+                q!(Vars { Type: &from_req, cfgs }, {
+                    cfgs
                     rweb::openapi::Collector::add_request_type_to::<Type>(__collector, &mut v);
                 })
                 .parse(),
@@ -149,9 +253,25 @@ pub fn compile_route(
         }
 
         match sig.output {
-            ReturnType::Default => panic!("http handlers should have return type"),
+            // Reported above.
+            ReturnType::Default => {}
             ReturnType::Type(_, ref ty) => {
-                if !contains_impl_trait(&**ty) {
+                if !contains_impl_trait(ty) && returns_result {
+                    op_body.stmts.push(
+                        q!(Vars { Type: ty }, {
+                            {
+                                use rweb::rt::{
+                                    ViaErrorEntity as _, ViaErrorStatus as _,
+                                    ViaResponseEntity as _,
+                                };
+
+                                (&&&rweb::rt::Tag::<Type>::new())
+                                    .add_responses_to(__collector, &mut v);
+                            }
+                        })
+                        .parse(),
+                    );
+                } else if !contains_impl_trait(ty) {
                     op_body.stmts.push(
                         q!(Vars { Type: ty }, {
                             rweb::openapi::Collector::add_response_to::<Type>(__collector, &mut v);
@@ -165,7 +285,7 @@ pub fn compile_route(
         op_body.stmts.push(
             q!(
                 Vars {
-                    path: &path,
+                    path: crate::path::strip_constraints(&path),
                     http_method: method,
                 },
                 {
@@ -193,6 +313,15 @@ pub fn compile_route(
         .parse();
     }
 
+    if !router_params.is_empty() {
+        // The enclosing router checks that it has the parameters, and passes their
+        // offsets.
+        expr = q!(Vars { expr }, {
+            rweb::rt::WithRouterParams::new(move |__router_param_offsets: &[usize]| expr)
+        })
+        .parse();
+    }
+
     let mut outer = if cfg!(feature = "boxed") {
         q!(
             Vars {
@@ -236,13 +365,75 @@ pub fn compile_route(
         .parse::<ItemFn>()
     };
 
+    if !router_params.is_empty() {
+        let output = match &outer.sig.output {
+            ReturnType::Type(_, ty) => ty,
+            ReturnType::Default => unreachable!(),
+        };
+        outer.sig.output = q!(
+            Vars {
+                output,
+                Params: crate::path::uses_router_params(&router_params),
+            },
+            { -> rweb::rt::WithRouterParams<output, Params> }
+        )
+        .parse();
+    }
+
     outer.vis = f.vis;
     outer.sig = Signature {
         inputs: data_inputs,
         ..outer.sig
     };
 
-    outer.dump().into()
+    errors.finish()?;
+
+    Ok(outer)
+}
+
+/// Returns true if the return type is written as `Result<T, E>`.
+/// Returns true for `Result<T, E>`. Aliases like `io::Result<T>` are rejected,
+/// as the error type is required to convert errors into responses.
+fn returns_result(output: &ReturnType) -> Result<bool, Error> {
+    let ty = match output {
+        ReturnType::Type(_, ty) => ty,
+        ReturnType::Default => return Ok(false),
+    };
+    let segment = match &**ty {
+        Type::Path(ty) => match ty.path.segments.last() {
+            Some(segment) if segment.ident == "Result" => segment,
+            _ => return Ok(false),
+        },
+        _ => return Ok(false),
+    };
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 2 => Ok(true),
+        _ => Err(Error::new_spanned(
+            ty,
+            "the return type should be written as `Result<T, E>`, as the error type is \
+             required to convert errors into responses",
+        )),
+    }
+}
+
+/// Replaces `impl Trait` in the type with `_`, so it can be used as the type of
+/// a closure parameter.
+fn replace_impl_trait(ty: &mut Type) {
+    struct Visitor;
+    impl VisitMut for Visitor {
+        fn visit_type_mut(&mut self, ty: &mut Type) {
+            if let Type::ImplTrait(..) = ty {
+                *ty = Type::Infer(TypeInfer {
+                    underscore_token: Default::default(),
+                });
+                return;
+            }
+            syn::visit_mut::visit_type_mut(self, ty);
+        }
+    }
+
+    Visitor.visit_type_mut(ty);
 }
 
 fn contains_impl_trait(ty: &Type) -> bool {
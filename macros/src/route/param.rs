@@ -1,144 +1,223 @@
-use crate::route::EqStr;
-use pmutil::{q, ToTokensExt};
+use crate::{path::is_router_param, route::EqStr, util::Errors};
+use pmutil::{q, Quote, ToTokensExt};
 use proc_macro2::TokenStream;
 use std::collections::HashSet;
 use syn::{
-    parse_quote::parse, punctuated::Punctuated, Attribute, Expr, FnArg, Pat, Path, Signature,
-    Token, Type,
+    parse2, punctuated::Punctuated, spanned::Spanned, Attribute, Error, Expr, FnArg, LitStr, Pat,
+    Path, Signature, Token, Type,
 };
 
-/// Returns (expr, actual_inputs_of_handler, from_request_types)
+/// (expr, actual_inputs_of_handler, from_request_types)
+///
+/// `#[cfg]` attributes of the parameter are stored with each type.
+pub type Compiled = (
+    Expr,
+    Punctuated<FnArg, Token![,]>,
+    Vec<(Type, Vec<Attribute>)>,
+);
+
 pub fn compile(
     mut expr: Expr,
     sig: &Signature,
     data_inputs: &mut Punctuated<FnArg, Token![,]>,
     path_vars: Vec<(String, usize)>,
     insert_data_provider: bool,
-) -> (Expr, Punctuated<FnArg, Token![,]>, Vec<Type>) {
+) -> Result<Compiled, Error> {
+    let mut errors = Errors::default();
     let mut path_params = HashSet::new();
-    let mut inputs = sig.inputs.clone();
     let mut from_request_types = vec![];
 
-    {
-        // Handle path parameters
-
-        for (orig_idx, (name, idx)) in path_vars.into_iter().enumerate() {
-            if path_params.contains(&orig_idx) {
-                continue;
-            }
-
-            if let FnArg::Typed(pat) = &sig.inputs[idx] {
-                match *pat.pat {
-                    Pat::Ident(ref i) if i.ident == name => {
-                        inputs[orig_idx] = sig.inputs[idx].clone();
-                        inputs[idx] = sig.inputs[orig_idx].clone();
-                        path_params.insert(orig_idx);
-                    }
-                    _ => {}
-                }
-            }
-        }
+    // Path parameters come first, in the order they are extracted.
+    let mut inputs: Punctuated<FnArg, Token![,]> = Default::default();
+    let path_indices: HashSet<_> = path_vars.iter().map(|(_, idx)| *idx).collect();
+    for (_, idx) in &path_vars {
+        path_params.insert(inputs.len());
+        inputs.push(sig.inputs[*idx].clone());
     }
+    inputs.extend(
+        sig.inputs
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| !path_indices.contains(idx))
+            .map(|(_, arg)| arg.clone()),
+    );
 
     let inputs = {
         let mut actual_inputs = vec![];
 
         // Handle annotated parameters.
         for (idx, mut i) in inputs.into_pairs().enumerate() {
+            let mut param_attr = None;
+            if let FnArg::Typed(pat) = i.value_mut() {
+                param_attr = pat
+                    .attrs
+                    .iter()
+                    .find(|attr| attr.path.is_ident("param"))
+                    .cloned();
+                // Documentation is not allowed on parameters of the handler.
+                pat.attrs
+                    .retain(|attr| !attr.path.is_ident("doc") && !attr.path.is_ident("param"));
+            }
+
             if path_params.contains(&idx) {
+                if let FnArg::Typed(pat) = i.value_mut() {
+                    pat.attrs.retain(|attr| !is_router_param(attr));
+                    for attr in pat.attrs.iter().filter(|attr| attr.path.is_ident("cfg")) {
+                        errors.push(Error::new_spanned(
+                            attr,
+                            "#[cfg] cannot be used on path parameters",
+                        ));
+                    }
+                }
                 actual_inputs.push(i);
                 continue;
             }
 
-            let cloned_i = i.clone();
-
             match i.value_mut() {
                 FnArg::Receiver(_) => continue,
                 FnArg::Typed(ref mut pat) => {
-                    if pat.attrs.is_empty() {
-                        // If there's no attribute, it's type should implement FromRequest
-
-                        actual_inputs.push(cloned_i);
-                        from_request_types.push(*pat.ty.clone());
-                        expr = q!(Vars { expr, T: &pat.ty }, {
-                            expr.and(<T as rweb::FromRequest>::new())
-                        })
-                        .parse();
-
-                        continue;
-                    }
+                    let cfgs: Vec<Attribute> = pat
+                        .attrs
+                        .iter()
+                        .filter(|attr| attr.path.is_ident("cfg"))
+                        .cloned()
+                        .collect();
+                    let mut rweb_attrs: Vec<Attribute> = pat
+                        .attrs
+                        .iter()
+                        .filter(|attr| is_rweb_arg_attr(attr))
+                        .cloned()
+                        .collect();
+                    // Other attributes like `#[allow]` are kept.
+                    pat.attrs.retain(|attr| !is_rweb_arg_attr(attr));
 
-                    let is_rweb_attr = pat.attrs.iter().any(is_rweb_arg_attr);
-                    if !is_rweb_attr {
-                        // We don't care about this parameter.
-                        actual_inputs.push(i);
+                    if rweb_attrs.len() > 1 {
+                        for attr in &rweb_attrs[1..] {
+                            errors.push(Error::new_spanned(
+                                attr,
+                                "rweb currently support only one extractor attribute on a \
+                                 parameter",
+                            ));
+                        }
                         continue;
                     }
 
-                    if pat.attrs.len() != 1 {
-                        // TODO: Support cfg?
-                        panic!("rweb currently support only one attribute on a parameter")
+                    let documented = rweb_attrs.iter().any(|attr| {
+                        attr.path.is_ident("header")
+                            || attr.path.is_ident("query")
+                            || attr.path.is_ident("cookie")
+                    });
+                    if let (Some(attr), false) = (&param_attr, documented) {
+                        errors.push(Error::new_spanned(
+                            attr,
+                            "#[param] can only be used with path, header, query and cookie \
+                             parameters",
+                        ));
                     }
 
-                    let attr = pat.attrs.get(0).cloned().unwrap();
-                    pat.attrs = vec![];
+                    let attr = match rweb_attrs.pop() {
+                        Some(attr) => attr,
+                        None => {
+                            // If there's no attribute, it's type should implement FromRequest
+                            //
+                            // Constraints of the type are checked if it implements
+                            // `Constraints`.
+                            from_request_types.push((*pat.ty.clone(), cfgs.clone()));
+                            expr = and_filter(
+                                expr,
+                                &cfgs,
+                                q!(Vars { T: &pat.ty }, {
+                                    {
+                                        use rweb::rt::{
+                                            ViaConstraints as _, ViaNoConstraints as _,
+                                        };
+                                        (&rweb::rt::Tag::<T>::new()).filter()
+                                    }
+                                }),
+                            );
+                            actual_inputs.push(i);
+                            continue;
+                        }
+                    };
 
                     if attr.path.is_ident("form") {
-                        expr = q!(Vars { expr }, { expr.and(rweb::filters::body::form()) }).parse()
+                        expr = and_filter(expr, &cfgs, q!({ rweb::filters::body::form() }));
                     } else if attr.path.is_ident("json") {
-                        expr = q!(Vars { expr }, { expr.and(rweb::filters::body::json()) }).parse()
+                        expr = and_filter(expr, &cfgs, q!({ rweb::filters::body::json() }));
                     } else if attr.path.is_ident("body") {
-                        expr = q!(Vars { expr }, { expr.and(rweb::filters::body::bytes()) }).parse()
+                        expr = and_filter(expr, &cfgs, q!({ rweb::filters::body::bytes() }));
                     } else if attr.path.is_ident("query") {
-                        expr = q!(Vars { expr }, { expr.and(rweb::filters::query::raw()) }).parse()
+                        expr = and_filter(expr, &cfgs, q!({ rweb::filters::query::raw() }));
                     } else if attr.path.is_ident("cookie") {
-                        if let Ok(cookie_name) = syn::parse2::<EqStr>(attr.tokens.clone()) {
-                            expr = q!(
-                                Vars {
-                                    expr,
-                                    cookie_name: cookie_name.value
-                                },
-                                { expr.and(rweb::filters::cookie::cookie(cookie_name)) }
-                            )
-                            .parse();
+                        if let Ok(cookie_name) = parse2::<EqStr>(attr.tokens.clone()) {
+                            expr = and_filter(
+                                expr,
+                                &cfgs,
+                                q!(
+                                    Vars {
+                                        cookie_name: cookie_name.value
+                                    },
+                                    { rweb::filters::cookie::cookie(cookie_name) }
+                                ),
+                            );
                         } else {
-                            panic!("#[cookie = \"foo\"] is used incorrectly")
+                            errors.push(Error::new_spanned(
+                                &attr,
+                                "#[cookie = \"foo\"] is used incorrectly",
+                            ));
+                            continue;
                         }
                     } else if attr.path.is_ident("header") {
-                        if let Ok(header_name) = syn::parse2::<EqStr>(attr.tokens.clone()) {
-                            expr = q!(
-                                Vars {
-                                    expr,
-                                    header_name: header_name.value
-                                },
-                                { expr.and(rweb::filters::header::header(header_name)) }
-                            )
-                            .parse();
+                        if let Ok(header_name) = parse2::<EqStr>(attr.tokens.clone()) {
+                            expr = and_filter(
+                                expr,
+                                &cfgs,
+                                q!(
+                                    Vars {
+                                        header_name: header_name.value
+                                    },
+                                    { rweb::filters::header::header(header_name) }
+                                ),
+                            );
                         } else {
-                            panic!(
-                                "invalid usage of header: {}\nCorrect usage is#[header = \
-                                 \"accpet\"]",
-                                attr.tokens.dump()
-                            )
+                            errors.push(Error::new_spanned(
+                                &attr,
+                                "invalid usage of header\nCorrect usage is #[header = \"accept\"]",
+                            ));
+                            continue;
                         }
                     } else if attr.path.is_ident("filter") {
-                        let filter_path: EqStr = parse(attr.tokens.clone());
-                        let filter_path = filter_path.value.value();
-                        let tts: TokenStream = filter_path.parse().expect("failed tokenize");
-                        let filter_path: Path = parse(tts);
-
-                        expr = q!(Vars { expr, filter_path }, { expr.and(filter_path()) }).parse();
+                        match parse_filter_path(&attr) {
+                            Ok(filter_path) => {
+                                expr = and_filter(
+                                    expr,
+                                    &cfgs,
+                                    q!(Vars { filter_path }, { filter_path() }),
+                                );
+                            }
+                            Err(err) => {
+                                errors.push(err);
+                                continue;
+                            }
+                        }
                     } else if attr.path.is_ident("data") {
                         let ident = match &*pat.pat {
                             Pat::Ident(i) => &i.ident,
-                            _ => unimplemented!("#[data] with complex pattern"),
+                            _ => {
+                                errors.push(Error::new(
+                                    pat.pat.span(),
+                                    "#[data] can only be used with a simple identifier pattern",
+                                ));
+                                continue;
+                            }
                         };
 
                         if insert_data_provider {
-                            expr = q!(Vars { expr, ident }, {
-                                expr.and(rweb::rt::provider(ident))
-                            })
-                            .parse();
+                            expr = and_filter(
+                                expr,
+                                &cfgs,
+                                q!(Vars { ident }, { rweb::rt::provider(ident) }),
+                            );
                         }
 
                         data_inputs.push(i.value().clone());
@@ -160,7 +239,47 @@ pub fn compile(
         actual_inputs.into_iter().collect()
     };
 
-    (expr, inputs, from_request_types)
+    errors.finish()?;
+
+    Ok((expr, inputs, from_request_types))
+}
+
+/// Chains `filter` to `expr`. If the parameter has `#[cfg]` attributes, the
+/// filter is only applied when they are enabled.
+fn and_filter(expr: Expr, cfgs: &[Attribute], filter: Quote) -> Expr {
+    if cfgs.is_empty() {
+        return q!(Vars { expr, filter }, { expr.and(filter) }).parse();
+    }
+
+    let cfgs: TokenStream = cfgs.iter().map(|attr| attr.dump()).collect();
+    q!(Vars { expr, filter, cfgs }, {
+        {
+            let __filter = expr;
+            cfgs
+            let __filter = __filter.and(filter);
+            __filter
+        }
+    })
+    .parse()
+}
+
+/// Parses `#[filter = "path::to::filter"]`.
+fn parse_filter_path(attr: &Attribute) -> Result<Path, Error> {
+    let correct_usage = "Correct usage: #[filter = \"path::to::filter\"]";
+    let filter_path: LitStr = parse2::<EqStr>(attr.tokens.clone())
+        .map_err(|_| Error::new_spanned(attr, correct_usage))?
+        .value;
+    let tts: TokenStream = filter_path
+        .value()
+        .parse()
+        .map_err(|_| Error::new(filter_path.span(), correct_usage))?;
+
+    parse2(tts).map_err(|_| {
+        Error::new(
+            filter_path.span(),
+            format!("`{}` is not a valid path", filter_path.value()),
+        )
+    })
 }
 
 fn is_rweb_arg_attr(a: &Attribute) -> bool {
@@ -1,92 +1,293 @@
-use crate::route::fn_attr::compile_fn_attrs;
+use crate::{
+    openapi::quote_parameter, path::Segment, route::fn_attr::compile_fn_attrs, util::Errors,
+};
 use pmutil::{q, Quote, ToTokensExt};
 use proc_macro2::{Ident, TokenStream};
+use quote::quote_spanned;
 use syn::{
     parse::{Parse, ParseStream},
     parse2,
     punctuated::{Pair, Punctuated},
-    Error, Expr, FnArg, ItemFn, LitStr, Meta, Pat, Token,
+    spanned::Spanned,
+    Error, Expr, FnArg, ItemFn, LitStr, Meta, NestedMeta, Pat, Path, ReturnType, Token,
 };
 
 struct Input {
     path: LitStr,
     _comma: Token![,],
     services: Meta,
+    /// `fallback(handler)`
+    fallback: Option<Meta>,
 }
 
 impl Parse for Input {
     fn parse(input: ParseStream) -> Result<Self, Error> {
+        let path = input.parse()?;
+        let _comma = input.parse()?;
+        let services = input.parse()?;
+        let fallback = if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
         Ok(Input {
-            path: input.parse()?,
-            _comma: input.parse()?,
-            services: input.parse()?,
+            path,
+            _comma,
+            services,
+            fallback,
         })
     }
 }
 
-pub fn router(attr: TokenStream, item: TokenStream) -> ItemFn {
-    let mut f: ItemFn = parse2(item).expect("failed to parse input as a function item");
-    assert!(
-        f.block.stmts.is_empty(),
-        "#[router] function cannot have body"
-    );
+/// Parses `fallback(handler)`.
+fn parse_fallback(meta: &Meta) -> Result<&Path, Error> {
+    match meta {
+        Meta::List(list) if list.path.is_ident("fallback") && list.nested.len() == 1 => {
+            match &list.nested[0] {
+                NestedMeta::Meta(Meta::Path(handler)) => Ok(handler),
+                nested => Err(Error::new_spanned(
+                    nested,
+                    "fallback should be a path to a function",
+                )),
+            }
+        }
+        Meta::List(list) if !list.path.is_ident("fallback") => Err(Error::new_spanned(
+            &list.path,
+            format!("Unknown path {}", list.path.dump()),
+        )),
+        _ => Err(Error::new_spanned(
+            meta,
+            "Correct usage: #[router(\"/path\", services(a, b), fallback(handler))]",
+        )),
+    }
+}
+
+pub fn router(attr: TokenStream, item: TokenStream) -> Result<ItemFn, Error> {
+    let mut f: ItemFn = parse2(item)?;
+    let mut errors = Errors::default();
+    if !f.block.stmts.is_empty() {
+        errors.push(Error::new_spanned(
+            &f.block,
+            "#[router] function cannot have body",
+        ));
+    }
 
     let router_name = &f.sig.ident;
     let vis = &f.vis;
     let mut data_inputs: Punctuated<_, Token![,]> = Default::default();
 
-    let attr: Input = parse2(attr).expect("failed to parse input as Input { path , service }");
+    let attr: Input = parse2(attr).map_err(|err| {
+        Error::new(
+            err.span(),
+            "#[router(\"/path\", services(a, b, c,))] is correct usage",
+        )
+    })?;
+
+    // Path parameters of outer routers, which are passed to services.
+    let outer_params = crate::path::router_params(&f.sig);
+    for pat in &outer_params {
+        if !matches!(&*pat.pat, Pat::Ident(..)) {
+            errors.push(Error::new_spanned(
+                &pat.pat,
+                "#[router_param] can only be used with a simple identifier pattern",
+            ));
+        }
+    }
+    let outer_params = if outer_params.is_empty() {
+        None
+    } else {
+        Some(crate::path::uses_router_params(&outer_params))
+    };
+    let mut sig = f.sig.clone();
+    sig.inputs = sig
+        .inputs
+        .into_iter()
+        .filter(|arg| match arg {
+            FnArg::Typed(pat) => !pat.attrs.iter().any(crate::path::is_router_param),
+            FnArg::Receiver(..) => true,
+        })
+        .collect();
 
-    let (expr, path_vars) = crate::path::compile(None, attr.path.dump(), None, false);
+    let (expr, path_vars) = crate::path::compile(None, attr.path.dump(), Some(&sig), false)?;
+    let extracted = path_vars.len();
     let (expr, inputs, _) =
-        crate::route::param::compile(expr, &f.sig, &mut data_inputs, path_vars, false);
+        crate::route::param::compile(expr, &sig, &mut data_inputs, path_vars, false)?;
+    // Path parameters are extracted for each request, so they are not arguments
+    // of the router.
+    let inputs: Punctuated<FnArg, Token![,]> = inputs.into_iter().skip(extracted).collect();
+
+    // Path parameters of this router, which are made available to services.
+    let path = attr.path.value();
+    let segments: Vec<_> = crate::path::split_path(&path)
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect();
+    let mut scope_params = q!({ () });
+    for (idx, segment) in segments.iter().enumerate().rev() {
+        match crate::path::parse_segment(segment) {
+            Ok(Segment::Param { name, .. }) => {
+                // Reported by `path::compile`.
+                let ty = match crate::path::find_ty(&sig, name) {
+                    Some(ty) => ty,
+                    None => continue,
+                };
+                scope_params = q!(
+                    Vars {
+                        Name: crate::path::name_type(name),
+                        ty,
+                        idx,
+                        scope_params,
+                    },
+                    { (rweb::rt::RouterParam<Name, ty, idx>, scope_params) }
+                );
+            }
+            Ok(Segment::Tail { .. }) => errors.push(Error::new_spanned(
+                &attr.path,
+                "catch-all path parameters are not allowed in #[router]",
+            )),
+            _ => {}
+        }
+    }
+    let scope = match &outer_params {
+        Some(uses) => q!(
+            Vars {
+                scope_params,
+                segments: segments.len(),
+                uses,
+            },
+            {
+                rweb::rt::RouterScope::<scope_params, rweb::rt::OuterRouterParams<uses>, segments>::new(
+                    rweb::rt::OuterRouterParams::new(__router_param_offsets),
+                )
+            }
+        ),
+        None => q!(
+            Vars {
+                scope_params,
+                segments: segments.len(),
+            },
+            {
+                rweb::rt::RouterScope::<scope_params, rweb::rt::NoRouterParams, segments>::new(
+                    rweb::rt::NoRouterParams,
+                )
+            }
+        ),
+    };
 
     let mut exprs: Punctuated<Expr, Token![.]> = Punctuated::default();
 
     let args: Punctuated<Ident, _> = data_inputs
         .pairs()
-        .map(|pair| {
+        .filter_map(|pair| {
             let p = pair.punct().cloned();
             let t = pair.value();
 
             let t = match t {
                 FnArg::Typed(pat) => match &*pat.pat {
                     Pat::Ident(p) => p.ident.clone(),
-                    _ => unimplemented!("proper error reporting for non-ident #[data] input"),
+                    _ => {
+                        errors.push(Error::new_spanned(
+                            &pat.pat,
+                            "#[data] can only be used with a simple identifier pattern",
+                        ));
+                        return None;
+                    }
                 },
-                _ => unimplemented!("proper error reporting for non-ident #[data] input"),
+                FnArg::Receiver(r) => {
+                    errors.push(Error::new_spanned(r, "#[router] cannot take self"));
+                    return None;
+                }
             };
             //
-            Pair::new(t, p)
+            Some(Pair::new(t, p))
         })
         .collect();
 
-    let mut expr = compile_fn_attrs(expr, &mut f.attrs, false);
+    let mut expr = errors
+        .collect(compile_fn_attrs(expr.clone(), &mut f.attrs, false))
+        .unwrap_or(expr);
 
     match attr.services {
-        Meta::List(ref list) => {
-            if list.path.is_ident("services") {
-                for name in list.nested.iter() {
-                    if exprs.is_empty() {
-                        exprs.push(q!(Vars { name, args: &args }, { name(args) }).parse());
-                    } else {
-                        exprs.push(q!(Vars { name, args: &args }, { or(name(args)) }).parse());
+        Meta::List(ref list) if list.path.is_ident("services") => {
+            for name in list.nested.iter() {
+                // Missing path parameters are reported at the service.
+                let service = quote_spanned!(name.span()=>
+                    rweb::rt::mount(&__scope, #name(#args))
+                );
+                if exprs.is_empty() {
+                    exprs.push(q!(Vars { service }, { service }).parse());
+                } else {
+                    exprs.push(q!(Vars { service }, { or(service) }).parse());
+                }
+            }
+
+            let fallback = attr
+                .fallback
+                .as_ref()
+                .and_then(|meta| errors.collect(parse_fallback(meta)));
+            // Requests with a wrong method are handled before the fallback.
+            let exprs: Expr = q!(
+                Vars {
+                    exprs: &exprs,
+                    prefix: crate::path::route_segments(&path),
+                    scope: &scope,
+                },
+                {
+                    {
+                        let __scope = scope;
+                        let (__services, __routes) = rweb::rt::collect_routes(prefix, || exprs);
+                        rweb::rt::method_not_allowed(__routes, __services)
                     }
                 }
+            )
+            .parse();
+            let exprs: Expr = match fallback {
+                Some(fallback) => q!(Vars { exprs, fallback }, { exprs.recover(fallback) }).parse(),
+                None => exprs,
+            };
 
+            if list.nested.is_empty() {
+                errors.push(Error::new_spanned(
+                    list,
+                    "#[router] requires at least one service",
+                ));
+            } else if extracted == 0 {
                 expr = q!(Vars { exprs, expr }, { expr.and(exprs) }).parse();
             } else {
-                panic!("Unknown path {}", list.path.dump())
+                let ignored: Punctuated<Token![_], Token![,]> =
+                    (0..extracted).map(|_| <Token![_]>::default()).collect();
+                // Services extract the parameters they use.
+                expr = q!(
+                    Vars {
+                        exprs,
+                        expr,
+                        ignored,
+                    },
+                    { expr.and(exprs).map(|ignored, reply| reply) }
+                )
+                .parse();
             }
         }
 
-        _ => panic!("#[router(\"/path\", services(a, b, c,))] is correct usage"),
+        Meta::List(ref list) => errors.push(Error::new_spanned(
+            &list.path,
+            format!("Unknown path {}", list.path.dump()),
+        )),
+
+        ref services => errors.push(Error::new_spanned(
+            services,
+            "#[router(\"/path\", services(a, b, c,))] is correct usage",
+        )),
     }
 
-    let mut expr = compile_fn_attrs(expr, &mut f.attrs, true);
+    let mut expr = errors
+        .collect(compile_fn_attrs(expr.clone(), &mut f.attrs, true))
+        .unwrap_or(expr);
 
     if cfg!(feature = "openapi") {
-        let op = crate::openapi::parse(&attr.path.value(), &f.sig, &mut f.attrs);
+        let op = errors
+            .collect(crate::openapi::parse(&path, &f.sig, &mut f.attrs))
+            .unwrap_or_default();
         let tags: Punctuated<Quote, Token![,]> = op
             .tags
             .iter()
@@ -98,26 +299,50 @@ pub fn router(attr: TokenStream, item: TokenStream) -> ItemFn {
             })
             .collect();
 
+        let params: Punctuated<Expr, Token![,]> =
+            op.parameters.iter().map(quote_parameter).collect();
+        let security = crate::openapi::quote_security(&op.security);
+
         expr = q!(
             Vars {
                 tags,
-                path: &attr.path,
+                params,
+                security,
+                path: crate::path::strip_constraints(&path),
                 expr
             },
             {
-                rweb::openapi::with(|__collector: Option<&mut rweb::openapi::Collector>| {
-                    if let Some(__collector) = __collector {
-                        __collector.with_appended_prefix(path, vec![tags], || expr)
-                    } else {
-                        expr
-                    }
-                })
+                {
+                    // `expr` is emitted once, as each closure in it has a unique type.
+                    let __build = || expr;
+                    rweb::openapi::with(|__collector: Option<&mut rweb::openapi::Collector>| {
+                        if let Some(__collector) = __collector {
+                            let __params = vec![params];
+                            __collector.with_appended_prefix(
+                                path,
+                                vec![tags],
+                                __params,
+                                security,
+                                __build,
+                            )
+                        } else {
+                            __build()
+                        }
+                    })
+                }
             }
         )
         .parse();
     }
 
-    // TODO: Default handler
+    if outer_params.is_some() {
+        // The enclosing router checks that it has the parameters.
+        expr = q!(Vars { expr }, {
+            rweb::rt::WithRouterParams::new(move |__router_param_offsets: &[usize]| expr)
+        })
+        .parse();
+    }
+
     let mut ret = q!(Vars { expr, router_name }, {
         fn router_name(
         ) -> impl Clone + rweb::Filter<Extract = (impl rweb::Reply,), Error = rweb::Rejection>
@@ -129,9 +354,22 @@ pub fn router(attr: TokenStream, item: TokenStream) -> ItemFn {
     })
     .parse::<ItemFn>();
 
+    if let Some(uses) = outer_params {
+        let output = match &ret.sig.output {
+            ReturnType::Type(_, ty) => ty,
+            ReturnType::Default => unreachable!(),
+        };
+        ret.sig.output = q!(Vars { output, uses }, {
+            -> rweb::rt::WithRouterParams<output, uses>
+        })
+        .parse();
+    }
+
     ret.attrs = f.attrs;
     ret.sig.inputs = inputs;
     ret.vis = vis.clone();
 
-    ret
+    errors.finish()?;
+
+    Ok(ret)
 }
@@ -1,7 +1,7 @@
 use pmutil::{synom_ext::FromSpan, ToTokensExt};
 use proc_macro2::Span;
 use quote::quote;
-use syn::{punctuated::Pair, *};
+use syn::*;
 
 pub fn call_site<T: FromSpan>() -> T {
     T::from_span(Span::call_site())
@@ -106,16 +106,34 @@ impl ItemImplExt for ItemImpl {
     }
 }
 
-pub trait PairExt<T, P>: Sized + Into<Pair<T, P>> {
-    fn map_item<F, NewItem>(self, op: F) -> Pair<NewItem, P>
-    where
-        F: FnOnce(T) -> NewItem,
-    {
-        match self.into() {
-            Pair::Punctuated(t, p) => Pair::Punctuated(op(t), p),
-            Pair::End(t) => Pair::End(op(t)),
+/// Accumulates errors so that a single macro invocation can report every
+/// misuse at once instead of stopping at the first one.
+#[derive(Default)]
+pub(crate) struct Errors(Option<Error>);
+
+impl Errors {
+    pub fn push(&mut self, err: Error) {
+        match self.0 {
+            Some(ref mut prev) => prev.combine(err),
+            None => self.0 = Some(err),
+        }
+    }
+
+    /// Stores the error (if any) and returns the value on success.
+    pub fn collect<T>(&mut self, res: Result<T>) -> Option<T> {
+        match res {
+            Ok(v) => Some(v),
+            Err(err) => {
+                self.push(err);
+                None
+            }
         }
     }
-}
 
-impl<T, P> PairExt<T, P> for Pair<T, P> {}
+    pub fn finish(self) -> Result<()> {
+        match self.0 {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,296 @@
+//! Bearer authentication.
+//!
+//! [BearerToken] extracts the token of `Authorization: Bearer <token>`. If
+//! the `jwt` feature is enabled, [JwtKeys] verifies the token as a JWT signed
+//! with HS256 or RS256, and deserializes its claims.
+//!
+//! Requests without a valid token are rejected with
+//! [Unauthorized](crate::rejection::Unauthorized), which is replied as
+//! `401 Unauthorized` with a `WWW-Authenticate` header by
+//! [recover](crate::problem::recover).
+//!
+//! If the `openapi` feature is enabled, operations using [BearerToken]
+//! require the `bearer` security scheme, which is registered automatically,
+//! and document the `401` response.
+//!
+//! # Example
+//!
+//! ```rust
+//! use rweb::{auth::BearerToken, problem::recover, *};
+//!
+//! #[get("/token")]
+//! fn token(token: BearerToken) -> String {
+//!     token.into_inner()
+//! }
+//!
+//! fn main() {
+//!     serve(token().recover(recover));
+//! }
+//! ```
+
+#[cfg(feature = "jwt")]
+pub use self::jwt::{Claims, JwtError, JwtKey, JwtKeys};
+use crate::{rejection::Unauthorized, FromRequest};
+use futures::future::ready;
+use warp::{filters::BoxedFilter, reject, Filter};
+
+/// Token of `Authorization: Bearer <token>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BearerToken(String);
+
+impl BearerToken {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+
+    /// Parses the value of an `Authorization` header.
+    fn parse(authorization: Option<String>) -> Result<Self, Unauthorized> {
+        let value = match authorization {
+            Some(v) => v,
+            None => return Err(Unauthorized::missing("Bearer")),
+        };
+        let mut parts = value.trim().splitn(2, ' ');
+        match (parts.next(), parts.next().map(str::trim)) {
+            (Some(scheme), token) if scheme.eq_ignore_ascii_case("bearer") => match token {
+                Some(token) if !token.is_empty() => Ok(BearerToken(token.to_string())),
+                _ => Err(Unauthorized::invalid("Bearer", "empty token")),
+            },
+            // Credentials of other schemes are not ours to judge.
+            _ => Err(Unauthorized::missing("Bearer")),
+        }
+    }
+}
+
+impl FromRequest for BearerToken {
+    type Filter = BoxedFilter<(BearerToken,)>;
+
+    #[cfg(feature = "openapi")]
+    fn describe_operation(
+        collector: &mut crate::openapi::Collector,
+        op: &mut crate::openapi::Operation,
+        _: fn(&mut crate::openapi::ComponentDescriptor) -> crate::openapi::ComponentOrInlineSchema,
+    ) {
+        collector.add_security_requirement_to(op, "bearer", crate::openapi::security::bearer());
+        collector.add_response_to::<Unauthorized>(op);
+    }
+
+    fn new() -> Self::Filter {
+        warp::header::optional::<String>("authorization")
+            .and_then(|authorization: Option<String>| {
+                ready(BearerToken::parse(authorization).map_err(reject::custom))
+            })
+            .boxed()
+    }
+}
+
+#[cfg(feature = "jwt")]
+mod jwt {
+    use super::BearerToken;
+    use crate::rejection::Unauthorized;
+    use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+    use serde::de::DeserializeOwned;
+    use std::{
+        ops::{Deref, DerefMut},
+        sync::Arc,
+    };
+    use warp::{filters::BoxedFilter, reject, Filter, Rejection};
+
+    pub use jsonwebtoken::errors::Error as JwtError;
+
+    /// A key verifying JWTs.
+    #[derive(Clone)]
+    pub struct JwtKey {
+        id: Option<String>,
+        algorithm: Algorithm,
+        key: DecodingKey,
+    }
+
+    impl JwtKey {
+        /// HS256 with a shared secret.
+        pub fn hs256(secret: &[u8]) -> Self {
+            JwtKey {
+                id: None,
+                algorithm: Algorithm::HS256,
+                key: DecodingKey::from_secret(secret),
+            }
+        }
+
+        /// RS256 with a PEM encoded public key.
+        pub fn rs256_pem(pem: &[u8]) -> Result<Self, JwtError> {
+            Ok(JwtKey {
+                id: None,
+                algorithm: Algorithm::RS256,
+                key: DecodingKey::from_rsa_pem(pem)?,
+            })
+        }
+
+        /// Sets the id of the key, which is matched against `kid` of tokens.
+        pub fn with_id(mut self, id: impl Into<String>) -> Self {
+            self.id = Some(id.into());
+            self
+        }
+
+        fn matches(&self, algorithm: Algorithm, id: Option<&str>) -> bool {
+            self.algorithm == algorithm
+                && match (&self.id, id) {
+                    (Some(a), Some(b)) => a == b,
+                    _ => true,
+                }
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct KeySet {
+        keys: Vec<JwtKey>,
+        issuers: Vec<String>,
+        audiences: Vec<String>,
+        leeway: u64,
+    }
+
+    /// Keys verifying JWTs, which is cheap to clone so that it can be passed
+    /// to handlers with `#[data]`.
+    ///
+    /// A token is verified with the keys of its algorithm, and its `kid` if
+    /// it has one. Expiration is always checked, and issuer and audience if
+    /// they are configured.
+    ///
+    /// ```rust
+    /// use rweb::{
+    ///     auth::{BearerToken, Claims, JwtKey, JwtKeys},
+    ///     problem::recover,
+    ///     *,
+    /// };
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct User {
+    ///     sub: String,
+    /// }
+    ///
+    /// #[get("/me")]
+    /// async fn me(#[data] keys: JwtKeys, token: BearerToken) -> Result<String, Rejection> {
+    ///     let user: Claims<User> = keys.verify(&token)?;
+    ///     Ok(user.sub.clone())
+    /// }
+    ///
+    /// fn main() {
+    ///     let keys = JwtKeys::new()
+    ///         .key(JwtKey::hs256(b"secret"))
+    ///         .issuer("https://example.com");
+    ///     serve(me(keys).recover(recover));
+    /// }
+    /// ```
+    #[derive(Clone, Default)]
+    pub struct JwtKeys(Arc<KeySet>);
+
+    impl JwtKeys {
+        pub fn new() -> Self {
+            Default::default()
+        }
+
+        pub fn key(mut self, key: JwtKey) -> Self {
+            Arc::make_mut(&mut self.0).keys.push(key);
+            self
+        }
+
+        /// Accepts tokens issued by `issuer`. Any issuer is accepted if none
+        /// is configured.
+        pub fn issuer(mut self, issuer: impl Into<String>) -> Self {
+            Arc::make_mut(&mut self.0).issuers.push(issuer.into());
+            self
+        }
+
+        /// Accepts tokens for `audience`. Any audience is accepted if none is
+        /// configured.
+        pub fn audience(mut self, audience: impl Into<String>) -> Self {
+            Arc::make_mut(&mut self.0).audiences.push(audience.into());
+            self
+        }
+
+        /// Leeway of time based claims in seconds.
+        pub fn leeway(mut self, seconds: u64) -> Self {
+            Arc::make_mut(&mut self.0).leeway = seconds;
+            self
+        }
+
+        /// Verifies `token`, and deserializes its claims.
+        pub fn verify<T>(&self, token: &BearerToken) -> Result<Claims<T>, Rejection>
+        where
+            T: DeserializeOwned,
+        {
+            let invalid =
+                |description: String| reject::custom(Unauthorized::invalid("Bearer", description));
+
+            let header = decode_header(token.as_str()).map_err(|err| invalid(err.to_string()))?;
+
+            let mut last_err = None;
+            for key in &self.0.keys {
+                if !key.matches(header.alg, header.kid.as_deref()) {
+                    continue;
+                }
+
+                match decode::<T>(token.as_str(), &key.key, &self.validation(key.algorithm)) {
+                    Ok(data) => return Ok(Claims(data.claims)),
+                    Err(err) => last_err = Some(err),
+                }
+            }
+
+            Err(invalid(match last_err {
+                Some(err) => err.to_string(),
+                None => "no key for the token".into(),
+            }))
+        }
+
+        /// Creates a filter extracting verified claims.
+        pub fn claims<T>(&self) -> BoxedFilter<(Claims<T>,)>
+        where
+            T: 'static + Send + DeserializeOwned,
+        {
+            let keys = self.clone();
+            <BearerToken as crate::FromRequest>::new()
+                .and_then(move |token: BearerToken| futures::future::ready(keys.verify(&token)))
+                .boxed()
+        }
+
+        fn validation(&self, algorithm: Algorithm) -> Validation {
+            let mut validation = Validation::new(algorithm);
+            validation.leeway = self.0.leeway;
+            validation.validate_aud = !self.0.audiences.is_empty();
+            if !self.0.audiences.is_empty() {
+                validation.set_audience(&self.0.audiences);
+            }
+            if !self.0.issuers.is_empty() {
+                validation.set_issuer(&self.0.issuers);
+            }
+            validation
+        }
+    }
+
+    /// Verified claims of a JWT.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct Claims<T>(T);
+
+    impl<T> Claims<T> {
+        pub fn into_inner(self) -> T {
+            self.0
+        }
+    }
+
+    impl<T> Deref for Claims<T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            &self.0
+        }
+    }
+
+    impl<T> DerefMut for Claims<T> {
+        fn deref_mut(&mut self) -> &mut T {
+            &mut self.0
+        }
+    }
+}
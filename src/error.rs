@@ -0,0 +1,148 @@
+#[cfg(feature = "openapi")]
+use crate::openapi::{self, ComponentDescriptor, Entity, MediaType, Responses};
+use http::StatusCode;
+#[cfg(feature = "openapi")]
+use indexmap::IndexMap;
+use serde::Serialize;
+#[cfg(feature = "openapi")]
+use std::borrow::Cow;
+use warp::{
+    reply::{json, with_status, Response},
+    Reply,
+};
+
+/// An error which can be returned from a handler, like
+/// `Result<Json<T>, MyError>`.
+///
+/// The error is converted into a response with the status code returned by
+/// [ResponseError::status_code] and the error serialized as json.
+///
+/// Note that the return type of the handler should be written as
+/// `Result<T, E>` for the conversion to work. Aliases with another number of
+/// generic arguments, like `io::Result<T>`, are rejected at compile time.
+///
+/// The filter created for a synchronous handler returning `Result<T, E>`
+/// extracts a [Response], whatever `E` is. Before this conversion existed,
+/// a handler returning e.g. `Result<T, http::Error>` extracted the `Result`
+/// itself, so code naming that type has to be updated. The response sent to
+/// the client is the same.
+///
+/// # Example
+///
+/// ```rust
+/// use rweb::*;
+/// use serde::Serialize;
+///
+/// #[derive(Debug, Serialize, Schema)]
+/// struct NotFound {
+///     message: String,
+/// }
+///
+/// impl ResponseError for NotFound {
+///     fn status_code(&self) -> http::StatusCode {
+///         http::StatusCode::NOT_FOUND
+///     }
+///
+///     fn status_codes() -> Vec<http::StatusCode> {
+///         vec![http::StatusCode::NOT_FOUND]
+///     }
+/// }
+///
+/// #[get("/users/{id}")]
+/// fn user(id: u32) -> Result<Json<String>, NotFound> {
+///     Err(NotFound {
+///         message: format!("user {} does not exist", id),
+///     })
+/// }
+/// ```
+///
+/// If the `openapi` feature is enabled and the error type implements
+/// [Entity](crate::openapi::Entity), a response is documented for each status
+/// code in [ResponseError::status_codes]. Error types implementing neither
+/// [Entity](crate::openapi::Entity) nor
+/// [ResponseEntity](crate::openapi::ResponseEntity) are not documented.
+///
+/// # Deriving
+///
+/// `#[derive(ResponseError)]` implements this trait and [Reply] for enums,
+/// using the status code of `#[response(..)]` on each variant.
+///
+/// With the `openapi` feature, the type should also derive
+/// [Schema](crate::Schema) to be documented: `#[derive(Schema)]` then
+/// implements [ResponseEntity](crate::openapi::ResponseEntity), documenting
+/// each response with its description and the schemas of the variants.
+/// [ResponseEntity](crate::openapi::ResponseEntity) requires
+/// [Entity](crate::openapi::Entity), so it is not implemented by
+/// `#[derive(ResponseError)]` alone.
+///
+/// ```rust
+/// use rweb::*;
+/// use serde::Serialize;
+///
+/// #[derive(Debug, Serialize, Schema, ResponseError)]
+/// enum Error {
+///     #[response(status = 404, description = "Item not found")]
+///     NotFound { id: u32 },
+///     /// The item is locked by another user.
+///     #[response(status = 409)]
+///     Locked,
+///     #[response(status = 500)]
+///     Internal(String),
+/// }
+/// ```
+pub trait ResponseError: Serialize + Send {
+    /// Status code of the response.
+    fn status_code(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+
+    /// All status codes [ResponseError::status_code] can return. Used to
+    /// document the error.
+    fn status_codes() -> Vec<StatusCode>
+    where
+        Self: Sized,
+    {
+        vec![StatusCode::INTERNAL_SERVER_ERROR]
+    }
+
+    /// Creates a response from the error.
+    fn error_response(&self) -> Response
+    where
+        Self: Sized,
+    {
+        with_status(json(self), self.status_code()).into_response()
+    }
+
+    /// Describes the responses for [ResponseError::status_codes].
+    #[cfg(feature = "openapi")]
+    #[doc(hidden)]
+    fn describe_error_responses(comp_d: &mut ComponentDescriptor) -> Responses
+    where
+        Self: Sized + Entity,
+    {
+        let schema = Self::describe(comp_d);
+        Self::status_codes()
+            .into_iter()
+            .map(|status| {
+                let mut content = IndexMap::new();
+                content.insert(
+                    Cow::Borrowed("application/json"),
+                    MediaType {
+                        schema: Some(schema.clone()),
+                        examples: None,
+                        encoding: Default::default(),
+                    },
+                );
+
+                (
+                    Cow::Owned(status.as_str().to_string()),
+                    openapi::Response {
+                        description: Cow::Borrowed(status.canonical_reason().unwrap_or_default()),
+                        content,
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect()
+    }
+}
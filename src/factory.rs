@@ -1,13 +1,23 @@
-use futures::future::ok;
+use crate::{
+    form::{FormStyle, Repeat, Style},
+    negotiation::Format,
+    rejection::{BodyDeserializeError, UnsupportedMediaType},
+    validation::{Constraints, Validate, Violation, Violations},
+};
+#[cfg(feature = "openapi")]
+use crate::openapi::{Collector, ComponentDescriptor, ComponentOrInlineSchema, Operation};
+use futures::future::{ok, ready};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::marker::PhantomData;
 #[cfg(feature = "multipart")]
 use warp::filters::multipart;
 #[cfg(feature = "websocket")]
 use warp::filters::ws::Ws;
 use warp::{
     filters::BoxedFilter,
+    hyper::body::Bytes,
     reply::{json, Response},
-    Filter, Rejection, Reply,
+    reject, Filter, Rejection, Reply,
 };
 
 pub trait FromRequest: Sized {
@@ -35,10 +45,50 @@ pub trait FromRequest: Sized {
         false
     }
 
+    /// It's true iff the request is validated, and rejected with
+    /// [Violations] if invalid.
+    ///
+    /// It returns true for `Validated<T>`.
+    fn is_validated() -> bool {
+        false
+    }
+
     fn content_type() -> &'static str {
         "*/*"
     }
 
+    /// All media types accepted as the body, which are documented with the
+    /// same schema.
+    ///
+    /// It returns [FromRequest::content_type] by default, and all formats of
+    /// [Format] for `Body<T>`.
+    fn content_types() -> Vec<&'static str> {
+        vec![Self::content_type()]
+    }
+
+    /// Style of arrays and nested objects in a form body.
+    ///
+    /// It returns `Some` for `Form<T, S>`.
+    fn form_style() -> Option<FormStyle> {
+        None
+    }
+
+    /// Documents the type in `op`. `schema` is
+    /// [Entity::describe](crate::openapi::Entity::describe) of the type.
+    ///
+    /// By default, the request body, query parameters and the response of
+    /// [Violations] are documented according to the methods above. Types
+    /// reading headers or credentials override it to add parameters,
+    /// security requirements or responses.
+    #[cfg(feature = "openapi")]
+    fn describe_operation(
+        collector: &mut Collector,
+        op: &mut Operation,
+        schema: fn(&mut ComponentDescriptor) -> ComponentOrInlineSchema,
+    ) {
+        collector.describe_request::<Self>(op, schema)
+    }
+
     fn new() -> Self::Filter;
 }
 
@@ -61,6 +111,45 @@ where
         T::is_query()
     }
 
+    fn content_type() -> &'static str {
+        T::content_type()
+    }
+
+    fn content_types() -> Vec<&'static str> {
+        T::content_types()
+    }
+
+    fn form_style() -> Option<FormStyle> {
+        T::form_style()
+    }
+
+    /// Documents `T`, but nothing added by `T` is required.
+    #[cfg(feature = "openapi")]
+    fn describe_operation(
+        collector: &mut Collector,
+        op: &mut Operation,
+        schema: fn(&mut ComponentDescriptor) -> ComponentOrInlineSchema,
+    ) {
+        use crate::openapi::{ObjectOrReference, SecurityRequirement};
+
+        let parameters = op.parameters.len();
+        let security = op.security.len();
+        T::describe_operation(collector, op, schema);
+
+        if let Some(ObjectOrReference::Object(body)) = &mut op.request_body {
+            body.required = Some(false);
+        }
+        for param in &mut op.parameters[parameters..] {
+            if let ObjectOrReference::Object(param) = param {
+                param.required = Some(false);
+            }
+        }
+        // An empty requirement makes the authentication optional.
+        if op.security.len() > security && !op.security.iter().any(|r| r.is_empty()) {
+            op.security.push(SecurityRequirement::new());
+        }
+    }
+
     fn new() -> Self::Filter {
         T::new()
             .map(Some)
@@ -113,6 +202,24 @@ where
     }
 }
 
+impl<T> Constraints for Json<T>
+where
+    T: Constraints,
+{
+    fn check_constraints(&self, path: &str, violations: &mut Vec<Violation>) {
+        self.0.check_constraints(path, violations)
+    }
+}
+
+impl<T> Validate for Json<T>
+where
+    T: Validate,
+{
+    fn validate(&self) -> Result<(), Violations> {
+        self.0.validate()
+    }
+}
+
 impl<T> Reply for Json<T>
 where
     T: Serialize + Send,
@@ -122,33 +229,76 @@ where
     }
 }
 
-/// Represents a request body with `www-url-form-encoded` content type.
+/// Represents a request body with `application/x-www-form-urlencoded` content
+/// type.
+///
+/// `S` selects how arrays and nested objects are encoded. See
+/// [form](crate::form) for details.
+///
+/// Requests with a `Content-Type` other than
+/// `application/x-www-form-urlencoded` are rejected with
+/// [UnsupportedMediaType](crate::rejection::UnsupportedMediaType), and invalid
+/// bodies with [BodyDeserializeError](crate::rejection::BodyDeserializeError).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
 #[serde(transparent)]
-pub struct Form<T>(T);
+pub struct Form<T, S = Repeat>(T, #[serde(skip)] PhantomData<S>);
 
-impl<T> Form<T> {
+impl<T, S> Form<T, S> {
     pub fn into_inner(self) -> T {
         self.0
     }
 }
 
-impl<T> FromRequest for Form<T>
+impl<T, S> FromRequest for Form<T, S>
 where
     T: 'static + Send + DeserializeOwned,
+    S: Style,
 {
-    type Filter = BoxedFilter<(Form<T>,)>;
+    type Filter = BoxedFilter<(Form<T, S>,)>;
 
     fn is_body() -> bool {
         true
     }
 
     fn content_type() -> &'static str {
-        "x-www-form-urlencoded"
+        "application/x-www-form-urlencoded"
+    }
+
+    fn form_style() -> Option<FormStyle> {
+        Some(S::FORM_STYLE)
     }
 
     fn new() -> Self::Filter {
-        warp::body::form().boxed()
+        Format::Form
+            .check_content_type()
+            .and(warp::body::bytes())
+            .and_then(|body: Bytes| {
+                ready(
+                    S::FORM_STYLE
+                        .deserialize(&body)
+                        .map(|v| Form(v, PhantomData))
+                        .map_err(|err| reject::custom(BodyDeserializeError::new(err))),
+                )
+            })
+            .boxed()
+    }
+}
+
+impl<T, S> Constraints for Form<T, S>
+where
+    T: Constraints,
+{
+    fn check_constraints(&self, path: &str, violations: &mut Vec<Violation>) {
+        self.0.check_constraints(path, violations)
+    }
+}
+
+impl<T, S> Validate for Form<T, S>
+where
+    T: Validate,
+{
+    fn validate(&self) -> Result<(), Violations> {
+        self.0.validate()
     }
 }
 
@@ -178,6 +328,312 @@ where
     }
 }
 
+impl<T> Constraints for Query<T>
+where
+    T: Constraints,
+{
+    fn check_constraints(&self, path: &str, violations: &mut Vec<Violation>) {
+        self.0.check_constraints(path, violations)
+    }
+}
+
+impl<T> Validate for Query<T>
+where
+    T: Validate,
+{
+    fn validate(&self) -> Result<(), Violations> {
+        self.0.validate()
+    }
+}
+
+/// Extracts `E` and validates it with [Validate].
+///
+/// Requests failing validation are rejected with [Violations], which is a
+/// `422 Unprocessable Entity` response as a [Reply]. If the `openapi` feature
+/// is enabled, the response is documented.
+///
+/// `E` is usually [Json], [Form] or [Query], which implements [Validate] if
+/// the inner type does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Validated<E>(E);
+
+impl<E> Validated<E> {
+    pub fn into_inner(self) -> E {
+        self.0
+    }
+}
+
+impl<E> FromRequest for Validated<E>
+where
+    E: 'static + FromRequest + Validate + Send,
+    E::Filter: Filter<Extract = (E,), Error = Rejection> + Send + Sync + 'static,
+{
+    type Filter = BoxedFilter<(Validated<E>,)>;
+
+    fn is_body() -> bool {
+        E::is_body()
+    }
+
+    fn is_optional() -> bool {
+        E::is_optional()
+    }
+
+    fn is_query() -> bool {
+        E::is_query()
+    }
+
+    fn is_validated() -> bool {
+        true
+    }
+
+    fn content_type() -> &'static str {
+        E::content_type()
+    }
+
+    fn content_types() -> Vec<&'static str> {
+        E::content_types()
+    }
+
+    fn form_style() -> Option<FormStyle> {
+        E::form_style()
+    }
+
+    #[cfg(feature = "openapi")]
+    fn describe_operation(
+        collector: &mut Collector,
+        op: &mut Operation,
+        schema: fn(&mut ComponentDescriptor) -> ComponentOrInlineSchema,
+    ) {
+        E::describe_operation(collector, op, schema);
+        collector.add_response_to::<Violations>(op);
+    }
+
+    fn new() -> Self::Filter {
+        E::new()
+            .and_then(|v: E| {
+                ready(match v.validate() {
+                    Ok(()) => Ok(Validated(v)),
+                    Err(err) => Err(reject::custom(err)),
+                })
+            })
+            .boxed()
+    }
+}
+
+impl<E> Constraints for Validated<E>
+where
+    E: Constraints,
+{
+    fn check_constraints(&self, path: &str, violations: &mut Vec<Violation>) {
+        self.0.check_constraints(path, violations)
+    }
+}
+
+/// Represents a request body in any format of [Format], selected by the
+/// `Content-Type` of the request.
+///
+/// Json is used if the request does not have a `Content-Type`, and other
+/// content types are rejected with
+/// [UnsupportedMediaType](crate::rejection::UnsupportedMediaType). If the
+/// `openapi` feature is enabled, all formats are documented.
+///
+/// ```rust
+/// use rweb::*;
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize, Schema)]
+/// struct Login {
+///     name: String,
+///     password: String,
+/// }
+///
+/// /// Accepts both `application/json` and `application/x-www-form-urlencoded`.
+/// #[post("/login")]
+/// fn login(body: Body<Login>) -> String {
+///     body.into_inner().name
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(transparent)]
+pub struct Body<T>(T);
+
+impl<T> Body<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> FromRequest for Body<T>
+where
+    T: 'static + Send + DeserializeOwned,
+{
+    type Filter = BoxedFilter<(Body<T>,)>;
+
+    fn is_body() -> bool {
+        true
+    }
+
+    fn content_type() -> &'static str {
+        Format::Json.media_type()
+    }
+
+    fn content_types() -> Vec<&'static str> {
+        Format::all().iter().map(|f| f.media_type()).collect()
+    }
+
+    fn new() -> Self::Filter {
+        warp::header::optional::<String>("content-type")
+            .and_then(|content_type: Option<String>| {
+                ready(match content_type {
+                    None => Ok(Format::Json),
+                    Some(ct) => Format::from_content_type(&ct).ok_or_else(|| {
+                        reject::custom(UnsupportedMediaType::new(ct, Self::content_types()))
+                    }),
+                })
+            })
+            .and(warp::body::bytes())
+            .and_then(|format: Format, body: Bytes| {
+                ready(
+                    format
+                        .deserialize(&body)
+                        .map(Body)
+                        .map_err(|err| reject::custom(BodyDeserializeError::new(err))),
+                )
+            })
+            .boxed()
+    }
+}
+
+impl<T> Constraints for Body<T>
+where
+    T: Constraints,
+{
+    fn check_constraints(&self, path: &str, violations: &mut Vec<Violation>) {
+        self.0.check_constraints(path, violations)
+    }
+}
+
+impl<T> Validate for Body<T>
+where
+    T: Validate,
+{
+    fn validate(&self) -> Result<(), Violations> {
+        self.0.validate()
+    }
+}
+
+/// Defines a request body or response serialized in a format of
+/// [Format](crate::negotiation::Format).
+macro_rules! body_type {
+    ($(#[$attr:meta])* $feature:literal, $T:ident, $format:expr) => {
+        $(#[$attr])*
+        #[cfg(feature = $feature)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $T<T>(T);
+
+        #[cfg(feature = $feature)]
+        impl<T> $T<T> {
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl<T> From<T> for $T<T> {
+            #[inline]
+            fn from(v: T) -> Self {
+                $T(v)
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl<T> FromRequest for $T<T>
+        where
+            T: 'static + Send + DeserializeOwned,
+        {
+            type Filter = BoxedFilter<($T<T>,)>;
+
+            fn is_body() -> bool {
+                true
+            }
+
+            fn content_type() -> &'static str {
+                $format.media_type()
+            }
+
+            fn new() -> Self::Filter {
+                $format.body().map($T).boxed()
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl<T> Constraints for $T<T>
+        where
+            T: Constraints,
+        {
+            fn check_constraints(&self, path: &str, violations: &mut Vec<Violation>) {
+                self.0.check_constraints(path, violations)
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl<T> Validate for $T<T>
+        where
+            T: Validate,
+        {
+            fn validate(&self) -> Result<(), Violations> {
+                self.0.validate()
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl<T> Reply for $T<T>
+        where
+            T: Serialize + Send,
+        {
+            fn into_response(self) -> Response {
+                $format.reply(&self.0)
+            }
+        }
+    };
+}
+
+body_type!(
+    /// Represents a request body or response with `application/msgpack`
+    /// content type.
+    "msgpack",
+    MsgPack,
+    Format::MsgPack
+);
+
+body_type!(
+    /// Represents a request body or response with `application/cbor` content
+    /// type.
+    "cbor",
+    Cbor,
+    Format::Cbor
+);
+
+body_type!(
+    /// Represents a request body or response with `application/yaml` content
+    /// type.
+    "yaml",
+    Yaml,
+    Format::Yaml
+);
+
+body_type!(
+    /// Represents a request body or response with `application/xml` content
+    /// type.
+    ///
+    /// As xml requires a root element, values are serialized as an element
+    /// named after their type.
+    "xml",
+    Xml,
+    Format::Xml
+);
+
 #[cfg(feature = "websocket")]
 impl FromRequest for Ws {
     type Filter = BoxedFilter<(Ws,)>;
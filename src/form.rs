@@ -0,0 +1,88 @@
+//! Styles of `application/x-www-form-urlencoded` bodies.
+//!
+//! `application/x-www-form-urlencoded` has no standard encoding for arrays
+//! and nested objects, so [Form](crate::Form) takes the style as a type
+//! parameter. It's used while extracting the body, and documented as the
+//! `encoding` of array and object fields if the `openapi` feature is enabled.
+//!
+//! # Example
+//!
+//! ```rust
+//! use rweb::{form::Brackets, *};
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, Deserialize, Schema)]
+//! struct Address {
+//!     city: String,
+//! }
+//!
+//! #[derive(Debug, Deserialize, Schema)]
+//! struct Signup {
+//!     name: String,
+//!     tags: Vec<String>,
+//!     address: Address,
+//! }
+//!
+//! /// Accepts `name=a&tags[0]=b&tags[1]=c&address[city]=d`.
+//! #[post("/signup")]
+//! fn signup(body: Form<Signup, Brackets>) -> String {
+//!     body.into_inner().name
+//! }
+//! ```
+
+use serde::de::DeserializeOwned;
+use std::error::Error;
+
+/// How arrays and nested objects are encoded in a form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FormStyle {
+    /// Arrays repeat the key, like `tags=a&tags=b`. Nested objects are not
+    /// supported.
+    ///
+    /// Arrays are documented as `style: form` with `explode: true`.
+    ///
+    /// Bodies are parsed with `serde_html_form` instead of
+    /// `serde_urlencoded`, which `warp::body::form` uses, so that sequences
+    /// are supported.
+    Repeat,
+    /// Arrays and nested objects use brackets, like
+    /// `tags[0]=a&tags[1]=b&address[city]=c`. `tags[]=a` is also accepted.
+    ///
+    /// Arrays and objects are documented as `style: deepObject` with
+    /// `explode: true`.
+    Brackets,
+}
+
+impl FormStyle {
+    pub fn deserialize<T>(self, body: &[u8]) -> Result<T, Box<dyn Error + Send + Sync>>
+    where
+        T: DeserializeOwned,
+    {
+        Ok(match self {
+            FormStyle::Repeat => serde_html_form::from_bytes(body)?,
+            // Browsers percent-encode brackets, so strict mode is disabled.
+            FormStyle::Brackets => serde_qs::Config::new(5, false).deserialize_bytes(body)?,
+        })
+    }
+}
+
+/// Selects the [FormStyle] of [Form](crate::Form) at type level.
+pub trait Style: 'static + Send + Sync {
+    const FORM_STYLE: FormStyle;
+}
+
+/// [FormStyle::Repeat], which is the default.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Repeat;
+
+impl Style for Repeat {
+    const FORM_STYLE: FormStyle = FormStyle::Repeat;
+}
+
+/// [FormStyle::Brackets].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Brackets;
+
+impl Style for Brackets {
+    const FORM_STYLE: FormStyle = FormStyle::Brackets;
+}
@@ -0,0 +1,547 @@
+//! Typed headers.
+//!
+//! [TypedHeader] extracts a header implementing [Header], and [WithHeader]
+//! adds one to a reply. [HeaderMap] can also be extracted to read all headers
+//! of a request.
+//!
+//! A missing or invalid header is rejected with
+//! [InvalidHeader](crate::rejection::InvalidHeader), which is replied as
+//! `400 Bad Request` by [recover](crate::problem::recover). Use
+//! `Option<TypedHeader<H>>` for an optional header.
+//!
+//! If the `openapi` feature is enabled, [TypedHeader] is documented as a
+//! header parameter and [WithHeader] as a header of the responses, with the
+//! schema of [Header::describe]. [HeaderMap] is not documented.
+//!
+//! # Example
+//!
+//! ```rust
+//! use rweb::{
+//!     headers::{ETag, IfNoneMatch, TypedHeader, UserAgent, WithHeader},
+//!     *,
+//! };
+//!
+//! #[get("/resource")]
+//! fn resource(
+//!     user_agent: TypedHeader<UserAgent>,
+//!     if_none_match: Option<TypedHeader<IfNoneMatch>>,
+//! ) -> WithHeader<String, ETag> {
+//!     let etag = ETag::strong("v1");
+//!     let cached = match if_none_match {
+//!         Some(h) => h.into_inner().matches(&etag),
+//!         None => false,
+//!     };
+//!     let body = format!("{} {}", user_agent.into_inner().as_str(), cached);
+//!     WithHeader::new(body, etag)
+//! }
+//!
+//! fn main() {
+//!     serve(resource());
+//! }
+//! ```
+
+use crate::{problem::Problem, rejection::InvalidHeader, FromRequest};
+use futures::future::ready;
+pub use http::HeaderMap;
+use http::{
+    header::{HeaderName, InvalidHeaderValue},
+    HeaderValue, StatusCode,
+};
+use std::{error::Error, fmt::Write, iter::once};
+use warp::{filters::BoxedFilter, reject, reply::Response, Filter, Reply};
+
+/// A header which can be decoded from and encoded into [HeaderValue].
+pub trait Header: Sized {
+    /// Name of the header, in lowercase.
+    const NAME: &'static str;
+
+    /// Parses the value of the header. Multiple lines of the header are
+    /// joined with `, `.
+    fn decode(value: &HeaderValue) -> Result<Self, Box<dyn Error + Send + Sync>>;
+
+    fn encode(&self) -> Result<HeaderValue, InvalidHeaderValue>;
+
+    /// Schema of the value, which is a string by default.
+    #[cfg(feature = "openapi")]
+    fn describe(
+        comp_d: &mut crate::openapi::ComponentDescriptor,
+    ) -> crate::openapi::ComponentOrInlineSchema {
+        <String as crate::openapi::Entity>::describe(comp_d)
+    }
+}
+
+/// Extracts the header `H`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypedHeader<H>(H);
+
+impl<H> TypedHeader<H> {
+    pub fn into_inner(self) -> H {
+        self.0
+    }
+}
+
+impl<H> From<H> for TypedHeader<H> {
+    #[inline]
+    fn from(header: H) -> Self {
+        TypedHeader(header)
+    }
+}
+
+impl<H> FromRequest for TypedHeader<H>
+where
+    H: 'static + Header + Send,
+{
+    type Filter = BoxedFilter<(TypedHeader<H>,)>;
+
+    #[cfg(feature = "openapi")]
+    fn describe_operation(
+        collector: &mut crate::openapi::Collector,
+        op: &mut crate::openapi::Operation,
+        schema: fn(
+            &mut crate::openapi::ComponentDescriptor,
+        ) -> crate::openapi::ComponentOrInlineSchema,
+    ) {
+        use crate::openapi::{Location, ObjectOrReference, Parameter, ParameterRepresentation};
+        use std::borrow::Cow;
+
+        op.parameters.push(ObjectOrReference::Object(Parameter {
+            name: Cow::Borrowed(H::NAME),
+            location: Location::Header,
+            required: Some(true),
+            representation: Some(ParameterRepresentation::Simple {
+                schema: schema(collector.components()),
+            }),
+            ..Default::default()
+        }));
+    }
+
+    fn new() -> Self::Filter {
+        warp::header::headers_cloned()
+            .and_then(|headers: HeaderMap| {
+                ready(
+                    decode::<H>(&headers)
+                        .map(TypedHeader)
+                        .map_err(reject::custom),
+                )
+            })
+            .boxed()
+    }
+}
+
+fn decode<H: Header>(headers: &HeaderMap) -> Result<H, InvalidHeader> {
+    let mut values = headers.get_all(H::NAME).iter();
+    let first = values
+        .next()
+        .ok_or_else(|| InvalidHeader::new(H::NAME, "missing header"))?;
+
+    let decoded = match values.next() {
+        None => H::decode(first),
+        Some(second) => {
+            let mut joined = first.as_bytes().to_vec();
+            for value in once(second).chain(values) {
+                joined.extend_from_slice(b", ");
+                joined.extend_from_slice(value.as_bytes());
+            }
+            HeaderValue::from_bytes(&joined)
+                .map_err(From::from)
+                .and_then(|value| H::decode(&value))
+        }
+    };
+    decoded.map_err(|err| InvalidHeader::new(H::NAME, err.to_string()))
+}
+
+/// Extracts all headers of the request.
+impl FromRequest for HeaderMap {
+    type Filter = BoxedFilter<(HeaderMap,)>;
+
+    fn new() -> Self::Filter {
+        warp::header::headers_cloned().boxed()
+    }
+}
+
+/// A reply with the header `H`.
+///
+/// If the header cannot be encoded, `500 Internal Server Error` is replied.
+#[derive(Debug, Clone)]
+pub struct WithHeader<T, H> {
+    reply: T,
+    header: H,
+}
+
+impl<T, H> WithHeader<T, H> {
+    pub fn new(reply: T, header: H) -> Self {
+        WithHeader { reply, header }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.reply
+    }
+}
+
+impl<T, H> Reply for WithHeader<T, H>
+where
+    T: Reply,
+    H: Header + Send,
+{
+    fn into_response(self) -> Response {
+        let value = match self.header.encode() {
+            Ok(value) => value,
+            Err(..) => return Problem::new(StatusCode::INTERNAL_SERVER_ERROR).into_response(),
+        };
+
+        let mut res = self.reply.into_response();
+        res.headers_mut()
+            .insert(HeaderName::from_static(H::NAME), value);
+        res
+    }
+}
+
+fn to_str(value: &HeaderValue) -> Result<&str, Box<dyn Error + Send + Sync>> {
+    Ok(value.to_str()?.trim())
+}
+
+/// `Authorization: <scheme> <credentials>`.
+///
+/// See [BearerToken](crate::auth::BearerToken) for bearer tokens.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Authorization {
+    scheme: String,
+    credentials: String,
+}
+
+impl Authorization {
+    pub fn new(scheme: impl Into<String>, credentials: impl Into<String>) -> Self {
+        Authorization {
+            scheme: scheme.into(),
+            credentials: credentials.into(),
+        }
+    }
+
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    pub fn credentials(&self) -> &str {
+        &self.credentials
+    }
+}
+
+impl Header for Authorization {
+    const NAME: &'static str = "authorization";
+
+    fn decode(value: &HeaderValue) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let mut parts = to_str(value)?.splitn(2, ' ');
+        match (parts.next(), parts.next()) {
+            (Some(scheme), credentials) if !scheme.is_empty() => Ok(Authorization::new(
+                scheme,
+                credentials.unwrap_or_default().trim(),
+            )),
+            _ => Err("missing scheme".into()),
+        }
+    }
+
+    fn encode(&self) -> Result<HeaderValue, InvalidHeaderValue> {
+        HeaderValue::from_str(&format!("{} {}", self.scheme, self.credentials))
+    }
+}
+
+/// `Content-Type`, like `application/json; charset=utf-8`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContentType(String);
+
+impl ContentType {
+    pub fn new(value: impl Into<String>) -> Self {
+        ContentType(value.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The media type without parameters, like `application/json`.
+    pub fn media_type(&self) -> &str {
+        self.0.split(';').next().unwrap_or_default().trim()
+    }
+}
+
+impl Header for ContentType {
+    const NAME: &'static str = "content-type";
+
+    fn decode(value: &HeaderValue) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        Ok(ContentType::new(to_str(value)?))
+    }
+
+    fn encode(&self) -> Result<HeaderValue, InvalidHeaderValue> {
+        HeaderValue::from_str(&self.0)
+    }
+}
+
+/// `User-Agent`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UserAgent(String);
+
+impl UserAgent {
+    pub fn new(value: impl Into<String>) -> Self {
+        UserAgent(value.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Header for UserAgent {
+    const NAME: &'static str = "user-agent";
+
+    fn decode(value: &HeaderValue) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        Ok(UserAgent::new(to_str(value)?))
+    }
+
+    fn encode(&self) -> Result<HeaderValue, InvalidHeaderValue> {
+        HeaderValue::from_str(&self.0)
+    }
+}
+
+/// `Location` of a created resource or a redirection.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Location(String);
+
+impl Location {
+    pub fn new(uri: impl Into<String>) -> Self {
+        Location(uri.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Header for Location {
+    const NAME: &'static str = "location";
+
+    fn decode(value: &HeaderValue) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        Ok(Location::new(to_str(value)?))
+    }
+
+    fn encode(&self) -> Result<HeaderValue, InvalidHeaderValue> {
+        HeaderValue::from_str(&self.0)
+    }
+}
+
+/// An entity tag, like `"v1"` or `W/"v1"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ETag {
+    tag: String,
+    weak: bool,
+}
+
+impl ETag {
+    pub fn strong(tag: impl Into<String>) -> Self {
+        ETag {
+            tag: tag.into(),
+            weak: false,
+        }
+    }
+
+    pub fn weak(tag: impl Into<String>) -> Self {
+        ETag {
+            tag: tag.into(),
+            weak: true,
+        }
+    }
+
+    /// The tag without quotes.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+
+    fn write(&self, buf: &mut String) {
+        if self.weak {
+            buf.push_str("W/");
+        }
+        let _ = write!(buf, "\"{}\"", self.tag);
+    }
+}
+
+/// Parses a comma separated list of entity tags.
+fn parse_etags(value: &str) -> Result<Vec<ETag>, Box<dyn Error + Send + Sync>> {
+    let mut tags = vec![];
+    let mut rest = value.trim();
+    while !rest.is_empty() {
+        let (weak, quoted) = match rest.strip_prefix("W/") {
+            Some(quoted) => (true, quoted),
+            None => (false, rest),
+        };
+        let quoted = quoted.strip_prefix('"').ok_or("invalid entity tag")?;
+        let end = quoted.find('"').ok_or("invalid entity tag")?;
+        tags.push(ETag {
+            tag: quoted[..end].to_string(),
+            weak,
+        });
+
+        rest = quoted[end + 1..].trim_start();
+        if !rest.is_empty() {
+            rest = rest
+                .strip_prefix(',')
+                .ok_or("invalid entity tag")?
+                .trim_start();
+        }
+    }
+    Ok(tags)
+}
+
+impl Header for ETag {
+    const NAME: &'static str = "etag";
+
+    fn decode(value: &HeaderValue) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let mut tags = parse_etags(to_str(value)?)?;
+        match tags.len() {
+            1 => Ok(tags.remove(0)),
+            _ => Err("expected one entity tag".into()),
+        }
+    }
+
+    fn encode(&self) -> Result<HeaderValue, InvalidHeaderValue> {
+        let mut buf = String::new();
+        self.write(&mut buf);
+        HeaderValue::from_str(&buf)
+    }
+}
+
+/// `If-None-Match`, which is `*` or a list of entity tags.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum IfNoneMatch {
+    Any,
+    Tags(Vec<ETag>),
+}
+
+impl IfNoneMatch {
+    /// Returns true if `etag` matches with the weak comparison, which means
+    /// that the precondition fails.
+    pub fn matches(&self, etag: &ETag) -> bool {
+        match self {
+            IfNoneMatch::Any => true,
+            IfNoneMatch::Tags(tags) => tags.iter().any(|t| t.tag == etag.tag),
+        }
+    }
+}
+
+impl Header for IfNoneMatch {
+    const NAME: &'static str = "if-none-match";
+
+    fn decode(value: &HeaderValue) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        match to_str(value)? {
+            "*" => Ok(IfNoneMatch::Any),
+            value => Ok(IfNoneMatch::Tags(parse_etags(value)?)),
+        }
+    }
+
+    fn encode(&self) -> Result<HeaderValue, InvalidHeaderValue> {
+        let tags = match self {
+            IfNoneMatch::Any => return Ok(HeaderValue::from_static("*")),
+            IfNoneMatch::Tags(tags) => tags,
+        };
+
+        let mut buf = String::new();
+        for (i, tag) in tags.iter().enumerate() {
+            if i != 0 {
+                buf.push_str(", ");
+            }
+            tag.write(&mut buf);
+        }
+        HeaderValue::from_str(&buf)
+    }
+}
+
+/// A range of `Range: bytes=...`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ByteRange {
+    /// `first-last`, where `last` is inclusive.
+    FromTo(u64, u64),
+    /// `first-`
+    From(u64),
+    /// `-length`
+    Last(u64),
+}
+
+impl ByteRange {
+    /// Bytes of a representation of `len` bytes selected by the range, or
+    /// `None` if the range is not satisfiable.
+    pub fn bounds(self, len: u64) -> Option<std::ops::Range<u64>> {
+        match self {
+            ByteRange::FromTo(first, last) if first < len => Some(first..len.min(last.saturating_add(1))),
+            ByteRange::From(first) if first < len => Some(first..len),
+            ByteRange::Last(length) if length > 0 && len > 0 => {
+                Some(len.saturating_sub(length)..len)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// `Range` in bytes. Other units are rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Range(Vec<ByteRange>);
+
+impl Range {
+    pub fn bytes(ranges: Vec<ByteRange>) -> Self {
+        Range(ranges)
+    }
+
+    pub fn ranges(&self) -> &[ByteRange] {
+        &self.0
+    }
+}
+
+impl Header for Range {
+    const NAME: &'static str = "range";
+
+    fn decode(value: &HeaderValue) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let value = to_str(value)?;
+        let ranges = match value.find('=') {
+            Some(i) if value[..i].trim().eq_ignore_ascii_case("bytes") => &value[i + 1..],
+            _ => return Err("unsupported range unit".into()),
+        };
+
+        let parse = |s: &str| s.trim().parse::<u64>();
+        let ranges = ranges
+            .split(',')
+            .map(|range| {
+                let (first, last) = match range.find('-') {
+                    Some(i) => (range[..i].trim(), range[i + 1..].trim()),
+                    None => return Err("invalid range".into()),
+                };
+                Ok(match (first.is_empty(), last.is_empty()) {
+                    (false, false) => {
+                        let (first, last) = (parse(first)?, parse(last)?);
+                        if first > last {
+                            return Err("invalid range".into());
+                        }
+                        ByteRange::FromTo(first, last)
+                    }
+                    (false, true) => ByteRange::From(parse(first)?),
+                    (true, false) => ByteRange::Last(parse(last)?),
+                    (true, true) => return Err("invalid range".into()),
+                })
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error + Send + Sync>>>()?;
+        Ok(Range(ranges))
+    }
+
+    fn encode(&self) -> Result<HeaderValue, InvalidHeaderValue> {
+        let ranges = self
+            .0
+            .iter()
+            .map(|range| match range {
+                ByteRange::FromTo(first, last) => format!("{}-{}", first, last),
+                ByteRange::From(first) => format!("{}-", first),
+                ByteRange::Last(length) => format!("-{}", length),
+            })
+            .collect::<Vec<_>>();
+        HeaderValue::from_str(&format!("bytes={}", ranges.join(",")))
+    }
+}
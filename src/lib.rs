@@ -9,6 +9,46 @@
 //!
 //! # Path parmeters
 //!
+//! A path parameter can have a constraint, like `{id:[0-9]+}`. If a segment
+//! does not match the constraint, the route is not matched and other routes
+//! are tried. Named constraints `int`, `uint`, `alpha`, `alnum` and `uuid`
+//! are also supported.
+//!
+//! ```rust
+//! use rweb::*;
+//!
+//! #[get("/users/{id:int}")]
+//! fn user_by_id(id: i64) -> String {
+//!     id.to_string()
+//! }
+//!
+//! #[get("/users/{name:[a-z-]+}")]
+//! fn user_by_name(name: String) -> String {
+//!     name
+//! }
+//!
+//! fn main() {
+//!     serve(user_by_id().or(user_by_name()));
+//! }
+//! ```
+//!
+//! The last segment can capture the remaining path with `{*name}`. The
+//! captured path is percent-decoded and normalized, and requests trying to
+//! escape it with `..` are rejected.
+//!
+//! ```rust
+//! use rweb::*;
+//! use std::path::PathBuf;
+//!
+//! #[get("/files/{*path}")]
+//! fn file(path: PathBuf) -> String {
+//!     path.display().to_string()
+//! }
+//!
+//! fn main() {
+//!     serve(file());
+//! }
+//! ```
 //!
 //! # Attributes on function item
 //!
@@ -100,7 +140,8 @@
 //! ```
 //!
 //! ## `#[form]`
-//! Parses request body. `Content-Type` should be `x-www-form-urlencoded`.
+//! Parses request body. `Content-Type` should be
+//! `application/x-www-form-urlencoded`.
 //! ```rust
 //! use rweb::*;
 //! use serde::Deserialize;
@@ -196,6 +237,9 @@
 //! }
 //! ```
 //!
+//! The value is parsed with `FromStr`. See [headers] for typed headers, which
+//! are also documented if the `openapi` feature is enabled.
+//!
 //! ## `#[cookie = "cookie-name"]`
 //! Value of the header.
 //! ```rust
@@ -278,6 +322,27 @@
 //! }
 //! ```
 //!
+//! ## Other attributes
+//!
+//! A parameter can have at most one of the attributes above. Other attributes
+//! like `#[allow]` are kept, doc comments are used as the description of path
+//! parameters, and a parameter disabled by `#[cfg]` is not extracted.
+//!
+//! ```rust
+//! use rweb::*;
+//!
+//! #[get("/items/{id}")]
+//! fn item(
+//!     /// Id of the item
+//!     id: u32,
+//!     #[cfg(feature = "tracing")]
+//!     #[header = "x-request-id"]
+//!     request_id: String,
+//! ) -> String {
+//!     id.to_string()
+//! }
+//! ```
+//!
 //! # FromRequest
 //! ```rust
 //! use http::StatusCode;
@@ -308,6 +373,11 @@
 //! }
 //! ```
 //!
+//! If the `openapi` feature is enabled, extractors can document the headers
+//! or credentials they read by overriding `FromRequest::describe_operation`.
+//!
+//! See [auth] for `Authorization: Bearer` tokens and JWTs.
+//!
 //!
 //! # Guards
 //! ```rust
@@ -343,6 +413,87 @@
 //! fn data_param(#[data] db: Db) {}
 //! ```
 //!
+//! ## Path parameters
+//!
+//! A router path can contain path parameters, which should be declared as
+//! arguments of the router. Services can use them with `#[router_param]`.
+//!
+//! ```rust
+//! use rweb::*;
+//!
+//! #[get("/repos/{name}")]
+//! fn repo(#[router_param] org_id: u64, name: String) -> String {
+//!     format!("{}/{}", org_id, name)
+//! }
+//!
+//! #[router("/orgs/{org_id:int}", services(repo))]
+//! fn org(org_id: u64) {}
+//! ```
+//!
+//! The router checks at compile time that it has the parameters used by its
+//! services, with the same types. A router mounted by another router declares
+//! the parameters of the outer router it passes to its services with
+//! `#[router_param]`.
+//!
+//! ```rust
+//! use rweb::*;
+//!
+//! #[get("/members/{member_id}")]
+//! fn member(
+//!     #[router_param] org_id: u64,
+//!     #[router_param] team_id: String,
+//!     member_id: u64,
+//! ) -> String {
+//!     format!("{}/{}/{}", org_id, team_id, member_id)
+//! }
+//!
+//! #[router("/teams/{team_id}", services(member))]
+//! fn team(team_id: String, #[router_param] org_id: u64) {}
+//!
+//! #[router("/orgs/{org_id:int}", services(team))]
+//! fn org(org_id: u64) {}
+//! ```
+//!
+//! A service with `#[router_param]` can only be used through a router.
+//!
+//! ## Method not allowed
+//!
+//! A router knows the methods of its services. If a request matches the path
+//! of a service but not its method, the router responds with `405 Method Not
+//! Allowed` and an `Allow` header. `OPTIONS` requests are answered with `204
+//! No Content` and the same header.
+//!
+//! Routes check the method after the path, so a request for an unknown path is
+//! rejected as not found whatever its method. Rejections of other filters in
+//! the router are kept as they are.
+//!
+//! ## Fallback
+//!
+//! `fallback(handler)` is called if no service of a router matches a request
+//! under its path. It receives the rejection, like the handler of
+//! `warp::Filter::recover`.
+//!
+//! ```rust
+//! use rweb::*;
+//!
+//! #[get("/users")]
+//! fn users() -> String {
+//!    String::new()
+//! }
+//!
+//! async fn not_found(_: Rejection) -> Result<impl Reply, Rejection> {
+//!     Ok(reply::with_status(
+//!         reply::json(&"not found"),
+//!         http::StatusCode::NOT_FOUND,
+//!     ))
+//! }
+//!
+//! #[router("/api", services(users), fallback(not_found))]
+//! fn api() {}
+//! ```
+//!
+//! Requests which don't match the path of the router are still rejected, so
+//! other routes get a chance to handle them.
 //!
 //! ## Guard
 //! ```rust
@@ -363,19 +514,44 @@
 //! fn admin() {}
 //! ```
 
-pub use self::factory::{Form, FromRequest, Json, Query};
-pub use rweb_macros::{delete, get, head, options, patch, post, put, router, Schema};
+pub use self::{
+    error::ResponseError,
+    factory::{Body, Form, FromRequest, Json, Query, Validated},
+    negotiation::{Accept, Negotiated},
+};
+#[cfg(feature = "cbor")]
+pub use self::factory::Cbor;
+#[cfg(feature = "msgpack")]
+pub use self::factory::MsgPack;
+#[cfg(feature = "xml")]
+pub use self::factory::Xml;
+#[cfg(feature = "yaml")]
+pub use self::factory::Yaml;
+#[cfg(feature = "multipart")]
+pub use rweb_macros::Multipart;
+pub use rweb_macros::{
+    delete, get, head, options, patch, post, put, router, ResponseError, Schema,
+};
 pub use warp::{self, *};
 
 #[cfg(feature = "openapi")]
 pub mod docs;
 #[cfg(feature = "openapi")]
 pub use self::docs::*;
+mod error;
 mod factory;
 #[cfg(feature = "openapi")]
 pub mod openapi;
 #[doc(hidden)]
 pub mod rt;
 
+pub mod auth;
+pub mod form;
+pub mod headers;
+#[cfg(feature = "multipart")]
+pub mod multipart;
+pub mod negotiation;
+pub mod problem;
+pub mod rejection;
 pub mod routes;
-pub use self::routes::*;
+pub mod validation;
@@ -0,0 +1,423 @@
+//! Typed `multipart/form-data` requests.
+//!
+//! `#[derive(Multipart)]` implements [FromRequest](crate::FromRequest) for a
+//! struct, reading each part into the field with the same name. Types of
+//! fields should implement [FromPart]:
+//!
+//!  - Text fields, like `String`, numbers and `bool`.
+//!  - `Vec<u8>` and `Bytes`, which are read into memory.
+//!  - [TempFile], which is streamed into a temporary file.
+//!
+//! Fields of type `Option<T>` are optional, and parts without a matching
+//! field are ignored. If the `openapi` feature is enabled, the request body
+//! is documented as `multipart/form-data`, and file fields have
+//! `format: binary`.
+//!
+//! # Attributes
+//!
+//! - `#[multipart(max_length = 10485760)]` on the struct: Limit of the whole
+//!   request body in bytes. Defaults to 2MB, like
+//!   [form](warp::multipart::form).
+//! - `#[multipart(max_size = 1024)]` on a field: Limit of the part in bytes.
+//! - `#[multipart(rename = "name")]` on a field: Name of the part.
+//!
+//! Parts larger than their limit are rejected with
+//! [PartTooLarge](crate::rejection::PartTooLarge), and invalid or missing
+//! parts with [InvalidPart](crate::rejection::InvalidPart).
+//!
+//! # Example
+//!
+//! ```rust
+//! use rweb::{multipart::TempFile, *};
+//!
+//! #[derive(Multipart)]
+//! #[multipart(max_length = 10485760)]
+//! struct Upload {
+//!     /// Title of the image.
+//!     #[multipart(max_size = 256)]
+//!     title: String,
+//!     description: Option<String>,
+//!     #[multipart(max_size = 8388608)]
+//!     image: TempFile,
+//! }
+//!
+//! #[post("/images")]
+//! async fn upload(form: Upload) -> Result<String, Rejection> {
+//!     Ok(format!("{}: {} bytes", form.title, form.image.size()))
+//! }
+//! ```
+
+use crate::rejection::{InvalidPart, PartTooLarge};
+use futures::{future::BoxFuture, StreamExt};
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    fs::{File, OpenOptions},
+    io::AsyncWriteExt,
+};
+pub use warp::multipart::*;
+use warp::{
+    filters::BoxedFilter,
+    hyper::body::{Buf, Bytes},
+    reject, Filter, Rejection,
+};
+
+/// A struct read from a `multipart/form-data` request, which is implemented
+/// by `#[derive(Multipart)]`.
+pub trait MultipartForm: Sized + Send + 'static {
+    /// Limit of the request body in bytes.
+    fn max_length() -> Option<u64>;
+
+    fn from_form(form: FormData) -> BoxFuture<'static, Result<Self, Rejection>>;
+}
+
+/// Creates a filter extracting `T`.
+pub fn filter<T>() -> BoxedFilter<(T,)>
+where
+    T: MultipartForm,
+{
+    let mut options = form();
+    if let Some(max) = T::max_length() {
+        options = options.max_length(max);
+    }
+
+    options.and_then(T::from_form).boxed()
+}
+
+/// A value of a part.
+pub trait FromPart: Sized + Send + 'static {
+    /// Reads `part` named `name`. `max_size` is the limit of the part in
+    /// bytes.
+    fn from_part(
+        part: Part,
+        name: &'static str,
+        max_size: Option<u64>,
+    ) -> BoxFuture<'static, Result<Self, Rejection>>;
+
+    #[cfg(feature = "openapi")]
+    fn describe(
+        comp_d: &mut crate::openapi::ComponentDescriptor,
+    ) -> crate::openapi::ComponentOrInlineSchema;
+}
+
+/// Calls `op` with each chunk of `part`, checking `max_size`.
+async fn read_chunks<F>(
+    mut part: Part,
+    name: &'static str,
+    max_size: Option<u64>,
+    mut op: F,
+) -> Result<(), Rejection>
+where
+    F: FnMut(&[u8]),
+{
+    let mut size = 0u64;
+    while let Some(buf) = part.data().await {
+        let mut buf = buf.map_err(|err| reject::custom(InvalidPart::new(name, err.to_string())))?;
+        while buf.has_remaining() {
+            let chunk = buf.chunk();
+            let len = chunk.len();
+            size += len as u64;
+            if let Some(max) = max_size {
+                if size > max {
+                    return Err(reject::custom(PartTooLarge::new(name, max)));
+                }
+            }
+            op(chunk);
+            buf.advance(len);
+        }
+    }
+
+    Ok(())
+}
+
+async fn read_all(
+    part: Part,
+    name: &'static str,
+    max_size: Option<u64>,
+) -> Result<Vec<u8>, Rejection> {
+    let mut data = vec![];
+    read_chunks(part, name, max_size, |chunk| data.extend_from_slice(chunk)).await?;
+    Ok(data)
+}
+
+#[cfg(feature = "openapi")]
+fn binary_schema() -> crate::openapi::ComponentOrInlineSchema {
+    crate::openapi::ComponentOrInlineSchema::Inline(crate::openapi::Schema {
+        schema_type: Some(crate::openapi::Type::String),
+        format: "binary".into(),
+        ..Default::default()
+    })
+}
+
+impl FromPart for Vec<u8> {
+    fn from_part(
+        part: Part,
+        name: &'static str,
+        max_size: Option<u64>,
+    ) -> BoxFuture<'static, Result<Self, Rejection>> {
+        Box::pin(read_all(part, name, max_size))
+    }
+
+    #[cfg(feature = "openapi")]
+    fn describe(
+        _: &mut crate::openapi::ComponentDescriptor,
+    ) -> crate::openapi::ComponentOrInlineSchema {
+        binary_schema()
+    }
+}
+
+impl FromPart for Bytes {
+    fn from_part(
+        part: Part,
+        name: &'static str,
+        max_size: Option<u64>,
+    ) -> BoxFuture<'static, Result<Self, Rejection>> {
+        Box::pin(async move { Ok(read_all(part, name, max_size).await?.into()) })
+    }
+
+    #[cfg(feature = "openapi")]
+    fn describe(
+        _: &mut crate::openapi::ComponentDescriptor,
+    ) -> crate::openapi::ComponentOrInlineSchema {
+        binary_schema()
+    }
+}
+
+impl FromPart for String {
+    fn from_part(
+        part: Part,
+        name: &'static str,
+        max_size: Option<u64>,
+    ) -> BoxFuture<'static, Result<Self, Rejection>> {
+        Box::pin(async move {
+            String::from_utf8(read_all(part, name, max_size).await?)
+                .map_err(|_| reject::custom(InvalidPart::new(name, "invalid utf-8")))
+        })
+    }
+
+    #[cfg(feature = "openapi")]
+    fn describe(
+        comp_d: &mut crate::openapi::ComponentDescriptor,
+    ) -> crate::openapi::ComponentOrInlineSchema {
+        <String as crate::openapi::Entity>::describe(comp_d)
+    }
+}
+
+macro_rules! from_str {
+    ($($T:ty),*) => {
+        $(
+            impl FromPart for $T {
+                fn from_part(
+                    part: Part,
+                    name: &'static str,
+                    max_size: Option<u64>,
+                ) -> BoxFuture<'static, Result<Self, Rejection>> {
+                    Box::pin(async move {
+                        let s = String::from_part(part, name, max_size).await?;
+                        <$T as FromStr>::from_str(s.trim()).map_err(|err| {
+                            reject::custom(InvalidPart::new(name, err.to_string()))
+                        })
+                    })
+                }
+
+                #[cfg(feature = "openapi")]
+                fn describe(
+                    comp_d: &mut crate::openapi::ComponentDescriptor,
+                ) -> crate::openapi::ComponentOrInlineSchema {
+                    <$T as crate::openapi::Entity>::describe(comp_d)
+                }
+            }
+        )*
+    };
+}
+
+from_str!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64, bool);
+
+/// A file part streamed into a temporary file, which is removed on drop
+/// unless it's persisted.
+#[derive(Debug)]
+pub struct TempFile {
+    path: PathBuf,
+    file_name: Option<String>,
+    content_type: Option<String>,
+    size: u64,
+    persisted: bool,
+}
+
+impl TempFile {
+    /// Path of the temporary file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// File name sent by the client.
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// Content type sent by the client.
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// Size of the file in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Moves the file to `path`, so that it's not removed.
+    pub async fn persist(mut self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        tokio::fs::rename(&self.path, path).await?;
+        self.persisted = true;
+        Ok(())
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if !self.persisted {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn temp_path() -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    std::env::temp_dir().join(format!(
+        "rweb-{}-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed),
+        nanos
+    ))
+}
+
+/// Creates a new file in the temp directory, readable and writable only by
+/// the current user.
+///
+/// Existing files are never opened, so that symlinks planted by other users
+/// are not followed.
+async fn create_temp_file() -> std::io::Result<(PathBuf, File)> {
+    let mut attempts = 0;
+    loop {
+        let path = temp_path();
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+
+        match options.open(&path).await {
+            Ok(file) => return Ok((path, file)),
+            Err(err) if err.kind() == ErrorKind::AlreadyExists && attempts < 16 => {
+                attempts += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+impl FromPart for TempFile {
+    fn from_part(
+        mut part: Part,
+        name: &'static str,
+        max_size: Option<u64>,
+    ) -> BoxFuture<'static, Result<Self, Rejection>> {
+        Box::pin(async move {
+            let io_error =
+                |err: std::io::Error| reject::custom(InvalidPart::new(name, err.to_string()));
+
+            let (path, mut out) = create_temp_file().await.map_err(io_error)?;
+            let mut file = TempFile {
+                path,
+                file_name: part.filename().map(|s| s.to_string()),
+                content_type: part.content_type().map(|s| s.to_string()),
+                size: 0,
+                persisted: false,
+            };
+
+            while let Some(buf) = part.data().await {
+                let mut buf =
+                    buf.map_err(|err| reject::custom(InvalidPart::new(name, err.to_string())))?;
+                file.size += buf.remaining() as u64;
+                if let Some(max) = max_size {
+                    if file.size > max {
+                        return Err(reject::custom(PartTooLarge::new(name, max)));
+                    }
+                }
+                while buf.has_remaining() {
+                    let len = buf.chunk().len();
+                    out.write_all(buf.chunk()).await.map_err(io_error)?;
+                    buf.advance(len);
+                }
+            }
+            out.flush().await.map_err(io_error)?;
+
+            Ok(file)
+        })
+    }
+
+    #[cfg(feature = "openapi")]
+    fn describe(
+        _: &mut crate::openapi::ComponentDescriptor,
+    ) -> crate::openapi::ComponentOrInlineSchema {
+        binary_schema()
+    }
+}
+
+/// Returns the next part of `form`.
+#[doc(hidden)]
+pub async fn next_part(form: &mut FormData) -> Result<Option<Part>, Rejection> {
+    match form.next().await {
+        Some(Ok(part)) => Ok(Some(part)),
+        Some(Err(err)) => Err(reject::custom(InvalidPart::new("", err.to_string()))),
+        None => Ok(None),
+    }
+}
+
+/// Returns the value of a required part.
+#[doc(hidden)]
+pub fn required<T>(value: Option<T>, name: &'static str) -> Result<T, Rejection> {
+    value.ok_or_else(|| reject::custom(InvalidPart::new(name, "missing part")))
+}
+
+/// Describes a form with `(name, schema, required, description)` of each
+/// field.
+#[cfg(feature = "openapi")]
+#[doc(hidden)]
+pub fn describe_form(
+    fields: Vec<(
+        &'static str,
+        crate::openapi::ComponentOrInlineSchema,
+        bool,
+        &'static str,
+    )>,
+) -> crate::openapi::ComponentOrInlineSchema {
+    use crate::openapi::{ComponentOrInlineSchema, Schema, Type};
+
+    let mut schema = Schema {
+        schema_type: Some(Type::Object),
+        ..Default::default()
+    };
+    for (name, mut field, required, description) in fields {
+        if !description.is_empty() {
+            if let ComponentOrInlineSchema::Inline(s) = &mut field {
+                s.description = description.into();
+            }
+        }
+        if required {
+            schema.required.push(name.into());
+        }
+        schema.properties.insert(name.into(), field);
+    }
+
+    ComponentOrInlineSchema::Inline(schema)
+}
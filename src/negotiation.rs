@@ -0,0 +1,393 @@
+//! Content negotiation.
+//!
+//! [Negotiated] serializes a value in the format preferred by the `Accept`
+//! header of the request, which is extracted as [Accept].
+//!
+//! # Formats
+//!
+//! - `application/json`
+//! - `application/x-www-form-urlencoded`
+//! - `application/cbor`, if the `cbor` feature is enabled.
+//! - `application/msgpack`, if the `msgpack` feature is enabled.
+//! - `application/yaml`, if the `yaml` feature is enabled.
+//! - `application/xml`, if the `xml` feature is enabled.
+//!
+//! Json is used if the request does not have an `Accept` header. If no format
+//! is acceptable, `406 Not Acceptable` is replied as a
+//! [Problem](crate::problem::Problem).
+//!
+//! # Example
+//!
+//! ```rust
+//! use rweb::*;
+//! use serde::Serialize;
+//!
+//! #[derive(Debug, Serialize, Schema)]
+//! struct Product {
+//!     id: String,
+//!     price: usize,
+//! }
+//!
+//! #[get("/products/{id}")]
+//! fn product(id: String, accept: Accept) -> Negotiated<Product> {
+//!     Negotiated::new(&accept, Product { id, price: 100 })
+//! }
+//!
+//! fn main() {
+//!     serve(product());
+//! }
+//! ```
+
+use crate::{
+    headers::Header,
+    problem::Problem,
+    rejection::{BodyDeserializeError, UnsupportedMediaType},
+    FromRequest,
+};
+use futures::future::ready;
+use http::{
+    header::{HeaderValue, InvalidHeaderValue, CONTENT_TYPE, VARY},
+    StatusCode,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::error::Error;
+use warp::{
+    filters::BoxedFilter, hyper::body::Bytes, reject, reply::Response, Filter, Rejection, Reply,
+};
+
+/// A format [Negotiated] can serialize values as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    Json,
+    Form,
+    #[cfg(feature = "cbor")]
+    Cbor,
+    #[cfg(feature = "msgpack")]
+    MsgPack,
+    #[cfg(feature = "yaml")]
+    Yaml,
+    #[cfg(feature = "xml")]
+    Xml,
+}
+
+impl Format {
+    /// All enabled formats, in the order of preference of the server.
+    pub fn all() -> &'static [Format] {
+        &[
+            Format::Json,
+            Format::Form,
+            #[cfg(feature = "cbor")]
+            Format::Cbor,
+            #[cfg(feature = "msgpack")]
+            Format::MsgPack,
+            #[cfg(feature = "yaml")]
+            Format::Yaml,
+            #[cfg(feature = "xml")]
+            Format::Xml,
+        ]
+    }
+
+    /// Media type used for the `Content-Type` of responses.
+    pub fn media_type(self) -> &'static str {
+        self.media_types()[0]
+    }
+
+    /// Media types matched against `Accept`, starting with
+    /// [Format::media_type].
+    fn media_types(self) -> &'static [&'static str] {
+        match self {
+            Format::Json => &["application/json"],
+            Format::Form => &["application/x-www-form-urlencoded"],
+            #[cfg(feature = "cbor")]
+            Format::Cbor => &["application/cbor"],
+            #[cfg(feature = "msgpack")]
+            Format::MsgPack => &["application/msgpack", "application/x-msgpack"],
+            #[cfg(feature = "yaml")]
+            Format::Yaml => &["application/yaml", "application/x-yaml", "text/yaml"],
+            #[cfg(feature = "xml")]
+            Format::Xml => &["application/xml", "text/xml"],
+        }
+    }
+
+    /// Finds the format of `content_type`.
+    pub fn from_content_type(content_type: &str) -> Option<Format> {
+        Format::all()
+            .iter()
+            .copied()
+            .find(|f| f.matches(content_type))
+    }
+
+    /// Returns true if `content_type` is a media type of this format.
+    /// Parameters like `charset` are ignored.
+    pub fn matches(self, content_type: &str) -> bool {
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+        self.media_types()
+            .iter()
+            .any(|ty| ty.eq_ignore_ascii_case(essence))
+    }
+
+    pub fn serialize<T>(self, value: &T) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(match self {
+            Format::Json => serde_json::to_vec(value)?,
+            Format::Form => serde_urlencoded::to_string(value)?.into_bytes(),
+            #[cfg(feature = "cbor")]
+            Format::Cbor => {
+                let mut buf = vec![];
+                ciborium::ser::into_writer(value, &mut buf)?;
+                buf
+            }
+            #[cfg(feature = "msgpack")]
+            Format::MsgPack => rmp_serde::to_vec_named(value)?,
+            #[cfg(feature = "yaml")]
+            Format::Yaml => serde_yaml::to_vec(value)?,
+            #[cfg(feature = "xml")]
+            Format::Xml => quick_xml::se::to_string(value)?.into_bytes(),
+        })
+    }
+
+    /// Creates a response with `value` serialized in this format, or
+    /// `500 Internal Server Error` if it cannot be serialized.
+    pub fn reply<T>(self, value: &T) -> Response
+    where
+        T: ?Sized + Serialize,
+    {
+        match self.serialize(value) {
+            Ok(body) => {
+                let mut res = Response::new(body.into());
+                res.headers_mut()
+                    .insert(CONTENT_TYPE, HeaderValue::from_static(self.media_type()));
+                res
+            }
+            Err(..) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        }
+    }
+
+    pub fn deserialize<T>(self, body: &[u8]) -> Result<T, Box<dyn Error + Send + Sync>>
+    where
+        T: DeserializeOwned,
+    {
+        Ok(match self {
+            Format::Json => serde_json::from_slice(body)?,
+            Format::Form => serde_urlencoded::from_bytes(body)?,
+            #[cfg(feature = "cbor")]
+            Format::Cbor => ciborium::de::from_reader(body)?,
+            #[cfg(feature = "msgpack")]
+            Format::MsgPack => rmp_serde::from_slice(body)?,
+            #[cfg(feature = "yaml")]
+            Format::Yaml => serde_yaml::from_slice(body)?,
+            #[cfg(feature = "xml")]
+            Format::Xml => quick_xml::de::from_str(std::str::from_utf8(body)?)?,
+        })
+    }
+
+    /// Rejects requests with a `Content-Type` of another format with
+    /// [UnsupportedMediaType]. Requests without `Content-Type` are accepted.
+    pub(crate) fn check_content_type(self) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+        warp::header::optional::<String>("content-type")
+            .and_then(move |content_type: Option<String>| {
+                ready(match content_type {
+                    Some(ct) if !self.matches(&ct) => Err(reject::custom(
+                        UnsupportedMediaType::new(ct, vec![self.media_type()]),
+                    )),
+                    _ => Ok(()),
+                })
+            })
+            .untuple_one()
+    }
+
+    /// Extracts the request body deserialized from this format.
+    ///
+    /// Requests with a `Content-Type` of another format are rejected with
+    /// [UnsupportedMediaType], and invalid bodies with
+    /// [BodyDeserializeError].
+    pub fn body<T>(self) -> BoxedFilter<(T,)>
+    where
+        T: 'static + Send + DeserializeOwned,
+    {
+        self.check_content_type()
+            .and(warp::body::bytes())
+            .and_then(move |body: Bytes| {
+                ready(
+                    self.deserialize(&body)
+                        .map_err(|err| reject::custom(BodyDeserializeError::new(err))),
+                )
+            })
+            .boxed()
+    }
+}
+
+/// The `Accept` header of a request.
+///
+/// The default value represents a request without the header.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Accept(Option<String>);
+
+impl From<String> for Accept {
+    fn from(value: String) -> Self {
+        Accept(Some(value))
+    }
+}
+
+impl Accept {
+    /// Selects the acceptable format with the highest quality.
+    ///
+    /// Ties are broken by the order of [Format::all], so json is used for
+    /// `*/*`. Returns `None` if no format is acceptable.
+    pub fn select(&self) -> Option<Format> {
+        let header = match &self.0 {
+            Some(v) if !v.trim().is_empty() => v,
+            _ => return Some(Format::Json),
+        };
+        let ranges = parse_ranges(header);
+
+        let mut best = None;
+        for &format in Format::all() {
+            let q = format
+                .media_types()
+                .iter()
+                .filter_map(|ty| quality(&ranges, ty))
+                .fold(0.0, f32::max);
+
+            match best {
+                Some((_, best_q)) if best_q >= q => {}
+                _ if q > 0.0 => best = Some((format, q)),
+                _ => {}
+            }
+        }
+
+        best.map(|(format, _)| format)
+    }
+}
+
+impl FromRequest for Accept {
+    type Filter = BoxedFilter<(Accept,)>;
+
+    fn new() -> Self::Filter {
+        warp::header::optional::<String>("accept")
+            .map(Accept)
+            .boxed()
+    }
+}
+
+impl Header for Accept {
+    const NAME: &'static str = "accept";
+
+    fn decode(value: &HeaderValue) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        Ok(Accept(Some(value.to_str()?.to_string())))
+    }
+
+    fn encode(&self) -> Result<HeaderValue, InvalidHeaderValue> {
+        HeaderValue::from_str(self.0.as_deref().unwrap_or("*/*"))
+    }
+}
+
+/// A media range of `Accept`, like `text/*;q=0.5`.
+struct MediaRange<'a> {
+    ty: &'a str,
+    subtype: &'a str,
+    q: f32,
+}
+
+fn parse_ranges(header: &str) -> Vec<MediaRange<'_>> {
+    header
+        .split(',')
+        .filter_map(|range| {
+            let mut params = range.split(';');
+            let mut essence = params.next()?.trim().splitn(2, '/');
+            let ty = essence.next()?.trim();
+            let subtype = essence.next()?.trim();
+
+            let q = params
+                .filter_map(|param| {
+                    let mut kv = param.splitn(2, '=');
+                    match (kv.next()?.trim(), kv.next()) {
+                        ("q", Some(v)) | ("Q", Some(v)) => v.trim().parse::<f32>().ok(),
+                        _ => None,
+                    }
+                })
+                .next()
+                .unwrap_or(1.0);
+
+            Some(MediaRange { ty, subtype, q })
+        })
+        .collect()
+}
+
+/// Quality of `media_type`, given by the most specific range matching it.
+fn quality(ranges: &[MediaRange], media_type: &str) -> Option<f32> {
+    let (ty, subtype) = media_type.split_at(media_type.find('/')?);
+    let subtype = &subtype[1..];
+
+    ranges
+        .iter()
+        .filter_map(|r| {
+            let specificity = match (r.ty, r.subtype) {
+                ("*", "*") => 0,
+                (t, "*") if t.eq_ignore_ascii_case(ty) => 1,
+                (t, s) if t.eq_ignore_ascii_case(ty) && s.eq_ignore_ascii_case(subtype) => 2,
+                _ => return None,
+            };
+            Some((specificity, r.q))
+        })
+        .max_by_key(|&(specificity, _)| specificity)
+        .map(|(_, q)| q)
+}
+
+/// A reply serialized in the format selected by [Accept::select].
+///
+/// If the `openapi` feature is enabled, the response is documented with all
+/// enabled formats.
+#[derive(Debug, Clone)]
+pub struct Negotiated<T> {
+    value: T,
+    format: Option<Format>,
+}
+
+impl<T> Negotiated<T> {
+    pub fn new(accept: &Accept, value: T) -> Self {
+        Negotiated {
+            value,
+            format: accept.select(),
+        }
+    }
+
+    /// The selected format, or `None` if no format is acceptable.
+    pub fn format(&self) -> Option<Format> {
+        self.format
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Reply for Negotiated<T>
+where
+    T: Serialize + Send,
+{
+    fn into_response(self) -> Response {
+        let format = match self.format {
+            Some(format) => format,
+            None => {
+                let supported = Format::all()
+                    .iter()
+                    .map(|f| f.media_type())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Problem::new(StatusCode::NOT_ACCEPTABLE)
+                    .with_detail(format!("Supported media types are {}", supported))
+                    .into_response();
+            }
+        };
+
+        let mut res = format.reply(&self.value);
+        if res.status().is_success() {
+            res.headers_mut()
+                .insert(VARY, HeaderValue::from_static("accept"));
+        }
+        res
+    }
+}
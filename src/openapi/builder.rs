@@ -5,6 +5,7 @@ use super::*;
 pub struct Builder {
     spec: Spec,
     path_prefix: String,
+    default_responses: Vec<fn(&mut ComponentDescriptor) -> Responses>,
 }
 
 /// Crates a new specification builder
@@ -35,6 +36,46 @@ impl Builder {
         self
     }
 
+    /// Documents responses of `T` for every operation, unless the operation
+    /// already documents a response with the same status code.
+    ///
+    /// ```rust
+    /// use rweb::{problem::Problem, *};
+    ///
+    /// #[get("/")]
+    /// fn index() -> String {
+    ///     String::new()
+    /// }
+    ///
+    /// let (spec, _) = openapi::spec()
+    ///     .default_response::<Problem>()
+    ///     .build(|| index());
+    ///
+    /// assert!(spec.paths["/"].get.as_ref().unwrap().responses.contains_key("default"));
+    /// ```
+    #[inline]
+    pub fn default_response<T: ResponseEntity>(mut self) -> Self {
+        self.default_responses.push(T::describe_responses);
+        self
+    }
+
+    /// Registers a security scheme, which is referred by
+    /// `#[openapi(security("name"))]`. See [security](super::security) for
+    /// constructors.
+    #[inline]
+    pub fn security_scheme(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        scheme: impl Into<SecurityScheme>,
+    ) -> Self {
+        self.spec
+            .components
+            .get_or_insert_with(Default::default)
+            .security_schemes
+            .insert(name.into(), ObjectOrReference::Object(scheme.into()));
+        self
+    }
+
     /// Creates an openapi specification. You can serialize this as json or yaml
     /// to generate client codes.
     pub fn build<F, Ret>(self, op: F) -> (Spec, Ret)
@@ -48,7 +89,11 @@ impl Builder {
         let cell = RefCell::new(collector);
 
         let ret = COLLECTOR.set(&cell, || op());
-        let mut spec = cell.into_inner().spec();
+        let mut collector = cell.into_inner();
+        for describe in self.default_responses {
+            collector.add_default_responses(describe);
+        }
+        let mut spec = collector.spec();
         spec.openapi = "3.0.1".into();
         (spec, ret)
     }
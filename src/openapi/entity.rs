@@ -1,10 +1,29 @@
-use crate::{Form, Json, Query};
+#[cfg(feature = "cbor")]
+use crate::Cbor;
+#[cfg(feature = "msgpack")]
+use crate::MsgPack;
+#[cfg(feature = "xml")]
+use crate::Xml;
+#[cfg(feature = "yaml")]
+use crate::Yaml;
+use crate::{
+    auth::BearerToken,
+    form::Style,
+    headers::{self, HeaderMap, TypedHeader, WithHeader},
+    negotiation::{Accept, Format, Negotiated},
+    problem::{Problem, CONTENT_TYPE_PROBLEM},
+    rejection::Unauthorized,
+    validation::{Violation, Violations},
+    Body, Form, Json, Query, Validated,
+};
 use indexmap::IndexMap;
 pub use rweb_openapi::v3_0::*;
 use std::{
     borrow::Cow,
     collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque},
     convert::Infallible,
+    net::{IpAddr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    path::PathBuf,
     sync::Arc,
 };
 use warp::{Rejection, Reply};
@@ -143,6 +162,66 @@ impl ComponentDescriptor {
 ///     serde_json::Value::String(String::from("this is example!"))
 /// }
 /// ```
+///
+/// ## Serde attributes
+///
+/// The schema follows serde attributes of the type, including `rename`,
+/// `rename_all`, `tag`, `content`, `untagged`, `transparent` and `skip`.
+///
+/// - Properties of `#[serde(flatten)]` fields are merged into the struct.
+///   Flattened maps become `additionalProperties`, and flattened enums are
+///   added to `allOf`.
+/// - Fields with `#[serde(default)]` or `#[serde(skip_serializing_if = "..")]`
+///   are not required. If the field type implements `Serialize`, the default
+///   value is documented.
+///
+/// ```rust
+/// use rweb::*;
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Debug, Serialize, Deserialize, Schema)]
+/// struct Pagination {
+///     page: u32,
+///     #[serde(default = "default_per_page")]
+///     per_page: u32,
+/// }
+///
+/// fn default_per_page() -> u32 {
+///     20
+/// }
+///
+/// #[derive(Debug, Serialize, Deserialize, Schema)]
+/// struct Search {
+///     query: String,
+///     #[serde(flatten)]
+///     pagination: Pagination,
+///     #[serde(skip_serializing_if = "Option::is_none")]
+///     sort: Option<String>,
+/// }
+/// ```
+///
+/// ## Tuple structs
+///
+/// Tuple structs with a single field are described by the field. Other tuple
+/// structs become arrays with a fixed length. OpenAPI 3.0 can't describe the
+/// type at each position, so `items` is the schema of the field type if all
+/// fields have the same type, and a `oneOf` of the field types otherwise.
+///
+/// ```rust
+/// use rweb::*;
+/// use serde::{Serialize, Deserialize};
+///
+/// // `items` is `oneOf: [integer, string]`, so `["a", 1]` is valid as well.
+/// #[derive(Debug, Serialize, Deserialize, Schema)]
+/// struct Pair(u32, String);
+/// ```
+///
+/// ## Constraints
+///
+/// Constraints like `#[schema(min = 1, max_length = 32)]` are written to the
+/// schema of the field. With `#[schema(validate)]` on the type, they are also
+/// checked while extracting requests. See [validation](crate::validation) for
+/// the list of constraints.
 pub trait Entity {
     /// String uniquely identifying this type, respecting component naming pattern.
     ///
@@ -518,6 +597,7 @@ where
 }
 
 delegate_entity!(String => str);
+delegate_entity!(PathBuf => str);
 
 impl ResponseEntity for String {
     fn describe_responses(comp_d: &mut ComponentDescriptor) -> Responses {
@@ -683,7 +763,294 @@ impl ResponseEntity for serde_json::Value {
 }
 
 delegate_entity!(<T: Entity> Query<T> => T);
-delegate_entity!(<T: Entity> Form<T> => T);
+delegate_entity!(<T: Entity, S: Style> Form<T, S> => T);
+delegate_entity!(<T: Entity> Body<T> => T);
+delegate_entity!(<E: Entity> Validated<E> => E);
+
+/// Implements [Entity] and [ResponseEntity] for a body type of `factory.rs`,
+/// like those for [Json].
+macro_rules! body_entity {
+    ($feature:literal, $T:ident, $format:expr) => {
+        #[cfg(feature = $feature)]
+        delegate_entity!(<T: Entity> $T<T> => T);
+
+        #[cfg(feature = $feature)]
+        impl<T> ResponseEntity for $T<T>
+        where
+            T: Entity,
+        {
+            fn describe_responses(comp_d: &mut ComponentDescriptor) -> Responses {
+                let schema = Self::describe(comp_d);
+                let mut content = IndexMap::new();
+                content.insert(
+                    Cow::Borrowed($format.media_type()),
+                    MediaType {
+                        schema: Some(schema),
+                        examples: None,
+                        encoding: Default::default(),
+                    },
+                );
+                let mut map = Responses::new();
+
+                map.insert(
+                    Cow::Borrowed("200"),
+                    Response {
+                        content,
+                        ..Default::default()
+                    },
+                );
+
+                map
+            }
+        }
+    };
+}
+
+body_entity!("msgpack", MsgPack, Format::MsgPack);
+body_entity!("cbor", Cbor, Format::Cbor);
+body_entity!("yaml", Yaml, Format::Yaml);
+body_entity!("xml", Xml, Format::Xml);
+
+delegate_entity!(Accept => ());
+delegate_entity!(BearerToken => ());
+delegate_entity!(<T: Entity> Negotiated<T> => T);
+delegate_entity!(HeaderMap => ());
+
+impl<H> Entity for TypedHeader<H>
+where
+    H: headers::Header,
+{
+    fn type_name() -> Cow<'static, str> {
+        Cow::Borrowed(H::NAME)
+    }
+
+    fn describe(comp_d: &mut ComponentDescriptor) -> ComponentOrInlineSchema {
+        H::describe(comp_d)
+    }
+}
+
+delegate_entity!(<T: Entity, H> WithHeader<T, H> => T);
+
+/// Documents `H` in all responses of `T`.
+impl<T, H> ResponseEntity for WithHeader<T, H>
+where
+    T: ResponseEntity,
+    H: headers::Header,
+{
+    fn describe_responses(comp_d: &mut ComponentDescriptor) -> Responses {
+        let schema = H::describe(comp_d);
+        let mut responses = T::describe_responses(comp_d);
+        for resp in responses.values_mut() {
+            resp.headers.insert(
+                Cow::Borrowed(H::NAME),
+                ObjectOrReference::Object(Header {
+                    required: Some(true),
+                    schema: Some(schema.clone()),
+                    ..Default::default()
+                }),
+            );
+        }
+        responses
+    }
+}
+
+/// Documented with all enabled formats, and `406 Not Acceptable`.
+impl<T> ResponseEntity for Negotiated<T>
+where
+    T: Entity,
+{
+    fn describe_responses(comp_d: &mut ComponentDescriptor) -> Responses {
+        let schema = T::describe(comp_d);
+        let content = Format::all()
+            .iter()
+            .map(|format| {
+                (
+                    Cow::Borrowed(format.media_type()),
+                    MediaType {
+                        schema: Some(schema.clone()),
+                        examples: None,
+                        encoding: Default::default(),
+                    },
+                )
+            })
+            .collect();
+
+        let mut map = Responses::new();
+        map.insert(
+            Cow::Borrowed("200"),
+            Response {
+                content,
+                ..Default::default()
+            },
+        );
+        map.extend(
+            Problem::describe_responses(comp_d)
+                .into_iter()
+                .map(|(_, resp)| {
+                    (
+                        Cow::Borrowed("406"),
+                        Response {
+                            description: Cow::Borrowed("Not Acceptable"),
+                            ..resp
+                        },
+                    )
+                }),
+        );
+        map
+    }
+}
+
+impl Entity for Violation {
+    fn type_name() -> Cow<'static, str> {
+        Cow::Borrowed("Violation")
+    }
+
+    fn describe(comp_d: &mut ComponentDescriptor) -> ComponentOrInlineSchema {
+        comp_d.describe_component(&Self::type_name(), |comp_d| {
+            let mut properties = IndexMap::new();
+            for name in &["path", "constraint", "message"] {
+                properties.insert(Cow::Borrowed(*name), String::describe(comp_d));
+            }
+
+            Schema {
+                schema_type: Some(Type::Object),
+                required: properties.keys().cloned().collect(),
+                properties,
+                ..Default::default()
+            }
+        })
+    }
+}
+
+impl Entity for Violations {
+    fn type_name() -> Cow<'static, str> {
+        Cow::Borrowed("Violations")
+    }
+
+    fn describe(comp_d: &mut ComponentDescriptor) -> ComponentOrInlineSchema {
+        comp_d.describe_component(&Self::type_name(), |comp_d| {
+            let mut properties = IndexMap::new();
+            properties.insert(
+                Cow::Borrowed("violations"),
+                Vec::<Violation>::describe(comp_d),
+            );
+
+            Schema {
+                schema_type: Some(Type::Object),
+                required: vec![Cow::Borrowed("violations")],
+                properties,
+                ..Default::default()
+            }
+        })
+    }
+}
+
+impl ResponseEntity for Violations {
+    fn describe_responses(comp_d: &mut ComponentDescriptor) -> Responses {
+        let mut content = IndexMap::new();
+        content.insert(
+            Cow::Borrowed("application/json"),
+            MediaType {
+                schema: Some(Self::describe(comp_d)),
+                examples: None,
+                encoding: Default::default(),
+            },
+        );
+
+        let mut map = Responses::new();
+        map.insert(
+            Cow::Borrowed("422"),
+            Response {
+                description: Cow::Borrowed("Unprocessable Entity"),
+                content,
+                ..Default::default()
+            },
+        );
+        map
+    }
+}
+
+impl Entity for Problem {
+    fn type_name() -> Cow<'static, str> {
+        Cow::Borrowed("Problem")
+    }
+
+    fn describe(comp_d: &mut ComponentDescriptor) -> ComponentOrInlineSchema {
+        comp_d.describe_component(&Self::type_name(), |comp_d| {
+            let mut properties = IndexMap::new();
+            properties.insert(
+                Cow::Borrowed("type"),
+                ComponentOrInlineSchema::Inline(Schema {
+                    schema_type: Some(Type::String),
+                    format: Cow::Borrowed("uri-reference"),
+                    ..Default::default()
+                }),
+            );
+            properties.insert(Cow::Borrowed("title"), String::describe(comp_d));
+            properties.insert(Cow::Borrowed("status"), u16::describe(comp_d));
+            properties.insert(Cow::Borrowed("detail"), String::describe(comp_d));
+            properties.insert(
+                Cow::Borrowed("instance"),
+                ComponentOrInlineSchema::Inline(Schema {
+                    schema_type: Some(Type::String),
+                    format: Cow::Borrowed("uri-reference"),
+                    ..Default::default()
+                }),
+            );
+
+            Schema {
+                schema_type: Some(Type::Object),
+                required: vec![Cow::Borrowed("title"), Cow::Borrowed("status")],
+                properties,
+                additional_properties: Some(Box::new(ComponentOrInlineSchema::Inline(
+                    Default::default(),
+                ))),
+                ..Default::default()
+            }
+        })
+    }
+}
+
+/// Documented as the `default` response.
+impl ResponseEntity for Problem {
+    fn describe_responses(comp_d: &mut ComponentDescriptor) -> Responses {
+        let mut content = IndexMap::new();
+        content.insert(
+            Cow::Borrowed(CONTENT_TYPE_PROBLEM),
+            MediaType {
+                schema: Some(Self::describe(comp_d)),
+                examples: None,
+                encoding: Default::default(),
+            },
+        );
+
+        let mut map = Responses::new();
+        map.insert(
+            Cow::Borrowed("default"),
+            Response {
+                description: Cow::Borrowed("Error"),
+                content,
+                ..Default::default()
+            },
+        );
+        map
+    }
+}
+
+delegate_entity!(Unauthorized => Problem);
+
+/// Documented as `401 Unauthorized` with a [Problem].
+impl ResponseEntity for Unauthorized {
+    fn describe_responses(comp_d: &mut ComponentDescriptor) -> Responses {
+        let mut responses = Problem::describe_responses(comp_d);
+        let mut resp = responses.swap_remove("default").unwrap();
+        resp.description = Cow::Borrowed("Unauthorized");
+
+        let mut map = Responses::new();
+        map.insert(Cow::Borrowed("401"), resp);
+        map
+    }
+}
 
 delegate_entity!(Rejection => ());
 
@@ -739,6 +1106,11 @@ impl Entity for std::net::Ipv6Addr {
     }
 }
 
+delegate_entity!(IpAddr => str);
+delegate_entity!(SocketAddr => str);
+delegate_entity!(SocketAddrV4 => str);
+delegate_entity!(SocketAddrV6 => str);
+
 #[cfg(feature = "uuid")]
 impl Entity for uuid::Uuid {
     fn type_name() -> Cow<'static, str> {
@@ -124,6 +124,74 @@
 //! fn math() {}
 //! ```
 //!
+//! ## `#[openapi(security("oauth", scopes("read")))]`
+//!
+//! Each `security(...)` adds a security requirement, which lists schemes
+//! registered with [Builder::security_scheme]. `scopes(...)` applies to the
+//! preceding scheme.
+//!
+//! Requirements of the innermost `#[router]` declaring them are combined with
+//! ones of its operations, including requirements documented by extractors
+//! like [BearerToken](crate::auth::BearerToken), so both are required.
+//!
+//! ```rust
+//! use rweb::*;
+//!
+//! #[get("/items")]
+//! #[openapi(security("oauth", scopes("read")))]
+//! fn items() -> String {
+//!     String::new()
+//! }
+//!
+//! #[delete("/items")]
+//! fn delete_items() -> String {
+//!     String::new()
+//! }
+//!
+//! // `items` requires `oauth` and `api_key`, and `delete_items` requires `api_key`.
+//! #[router("/admin", services(items, delete_items))]
+//! #[openapi(security("api_key"))]
+//! fn admin() {}
+//! ```
+//!
+//! ## `#[openapi(response(code = 201, description = "foo"))]`
+//!
+//! Documents a response, which is merged with the response of the same code
+//! documented by the return type.
+//!
+//!  - `schema = "Type"` documents the body with a [ResponseEntity].
+//!  - `headers(location = "String")` documents headers with the schema of the
+//!    type. `_` in names are replaced with `-`, so `x_rate_limit` documents
+//!    `x-rate-limit`.
+//!  - `example = "..."` is an example of the body, which is parsed as json if
+//!    possible.
+//!  - `links(name(operation_id = "id", parameters(id = "$response.body#/id")))`
+//!    documents a link to another operation. `operation_ref` can be used
+//!    instead of `operation_id`, and `description` is optional.
+//!
+//! ```rust
+//! use rweb::*;
+//! use serde::Serialize;
+//!
+//! #[derive(Debug, Serialize, Schema)]
+//! struct Item {
+//!     id: u64,
+//! }
+//!
+//! #[post("/items")]
+//! #[openapi(response(
+//!     code = 201,
+//!     description = "Created",
+//!     schema = "Json<Item>",
+//!     headers(location = "String", x_rate_limit_remaining = "u32"),
+//!     example = r#"{"id": 1}"#,
+//!     links(get_item(operation_id = "get_item", parameters(id = "$response.body#/id"))),
+//! ))]
+//! fn create() -> Json<Item> {
+//!     Json::from(Item { id: 1 })
+//! }
+//! ```
+//!
 //!
 //! # Parameters
 //!
@@ -158,6 +226,84 @@
 //! }
 //! ```
 //!
+//! ## `#[param(description = "foo", example = 1, deprecated)]`
+//!
+//! Path, header and cookie parameters are documented with their doc comments,
+//! which can be overridden by `#[param]`. A `#[query]` string is documented as
+//! a free-form query parameter named after the argument.
+//!
+//! ```rust
+//! use rweb::*;
+//!
+//! #[get("/items/{id}")]
+//! pub fn item(
+//!     #[param(example = 3)] id: u32,
+//!     /// Used to trace the request.
+//!     #[header = "x-request-id"]
+//!     request_id: String,
+//!     #[header = "x-version"]
+//!     #[param(description = "Version of the client", deprecated)]
+//!     version: String,
+//!     /// Session of the user.
+//!     #[cookie = "sess"]
+//!     session: String,
+//!     /// Filters applied to the items.
+//!     #[query]
+//!     filters: String,
+//! ) -> String {
+//!     String::new()
+//! }
+//! ```
+//!
+//! # Custom extractors
+//!
+//! [FromRequest] types document what they read by overriding
+//! [FromRequest::describe_operation]. By default, only request bodies and
+//! queries are documented.
+//!
+//! ```rust
+//! use rweb::{filters::BoxedFilter, openapi::Collector, *};
+//! use std::borrow::Cow;
+//!
+//! #[derive(Schema)]
+//! struct User {
+//!     id: String,
+//! }
+//!
+//! impl FromRequest for User {
+//!     type Filter = BoxedFilter<(User,)>;
+//!
+//!     fn describe_operation(
+//!         collector: &mut Collector,
+//!         op: &mut openapi::Operation,
+//!         _: fn(&mut openapi::ComponentDescriptor) -> openapi::ComponentOrInlineSchema,
+//!     ) {
+//!         op.parameters.push(openapi::ObjectOrReference::Object(openapi::Parameter {
+//!             name: Cow::Borrowed("x-user-id"),
+//!             location: openapi::Location::Header,
+//!             required: Some(true),
+//!             representation: Some(openapi::ParameterRepresentation::Simple {
+//!                 schema: <String as openapi::Entity>::describe(collector.components()),
+//!             }),
+//!             ..Default::default()
+//!         }));
+//!     }
+//!
+//!     fn new() -> Self::Filter {
+//!         header::<String>("x-user-id").map(|id| User { id }).boxed()
+//!     }
+//! }
+//!
+//! #[get("/")]
+//! fn index(user: User) -> String {
+//!     user.id
+//! }
+//!
+//! let (spec, _) = openapi::spec().build(|| index());
+//! let op = spec.paths["/"].get.as_ref().unwrap();
+//! assert_eq!(op.parameters.len(), 1);
+//! ```
+//!
 //! # Response body
 //!
 //! ```rust
@@ -182,6 +328,10 @@
 //!
 //! # Custom error
 //!
+//! `#[derive(Schema, ResponseError)]` documents a response for each variant of
+//! an error enum. See [ResponseError](crate::ResponseError) for details. Other
+//! types can implement [ResponseEntity] manually.
+//!
 //! ```rust
 //! use rweb::*;
 //! use indexmap::IndexMap;
@@ -213,7 +363,7 @@ pub use self::{
     builder::{spec, Builder},
     entity::{ComponentDescriptor, Entity, ResponseEntity, Responses},
 };
-use crate::FromRequest;
+use crate::{form::FormStyle, validation::Violations, FromRequest, ResponseError};
 use http::Method;
 use indexmap::IndexMap;
 pub use rweb_openapi::v3_0::*;
@@ -222,6 +372,7 @@ use std::{borrow::Cow, cell::RefCell, mem::replace};
 
 mod builder;
 mod entity;
+pub mod security;
 
 scoped_thread_local!(static COLLECTOR: RefCell<Collector>);
 
@@ -231,11 +382,14 @@ pub struct Collector {
     components: ComponentDescriptor,
     path_prefix: String,
     tags: Vec<Cow<'static, str>>,
+    /// Path parameters declared by enclosing routers.
+    parameters: Vec<ObjectOrReference<Parameter>>,
+    /// Security requirements of the innermost router declaring them.
+    security: Vec<SecurityRequirement>,
 }
 
 impl Collector {
-    /// Method used by `#[op]`
-    #[doc(hidden)]
+    /// Descriptor of schemas, which are registered as components.
     pub fn components(&mut self) -> &mut ComponentDescriptor {
         &mut self.components
     }
@@ -246,6 +400,8 @@ impl Collector {
         &mut self,
         prefix: &str,
         tags: Vec<Cow<'static, str>>,
+        parameters: Vec<ObjectOrReference<Parameter>>,
+        security: Vec<SecurityRequirement>,
         op: F,
     ) -> Ret
     where
@@ -255,6 +411,13 @@ impl Collector {
         self.path_prefix.push_str(prefix);
         let orig_tag_len = self.tags.len();
         self.tags.extend(tags);
+        let orig_param_len = self.parameters.len();
+        self.parameters.extend(parameters);
+        let orig_security = if security.is_empty() {
+            None
+        } else {
+            Some(replace(&mut self.security, security))
+        };
 
         let new = replace(self, new());
         let cell = RefCell::new(new);
@@ -263,47 +426,140 @@ impl Collector {
         let new = cell.into_inner();
         let _ = replace(self, new);
 
+        if let Some(security) = orig_security {
+            self.security = security;
+        }
+        self.parameters.drain(orig_param_len..);
         self.tags.drain(orig_tag_len..);
         self.path_prefix.drain(orig_len..);
         ret
     }
 
+    /// Documents `T` with [FromRequest::describe_operation].
     pub fn add_request_type_to<T: FromRequest + Entity>(&mut self, op: &mut Operation) {
-        if T::is_body() {
-            if op.request_body.is_some() {
-                panic!("Multiple body detected");
-            }
+        T::describe_operation(self, op, T::describe);
+    }
 
-            let s = T::describe(&mut self.components);
+    /// Documents `T` according to [FromRequest::is_body],
+    /// [FromRequest::is_query] and [FromRequest::is_validated], which is the
+    /// default of [FromRequest::describe_operation].
+    pub fn describe_request<T: FromRequest>(
+        &mut self,
+        op: &mut Operation,
+        schema: fn(&mut ComponentDescriptor) -> ComponentOrInlineSchema,
+    ) {
+        if T::is_body() {
+            self.add_request_body_to::<T>(op, schema);
+        }
 
-            let mut content = IndexMap::new();
+        if T::is_query() {
+            self.add_query_parameters_to(op, schema);
+        }
 
-            // TODO
-            content.insert(
-                Cow::Borrowed(T::content_type()),
-                MediaType {
-                    schema: Some(s),
-                    examples: None,
-                    encoding: Default::default(),
-                },
-            );
+        if T::is_validated() {
+            self.add_response_to::<Violations>(op);
+        }
+    }
 
-            op.request_body = Some(ObjectOrReference::Object(RequestBody {
-                content,
-                required: Some(!T::is_optional()),
-                ..Default::default()
-            }));
+    /// Documents the request body of `T` with the content types of `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `op` already has a request body.
+    pub fn add_request_body_to<T: FromRequest>(
+        &mut self,
+        op: &mut Operation,
+        schema: fn(&mut ComponentDescriptor) -> ComponentOrInlineSchema,
+    ) {
+        if op.request_body.is_some() {
+            panic!("Multiple body detected");
         }
 
-        if T::is_query() {
-            self.add_query_type_to::<T>(op);
+        let s = schema(&mut self.components);
+        let encoding = match T::form_style() {
+            Some(style) => self.form_encoding(&s, style),
+            None => Default::default(),
+        };
+
+        let content = T::content_types()
+            .into_iter()
+            .map(|content_type| {
+                (
+                    Cow::Borrowed(content_type),
+                    MediaType {
+                        schema: Some(s.clone()),
+                        examples: None,
+                        encoding: encoding.clone(),
+                    },
+                )
+            })
+            .collect();
+
+        op.request_body = Some(ObjectOrReference::Object(RequestBody {
+            content,
+            required: Some(!T::is_optional()),
+            ..Default::default()
+        }));
+    }
+
+    /// Documents the security scheme `name` as required by `op`, and
+    /// registers it.
+    pub fn add_security_requirement_to(
+        &mut self,
+        op: &mut Operation,
+        name: &'static str,
+        scheme: SecurityScheme,
+    ) {
+        self.add_security_scheme(name, scheme);
+        let mut requirement = SecurityRequirement::new();
+        requirement.insert(Cow::Borrowed(name), vec![]);
+        if !op.security.contains(&requirement) {
+            op.security.push(requirement);
         }
     }
 
-    fn add_query_type_to<T: FromRequest + Entity>(&mut self, op: &mut Operation) {
-        debug_assert!(T::is_query());
+    /// Describes how arrays and nested objects of a form body are encoded.
+    fn form_encoding(
+        &self,
+        s: &ComponentOrInlineSchema,
+        style: FormStyle,
+    ) -> IndexMap<Cow<'static, str>, Encoding> {
+        let s = self.components.get_unpack(s);
+
+        s.properties
+            .iter()
+            .filter_map(|(name, ps)| {
+                let ty = self.components.get_unpack(ps).schema_type;
+                let style = match (style, ty) {
+                    (FormStyle::Repeat, Some(Type::Array)) => "form",
+                    (FormStyle::Brackets, Some(Type::Array))
+                    | (FormStyle::Brackets, Some(Type::Object)) => "deepObject",
+                    _ => return None,
+                };
+
+                Some((
+                    name.clone(),
+                    Encoding {
+                        style: Cow::Borrowed(style),
+                        explode: Some(true),
+                        ..Default::default()
+                    },
+                ))
+            })
+            .collect()
+    }
 
-        let s = T::describe(&mut self.components);
+    /// Documents each property of `schema` as a query parameter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `schema` is not an object.
+    pub fn add_query_parameters_to(
+        &mut self,
+        op: &mut Operation,
+        schema: fn(&mut ComponentDescriptor) -> ComponentOrInlineSchema,
+    ) {
+        let s = schema(&mut self.components);
         let s = self.components.get_unpack(&s);
 
         assert_eq!(
@@ -326,16 +582,30 @@ impl Collector {
     pub fn add_response_to<T: ResponseEntity>(&mut self, op: &mut Operation) {
         // T::describe(&mut self.components);
         let mut responses = T::describe_responses(&mut self.components);
-        for (code, mut resp) in &mut responses {
-            if let Some(ex_resp) = op.responses.remove(code) {
-                if !ex_resp.description.is_empty() {
-                    resp.description = ex_resp.description
-                }
+        for (code, resp) in &mut responses {
+            if let Some(documented) = op.responses.remove(code) {
+                merge_response(resp, documented);
             }
         }
         op.responses.extend(responses);
     }
 
+    /// Adds responses of `T`, and a response for each status code of `E`.
+    pub fn add_error_responses_to<T, E>(&mut self, op: &mut Operation)
+    where
+        T: ResponseEntity,
+        E: ResponseError + Entity,
+    {
+        self.add_response_to::<T>(op);
+
+        for (code, mut resp) in E::describe_error_responses(&mut self.components) {
+            if let Some(documented) = op.responses.remove(&code) {
+                merge_response(&mut resp, documented);
+            }
+            op.responses.insert(code, resp);
+        }
+    }
+
     #[doc(hidden)]
     #[inline(never)]
     pub fn add(&mut self, path: &str, method: Method, operation: Operation) {
@@ -384,10 +654,54 @@ impl Collector {
 
         let op = op.as_mut().unwrap();
         op.tags.extend(self.tags.clone());
+        for param in self.parameters.iter().rev() {
+            if !op.parameters.contains(param) {
+                op.parameters.insert(0, param.clone());
+            }
+        }
+        // An operation requires both its own requirements and ones of routers.
+        op.security = merge_security(&op.security, &self.security);
+    }
+
+    /// Registers a security scheme, unless a scheme with the same name is
+    /// already registered.
+    pub fn add_security_scheme(&mut self, name: &'static str, scheme: SecurityScheme) {
+        self.spec
+            .components
+            .get_or_insert_with(Default::default)
+            .security_schemes
+            .entry(Cow::Borrowed(name))
+            .or_insert(ObjectOrReference::Object(scheme));
     }
 
+    /// This never registered anything. Kept so that existing callers compile.
+    #[deprecated(note = "use `Collector::add_security_scheme` instead")]
     pub fn add_scheme<T>() {}
 
+    /// Adds responses to all operations which do not document them.
+    fn add_default_responses(&mut self, describe: fn(&mut ComponentDescriptor) -> Responses) {
+        let responses = describe(&mut self.components);
+        for item in self.spec.paths.values_mut() {
+            let ops = vec![
+                &mut item.get,
+                &mut item.put,
+                &mut item.post,
+                &mut item.delete,
+                &mut item.options,
+                &mut item.head,
+                &mut item.patch,
+                &mut item.trace,
+            ];
+            for op in ops.into_iter().flatten() {
+                for (code, resp) in &responses {
+                    op.responses
+                        .entry(code.clone())
+                        .or_insert_with(|| resp.clone());
+                }
+            }
+        }
+    }
+
     fn spec(self) -> Spec {
         let mut spec = self.spec;
         spec.components
@@ -398,12 +712,71 @@ impl Collector {
     }
 }
 
+/// Merges a response documented by `#[openapi(response(...))]` into `resp`,
+/// which is documented by a type.
+///
+/// The description, headers and links of `documented` take precedence, and
+/// its example for `*/*` applies to all media types of `resp`.
+fn merge_response(resp: &mut Response, documented: Response) {
+    if !documented.description.is_empty() {
+        resp.description = documented.description;
+    }
+    resp.headers.extend(documented.headers);
+    resp.links.extend(documented.links);
+
+    if let Some(any) = documented.content.get("*/*") {
+        if resp.content.is_empty() {
+            resp.content.insert(Cow::Borrowed("*/*"), any.clone());
+        }
+        for media in resp.content.values_mut() {
+            if media.examples.is_none() {
+                media.examples = any.examples.clone();
+            }
+        }
+    }
+}
+
+/// Combines alternative requirements of an operation with alternative
+/// requirements of a router, so that both are required.
+fn merge_security(
+    op: &[SecurityRequirement],
+    router: &[SecurityRequirement],
+) -> Vec<SecurityRequirement> {
+    if op.is_empty() {
+        return router.to_vec();
+    }
+    if router.is_empty() {
+        return op.to_vec();
+    }
+
+    let mut merged = vec![];
+    for op in op {
+        for router in router {
+            let mut requirement = op.clone();
+            for (name, scopes) in router {
+                let merged_scopes = requirement.entry(name.clone()).or_default();
+                for scope in scopes {
+                    if !merged_scopes.contains(scope) {
+                        merged_scopes.push(scope.clone());
+                    }
+                }
+            }
+            if !merged.contains(&requirement) {
+                merged.push(requirement);
+            }
+        }
+    }
+    merged
+}
+
 fn new() -> Collector {
     Collector {
         spec: Default::default(),
         components: ComponentDescriptor::new(),
         path_prefix: Default::default(),
         tags: vec![],
+        parameters: vec![],
+        security: vec![],
     }
 }
 
@@ -0,0 +1,189 @@
+//! Security schemes, which are registered with
+//! [Builder::security_scheme](super::Builder::security_scheme).
+//!
+//! Operations refer to the schemes by name with
+//! `#[openapi(security("name", scopes("read")))]`.
+//!
+//! ```rust
+//! use rweb::{
+//!     openapi::{
+//!         security::{self, ApiKeyLocation, OAuth2},
+//!         SecurityScheme,
+//!     },
+//!     *,
+//! };
+//! use std::convert::TryFrom;
+//!
+//! #[get("/")]
+//! #[openapi(security("bearer"))]
+//! fn index() -> String {
+//!     String::new()
+//! }
+//!
+//! let (spec, _) = openapi::spec()
+//!     .security_scheme("bearer", security::jwt())
+//!     .security_scheme("api_key", security::api_key(ApiKeyLocation::Header, "x-api-key"))
+//!     .security_scheme(
+//!         "oauth",
+//!         SecurityScheme::try_from(OAuth2::new().client_credentials(
+//!             "https://example.com/oauth/token",
+//!             &[("read", "Read access")],
+//!         ))
+//!         .unwrap(),
+//!     )
+//!     .build(|| index());
+//!
+//! assert_eq!(spec.components.unwrap().security_schemes.len(), 3);
+//! ```
+
+use super::SecurityScheme;
+use serde_json::{json, Map, Value};
+use std::{borrow::Cow, convert::TryFrom, error::Error, fmt};
+
+/// `Authorization: Bearer <token>`.
+pub fn bearer() -> SecurityScheme {
+    http("bearer", "")
+}
+
+/// `Authorization: Bearer <token>`, where the token is a JWT.
+pub fn jwt() -> SecurityScheme {
+    http("bearer", "JWT")
+}
+
+/// `Authorization: Basic <credentials>`.
+pub fn basic() -> SecurityScheme {
+    http("basic", "")
+}
+
+fn http(scheme: &'static str, bearer_format: &'static str) -> SecurityScheme {
+    SecurityScheme::Http {
+        scheme: Cow::Borrowed(scheme),
+        bearer_format: Cow::Borrowed(bearer_format),
+    }
+}
+
+/// Location of an api key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApiKeyLocation {
+    Header,
+    Query,
+    Cookie,
+}
+
+/// An api key sent in the header, query parameter or cookie named `name`.
+pub fn api_key(location: ApiKeyLocation, name: impl Into<Cow<'static, str>>) -> SecurityScheme {
+    SecurityScheme::ApiKey {
+        name: name.into(),
+        location: Cow::Borrowed(match location {
+            ApiKeyLocation::Header => "header",
+            ApiKeyLocation::Query => "query",
+            ApiKeyLocation::Cookie => "cookie",
+        }),
+    }
+}
+
+/// [OpenID Connect Discovery](https://openid.net/specs/openid-connect-discovery-1_0.html).
+pub fn open_id_connect(url: impl Into<Cow<'static, str>>) -> SecurityScheme {
+    SecurityScheme::OpenIdConnect {
+        open_id_connect_url: url.into(),
+    }
+}
+
+/// Builder for an OAuth2 security scheme with one or more flows.
+///
+/// Scopes are given as `(name, description)`. Urls are checked when it is
+/// converted into a [SecurityScheme].
+#[derive(Debug, Clone, Default)]
+pub struct OAuth2 {
+    flows: Map<String, Value>,
+}
+
+impl OAuth2 {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn implicit(self, authorization_url: &str, scopes: &[(&str, &str)]) -> Self {
+        self.flow(
+            "implicit",
+            json!({
+                "authorizationUrl": authorization_url,
+                "scopes": scopes_map(scopes),
+            }),
+        )
+    }
+
+    pub fn password(self, token_url: &str, scopes: &[(&str, &str)]) -> Self {
+        self.flow(
+            "password",
+            json!({
+                "tokenUrl": token_url,
+                "scopes": scopes_map(scopes),
+            }),
+        )
+    }
+
+    pub fn client_credentials(self, token_url: &str, scopes: &[(&str, &str)]) -> Self {
+        self.flow(
+            "clientCredentials",
+            json!({
+                "tokenUrl": token_url,
+                "scopes": scopes_map(scopes),
+            }),
+        )
+    }
+
+    pub fn authorization_code(
+        self,
+        authorization_url: &str,
+        token_url: &str,
+        scopes: &[(&str, &str)],
+    ) -> Self {
+        self.flow(
+            "authorizationCode",
+            json!({
+                "authorizationUrl": authorization_url,
+                "tokenUrl": token_url,
+                "scopes": scopes_map(scopes),
+            }),
+        )
+    }
+
+    fn flow(mut self, name: &str, flow: Value) -> Self {
+        self.flows.insert(name.to_string(), flow);
+        self
+    }
+}
+
+fn scopes_map(scopes: &[(&str, &str)]) -> Map<String, Value> {
+    scopes
+        .iter()
+        .map(|(name, description)| (name.to_string(), Value::from(*description)))
+        .collect()
+}
+
+/// An url of a flow of [OAuth2] is invalid.
+#[derive(Debug)]
+pub struct InvalidOAuth2Url(serde_json::Error);
+
+impl fmt::Display for InvalidOAuth2Url {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid url in oauth2 flow: {}", self.0)
+    }
+}
+
+impl Error for InvalidOAuth2Url {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl TryFrom<OAuth2> for SecurityScheme {
+    type Error = InvalidOAuth2Url;
+
+    fn try_from(oauth: OAuth2) -> Result<Self, Self::Error> {
+        // Fields of flows are not public, so they are deserialized.
+        let flows = serde_json::from_value(Value::Object(oauth.flows)).map_err(InvalidOAuth2Url)?;
+        Ok(SecurityScheme::OAuth2 { flows })
+    }
+}
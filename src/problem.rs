@@ -0,0 +1,266 @@
+//! Problem details for HTTP APIs, as described in
+//! [RFC 7807](https://tools.ietf.org/html/rfc7807).
+//!
+//! [recover] converts rejections into [Problem]s, which are replied as
+//! `application/problem+json`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use rweb::{problem::recover, *};
+//!
+//! #[get("/sum/{a}/{b}")]
+//! fn sum(a: usize, b: usize) -> String {
+//!     (a + b).to_string()
+//! }
+//!
+//! fn main() {
+//!     serve(sum().recover(recover));
+//! }
+//! ```
+//!
+//! A request to `/sum/1/x` is replied with
+//!
+//! ```json
+//! {
+//!   "title": "Not Found",
+//!   "status": 404
+//! }
+//! ```
+//!
+//! With the `openapi` feature, [Problem] can be documented as the default
+//! response of every operation with
+//! [Builder::default_response](crate::openapi::Builder::default_response).
+
+use crate::{rejection, validation::Violations, ResponseError};
+use http::{
+    header::{HeaderName, HeaderValue, CONTENT_TYPE, WWW_AUTHENTICATE},
+    HeaderMap, StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    convert::Infallible,
+    fmt::{self, Display, Formatter},
+};
+use warp::{
+    body::BodyDeserializeError,
+    cors::CorsForbidden,
+    reject::{
+        InvalidHeader, InvalidQuery, LengthRequired, MethodNotAllowed, MissingCookie,
+        MissingHeader, PayloadTooLarge, Reject, UnsupportedMediaType,
+    },
+    reply::{json, with_header, with_status, Response},
+    Rejection, Reply,
+};
+
+/// Media type of [Problem].
+pub const CONTENT_TYPE_PROBLEM: &str = "application/problem+json";
+
+/// A problem detail object.
+///
+/// This can be used as a [Reply], a rejection, or an error returned from a
+/// handler.
+///
+/// ```rust
+/// use rweb::{problem::Problem, *};
+///
+/// #[get("/items/{id}")]
+/// fn item(id: u32) -> Result<String, Problem> {
+///     Err(Problem::new(http::StatusCode::NOT_FOUND)
+///         .with_detail(format!("item {} does not exist", id))
+///         .with_extension("id", id))
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Problem {
+    /// URI reference identifying the problem type. `about:blank` if absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<String>,
+    /// Short summary of the problem type.
+    pub title: String,
+    /// Status code of the response.
+    pub status: u16,
+    /// Explanation specific to this occurrence of the problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// URI reference identifying this occurrence of the problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Additional members of the problem.
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+    /// Headers of the response, like `WWW-Authenticate`.
+    #[serde(skip)]
+    pub headers: HeaderMap,
+}
+
+impl Problem {
+    /// Creates a problem using the canonical reason of `status` as the title.
+    pub fn new(status: StatusCode) -> Self {
+        Problem {
+            r#type: None,
+            title: status.canonical_reason().unwrap_or_default().to_string(),
+            status: status.as_u16(),
+            detail: None,
+            instance: None,
+            extensions: Default::default(),
+            headers: Default::default(),
+        }
+    }
+
+    pub fn with_type(mut self, ty: impl Into<String>) -> Self {
+        self.r#type = Some(ty.into());
+        self
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Adds an extension member.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` cannot be serialized as json.
+    pub fn with_extension(mut self, name: impl Into<String>, value: impl Serialize) -> Self {
+        let value = serde_json::to_value(value).expect("failed to serialize extension member");
+        self.extensions.insert(name.into(), value);
+        self
+    }
+
+    /// Adds a header to the response.
+    pub fn with_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Status code of the response, or `500 Internal Server Error` if
+    /// [Problem::status] is invalid.
+    pub fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    /// Converts a rejection into a problem.
+    ///
+    /// Rejections of warp and rweb for invalid requests, [Violations] and [Problem]
+    /// are converted with their status code and a detail. Other rejections
+    /// become `500 Internal Server Error` without detail, so that internal
+    /// errors are not leaked.
+    pub fn from_rejection(r: &Rejection) -> Self {
+        if let Some(p) = r.find::<Problem>() {
+            return p.clone();
+        }
+
+        if let Some(v) = r.find::<Violations>() {
+            return Problem::new(StatusCode::UNPROCESSABLE_ENTITY)
+                .with_detail(v.to_string())
+                .with_extension("violations", &v.violations);
+        }
+
+        if let Some(err) = r.find::<rejection::Unauthorized>() {
+            let problem = Problem::new(StatusCode::UNAUTHORIZED).with_detail(err.to_string());
+            return match HeaderValue::from_str(&err.challenge()) {
+                Ok(challenge) => problem.with_header(WWW_AUTHENTICATE, challenge),
+                Err(..) => problem,
+            };
+        }
+
+        macro_rules! known {
+            ($($ty:ty => $status:expr,)*) => {
+                $(
+                    if let Some(err) = r.find::<$ty>() {
+                        return Problem::new($status).with_detail(err.to_string());
+                    }
+                )*
+            };
+        }
+
+        known! {
+            BodyDeserializeError => StatusCode::BAD_REQUEST,
+            rejection::BodyDeserializeError => StatusCode::BAD_REQUEST,
+            rejection::InvalidPart => StatusCode::BAD_REQUEST,
+            rejection::InvalidHeader => StatusCode::BAD_REQUEST,
+            rejection::PartTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            InvalidQuery => StatusCode::BAD_REQUEST,
+            MissingHeader => StatusCode::BAD_REQUEST,
+            InvalidHeader => StatusCode::BAD_REQUEST,
+            MissingCookie => StatusCode::BAD_REQUEST,
+            CorsForbidden => StatusCode::FORBIDDEN,
+            LengthRequired => StatusCode::LENGTH_REQUIRED,
+            PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            rejection::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+        }
+
+        if r.is_not_found() {
+            return Problem::new(StatusCode::NOT_FOUND);
+        }
+
+        Problem::new(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.status, self.title)?;
+        if let Some(detail) = &self.detail {
+            write!(f, ": {}", detail)?;
+        }
+        Ok(())
+    }
+}
+
+impl Reject for Problem {}
+
+impl Reply for Problem {
+    fn into_response(mut self) -> Response {
+        let status = self.status_code();
+        let headers = std::mem::take(&mut self.headers);
+        let mut res = with_header(
+            with_status(json(&self), status),
+            CONTENT_TYPE,
+            CONTENT_TYPE_PROBLEM,
+        )
+        .into_response();
+        res.headers_mut().extend(headers);
+        res
+    }
+}
+
+impl ResponseError for Problem {
+    fn status_code(&self) -> StatusCode {
+        Problem::status_code(self)
+    }
+
+    fn error_response(&self) -> Response {
+        self.clone().into_response()
+    }
+
+    #[cfg(feature = "openapi")]
+    fn describe_error_responses(
+        comp_d: &mut crate::openapi::ComponentDescriptor,
+    ) -> crate::openapi::Responses {
+        <Self as crate::openapi::ResponseEntity>::describe_responses(comp_d)
+    }
+}
+
+/// Converts a rejection into an `application/problem+json` response.
+///
+/// This can be passed to [Filter::recover](crate::Filter::recover). See
+/// [Problem::from_rejection] for the conversion.
+pub async fn recover(r: Rejection) -> Result<Problem, Infallible> {
+    Ok(Problem::from_rejection(&r))
+}
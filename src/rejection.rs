@@ -0,0 +1,237 @@
+//! Rejections of extractors provided by rweb.
+//!
+//! These are converted into problems by
+//! [recover](crate::problem::recover).
+
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+use warp::reject::Reject;
+
+/// Rejection for a request body with an unexpected `Content-Type`.
+#[derive(Debug)]
+pub struct UnsupportedMediaType {
+    content_type: String,
+    expected: Vec<&'static str>,
+}
+
+impl UnsupportedMediaType {
+    pub(crate) fn new(content_type: String, expected: Vec<&'static str>) -> Self {
+        UnsupportedMediaType {
+            content_type,
+            expected,
+        }
+    }
+
+    /// `Content-Type` of the request.
+    pub fn content_type(&self) -> &str {
+        &self.content_type
+    }
+
+    /// The expected media types.
+    pub fn expected(&self) -> &[&'static str] {
+        &self.expected
+    }
+}
+
+impl Display for UnsupportedMediaType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Unsupported content-type {:?}, expected {}",
+            self.content_type,
+            self.expected.join(" or ")
+        )
+    }
+}
+
+impl Error for UnsupportedMediaType {}
+
+impl Reject for UnsupportedMediaType {}
+
+/// Rejection for a request body which cannot be deserialized.
+#[derive(Debug)]
+pub struct BodyDeserializeError {
+    cause: Box<dyn Error + Send + Sync>,
+}
+
+impl BodyDeserializeError {
+    pub(crate) fn new(cause: Box<dyn Error + Send + Sync>) -> Self {
+        BodyDeserializeError { cause }
+    }
+}
+
+impl Display for BodyDeserializeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Request body deserialize error: {}", self.cause)
+    }
+}
+
+impl Error for BodyDeserializeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.cause)
+    }
+}
+
+impl Reject for BodyDeserializeError {}
+
+/// Rejection for an invalid or missing part of a `multipart/form-data`
+/// request.
+#[derive(Debug)]
+pub struct InvalidPart {
+    name: String,
+    message: String,
+}
+
+impl InvalidPart {
+    #[cfg(feature = "multipart")]
+    pub(crate) fn new(name: impl Into<String>, message: impl Into<String>) -> Self {
+        InvalidPart {
+            name: name.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Name of the part.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Display for InvalidPart {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid part {:?}: {}", self.name, self.message)
+    }
+}
+
+impl Error for InvalidPart {}
+
+impl Reject for InvalidPart {}
+
+/// Rejection for a missing or invalid header of
+/// [TypedHeader](crate::headers::TypedHeader).
+#[derive(Debug)]
+pub struct InvalidHeader {
+    name: &'static str,
+    message: String,
+}
+
+impl InvalidHeader {
+    pub(crate) fn new(name: &'static str, message: impl Into<String>) -> Self {
+        InvalidHeader {
+            name,
+            message: message.into(),
+        }
+    }
+
+    /// Name of the header.
+    pub fn name(&self) -> &str {
+        self.name
+    }
+}
+
+impl Display for InvalidHeader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid header {:?}: {}", self.name, self.message)
+    }
+}
+
+impl Error for InvalidHeader {}
+
+impl Reject for InvalidHeader {}
+
+/// Rejection for a part of a `multipart/form-data` request which is larger
+/// than its limit.
+#[derive(Debug)]
+pub struct PartTooLarge {
+    name: String,
+    max_size: u64,
+}
+
+impl PartTooLarge {
+    #[cfg(feature = "multipart")]
+    pub(crate) fn new(name: impl Into<String>, max_size: u64) -> Self {
+        PartTooLarge {
+            name: name.into(),
+            max_size,
+        }
+    }
+
+    /// Name of the part.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The limit in bytes.
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+}
+
+impl Display for PartTooLarge {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Part {:?} is larger than {} bytes",
+            self.name, self.max_size
+        )
+    }
+}
+
+impl Error for PartTooLarge {}
+
+impl Reject for PartTooLarge {}
+
+/// Rejection for a request without valid credentials, which is replied with
+/// `401 Unauthorized` and a `WWW-Authenticate` challenge.
+#[derive(Debug)]
+pub struct Unauthorized {
+    scheme: &'static str,
+    /// Description of the invalid credentials. `None` if they are missing.
+    invalid: Option<String>,
+}
+
+impl Unauthorized {
+    /// Credentials of `scheme` are missing.
+    pub(crate) fn missing(scheme: &'static str) -> Self {
+        Unauthorized {
+            scheme,
+            invalid: None,
+        }
+    }
+
+    /// Credentials of `scheme` are invalid.
+    pub(crate) fn invalid(scheme: &'static str, description: impl Into<String>) -> Self {
+        Unauthorized {
+            scheme,
+            invalid: Some(description.into()),
+        }
+    }
+
+    /// Value of the `WWW-Authenticate` header, as described in
+    /// [RFC 6750](https://tools.ietf.org/html/rfc6750#section-3).
+    pub fn challenge(&self) -> String {
+        match &self.invalid {
+            None => self.scheme.to_string(),
+            Some(description) => format!(
+                "{} error=\"invalid_token\", error_description=\"{}\"",
+                self.scheme,
+                description.replace('\\', "\\\\").replace('"', "\\\"")
+            ),
+        }
+    }
+}
+
+impl Display for Unauthorized {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.invalid {
+            None => write!(f, "Missing {} credentials", self.scheme),
+            Some(description) => write!(f, "Invalid {} credentials: {}", self.scheme, description),
+        }
+    }
+}
+
+impl Error for Unauthorized {}
+
+impl Reject for Unauthorized {}
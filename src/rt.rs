@@ -1,13 +1,1161 @@
-pub use http::StatusCode;
+use crate::{
+    validation::{Constraints, Violation, Violations},
+    FromRequest, ResponseError,
+};
+use futures::future::ready;
+pub use futures::future::BoxFuture;
+use http::header::{HeaderValue, ALLOW};
+pub use http::{Method, StatusCode};
 pub use indexmap::{indexmap, IndexMap};
+use percent_encoding::percent_decode_str;
+use regex::Regex;
+#[cfg(feature = "openapi")]
+use serde::Serialize;
 pub use serde_json;
-use std::convert::Infallible;
 pub use std::{borrow::Cow, clone::Clone, default::Default};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque},
+    convert::Infallible,
+    marker::PhantomData,
+    str::FromStr,
+    sync::Arc,
+};
 pub use tokio;
-use warp::{any, Filter};
+use warp::{
+    any,
+    filters::BoxedFilter,
+    path::{FullPath, Peek, Tail},
+    reject,
+    reply::{Reply, Response},
+    Filter, Rejection,
+};
 
 pub fn provider<T: Clone + Send + Sync>(
     data: T,
 ) -> impl Filter<Extract = (T,), Error = Infallible> + Clone {
     any().map(move || data.clone())
 }
+
+/// Path parameter with a constraint like `{id:[0-9]+}`.
+///
+/// Segments which don't match `pattern` are rejected before being parsed as
+/// `T`, so other routes get a chance to handle them.
+pub fn constrained_param<T>(
+    pattern: &'static str,
+) -> impl Filter<Extract = (T,), Error = Rejection> + Clone
+where
+    T: FromStr + Send + 'static,
+{
+    let pattern = Arc::new(Regex::new(pattern).expect("invalid path constraint"));
+
+    warp::path::param::<String>().and_then(move |segment: String| {
+        let res = if pattern.is_match(&segment) {
+            segment.parse::<T>().map_err(|_| reject::not_found())
+        } else {
+            Err(reject::not_found())
+        };
+
+        ready(res)
+    })
+}
+
+/// Catch-all path parameter like `{*rest}`.
+///
+/// The remaining path is percent-decoded and normalized, so empty and `.`
+/// segments are removed. Paths which try to escape the route, like
+/// `../etc/passwd`, are rejected.
+pub fn tail_param<T>() -> impl Filter<Extract = (T,), Error = Rejection> + Clone
+where
+    T: FromStr + Send + 'static,
+{
+    warp::path::tail().and_then(|tail: Tail| {
+        let res = normalize_tail(tail.as_str())
+            .and_then(|path| path.parse::<T>().ok())
+            .ok_or_else(reject::not_found);
+
+        ready(res)
+    })
+}
+
+fn normalize_tail(tail: &str) -> Option<String> {
+    let mut segments = vec![];
+
+    for segment in tail.split('/') {
+        let segment = percent_decode_str(segment).decode_utf8().ok()?;
+        if segment == ".." || segment.contains(&['/', '\\', '\0'][..]) {
+            return None;
+        }
+        if segment.is_empty() || segment == "." {
+            continue;
+        }
+        segments.push(segment);
+    }
+
+    Some(segments.join("/"))
+}
+
+/// A character of the name of a path parameter.
+///
+/// Names are spelled at type level, so `org_id` is
+/// `(Ch<'o'>, Ch<'r'>, Ch<'g'>, Ch<'_'>, Ch<'i'>, Ch<'d'>)`.
+pub struct Ch<const C: char>;
+
+/// Path parameter `Name` of type `T`, which is the segment `IDX` of a router
+/// path.
+pub struct RouterParam<Name, T, const IDX: usize>(PhantomData<(Name, T)>);
+
+/// Path parameter `Name` of type `T` of an enclosing router, used with
+/// `#[router_param]`.
+pub struct UsesRouterParam<Name, T>(PhantomData<(Name, T)>);
+
+/// Path parameters available to the services of a `#[router]`.
+///
+/// `Params` is a list like `(RouterParam<..>, (RouterParam<..>, ()))`,
+/// `Parent` is the scope of the enclosing router and `SEGMENTS` is the number
+/// of segments of the router path.
+pub struct RouterScope<Params, Parent, const SEGMENTS: usize> {
+    parent: Parent,
+    _params: PhantomData<Params>,
+}
+
+impl<Params, Parent, const SEGMENTS: usize> RouterScope<Params, Parent, SEGMENTS> {
+    pub fn new(parent: Parent) -> Self {
+        RouterScope {
+            parent,
+            _params: PhantomData,
+        }
+    }
+}
+
+/// Scope of a `#[router]` which is not mounted by another router.
+pub struct NoRouterParams;
+
+/// Parameters of outer routers declared by a `#[router]` with
+/// `#[router_param]`, with their offsets.
+///
+/// `Params` is a list like `(UsesRouterParam<..>, (UsesRouterParam<..>, ()))`.
+pub struct OuterRouterParams<Params> {
+    offsets: Vec<usize>,
+    _params: PhantomData<Params>,
+}
+
+impl<Params> OuterRouterParams<Params> {
+    pub fn new(offsets: &[usize]) -> Self {
+        OuterRouterParams {
+            offsets: offsets.to_vec(),
+            _params: PhantomData,
+        }
+    }
+}
+
+/// Position of a parameter at the start of a list.
+pub struct Here;
+
+/// Position of a parameter after the first one of a list.
+pub struct Next<I>(PhantomData<I>);
+
+/// Position of a parameter in the router of a scope.
+pub struct Own<I>(PhantomData<I>);
+
+/// Position of a parameter in the enclosing router.
+pub struct Outer<I>(PhantomData<I>);
+
+/// Implemented by a list of [RouterParam] which contains `Name` of type `T`.
+pub trait RouterParamList<Name, T, I> {
+    /// Index of the segment.
+    const IDX: usize;
+}
+
+impl<Name, T, Rest, const IDX: usize> RouterParamList<Name, T, Here>
+    for (RouterParam<Name, T, IDX>, Rest)
+{
+    const IDX: usize = IDX;
+}
+
+impl<Name, T, I, First, Rest> RouterParamList<Name, T, Next<I>> for (First, Rest)
+where
+    Rest: RouterParamList<Name, T, I>,
+{
+    const IDX: usize = Rest::IDX;
+}
+
+/// Implemented by a list of [UsesRouterParam] which contains `Name` of type
+/// `T`.
+pub trait UsesRouterParamList<Name, T, I> {
+    /// Position in the list.
+    const POS: usize;
+}
+
+impl<Name, T, Rest> UsesRouterParamList<Name, T, Here> for (UsesRouterParam<Name, T>, Rest) {
+    const POS: usize = 0;
+}
+
+impl<Name, T, I, First, Rest> UsesRouterParamList<Name, T, Next<I>> for (First, Rest)
+where
+    Rest: UsesRouterParamList<Name, T, I>,
+{
+    const POS: usize = Rest::POS + 1;
+}
+
+/// Implemented by a scope which has the path parameter `Name` of type `T`.
+/// `I` is inferred by the compiler.
+#[diagnostic::on_unimplemented(
+    message = "a `#[router_param]` is not a path parameter of the enclosing `#[router]`",
+    label = "the parameter is missing from this router, or has another type",
+    note = "routers mounted by another router should declare parameters of outer routers \
+            with `#[router_param]`"
+)]
+pub trait HasRouterParam<Name, T, I> {
+    /// Number of segments following the parameter in the path matched by
+    /// routers of the scope.
+    fn offset(&self) -> usize;
+}
+
+impl<Name, T, I, Params, Parent, const SEGMENTS: usize> HasRouterParam<Name, T, Own<I>>
+    for RouterScope<Params, Parent, SEGMENTS>
+where
+    Params: RouterParamList<Name, T, I>,
+{
+    fn offset(&self) -> usize {
+        SEGMENTS - Params::IDX - 1
+    }
+}
+
+impl<Name, T, I, Params, Parent, const SEGMENTS: usize> HasRouterParam<Name, T, Outer<I>>
+    for RouterScope<Params, Parent, SEGMENTS>
+where
+    Parent: HasRouterParam<Name, T, I>,
+{
+    fn offset(&self) -> usize {
+        self.parent.offset() + SEGMENTS
+    }
+}
+
+impl<Name, T, I, Params> HasRouterParam<Name, T, I> for OuterRouterParams<Params>
+where
+    Params: UsesRouterParamList<Name, T, I>,
+{
+    fn offset(&self) -> usize {
+        self.offsets[Params::POS]
+    }
+}
+
+/// Implemented by a scope which has all parameters of `Params`, a list of
+/// [UsesRouterParam].
+#[diagnostic::on_unimplemented(
+    message = "a `#[router_param]` is not a path parameter of the enclosing `#[router]`",
+    label = "a parameter used by this service is missing from the router, or has another type",
+    note = "routers mounted by another router should declare parameters of outer routers \
+            with `#[router_param]`"
+)]
+pub trait ProvidesRouterParams<Params, I> {
+    /// Appends offsets of `Params`, in order.
+    fn offsets(&self, offsets: &mut Vec<usize>);
+}
+
+impl<S> ProvidesRouterParams<(), ()> for S {
+    fn offsets(&self, _: &mut Vec<usize>) {}
+}
+
+impl<S, Name, T, Rest, I, IR> ProvidesRouterParams<(UsesRouterParam<Name, T>, Rest), (I, IR)> for S
+where
+    S: HasRouterParam<Name, T, I> + ProvidesRouterParams<Rest, IR>,
+{
+    fn offsets(&self, offsets: &mut Vec<usize>) {
+        offsets.push(self.offset());
+        ProvidesRouterParams::<Rest, IR>::offsets(self, offsets);
+    }
+}
+
+/// Creates a filter from offsets of path parameters.
+type BuildFilter<F> = Box<dyn FnOnce(&[usize]) -> F>;
+
+/// A service which uses `Params`, path parameters of enclosing routers.
+///
+/// Returned by handlers with `#[router_param]`. It can only be mounted by a
+/// `#[router]`, which checks that it has the parameters.
+pub struct WithRouterParams<F, Params> {
+    build: BuildFilter<F>,
+    _params: PhantomData<Params>,
+}
+
+impl<F, Params> WithRouterParams<F, Params> {
+    /// `build` creates the filter from the offsets of `Params`.
+    pub fn new<B>(build: B) -> Self
+    where
+        B: 'static + FnOnce(&[usize]) -> F,
+    {
+        WithRouterParams {
+            build: Box::new(build),
+            _params: PhantomData,
+        }
+    }
+}
+
+/// A service of a `#[router]`.
+pub trait Mount {
+    /// Path parameters of enclosing routers used by the service.
+    type Params;
+    type Filter;
+
+    fn mount(self, offsets: &[usize]) -> Self::Filter;
+}
+
+impl<F> Mount for F
+where
+    F: Filter,
+{
+    type Params = ();
+    type Filter = F;
+
+    fn mount(self, _: &[usize]) -> F {
+        self
+    }
+}
+
+impl<F, Params> Mount for WithRouterParams<F, Params> {
+    type Params = Params;
+    type Filter = F;
+
+    fn mount(self, offsets: &[usize]) -> F {
+        (self.build)(offsets)
+    }
+}
+
+/// Used by `#[router]` to mount a service in `scope`.
+pub fn mount<S, I, M>(scope: &S, service: M) -> M::Filter
+where
+    M: Mount,
+    S: ProvidesRouterParams<M::Params, I>,
+{
+    let mut offsets = vec![];
+    scope.offsets(&mut offsets);
+    service.mount(&offsets)
+}
+
+/// Path parameter declared by an enclosing `#[router]`, like
+/// `#[router_param] org_id: u64`.
+///
+/// `offset` is the number of segments following the parameter in the path
+/// matched by routers. The segment is percent-decoded before it is parsed.
+pub fn router_param<T>(offset: usize) -> impl Filter<Extract = (T,), Error = Rejection> + Clone
+where
+    T: FromStr + Send + 'static,
+{
+    warp::path::full()
+        .and(warp::path::peek())
+        .and_then(move |full: FullPath, rest: Peek| {
+            // Path matched by routers until now.
+            let matched = &full.as_str()[..full.as_str().len() - rest.as_str().len()];
+            let res = matched
+                .split('/')
+                .filter(|s| !s.is_empty())
+                .rev()
+                .nth(offset)
+                .and_then(|segment| {
+                    let segment = percent_decode_str(segment).decode_utf8().ok()?;
+                    segment.parse::<T>().ok()
+                })
+                .ok_or_else(reject::not_found);
+
+            ready(res)
+        })
+}
+
+/// Segments and method of a route.
+type RouteEntry = (Vec<RouteSegment>, Method);
+
+thread_local! {
+    /// Routes created while a `#[router]` is being created.
+    static ROUTES: RefCell<Option<Vec<RouteEntry>>> = const { RefCell::new(None) };
+}
+
+/// A segment of a route path, as parsed by `#[get]`, `#[post]`, ...
+#[derive(Debug, Clone, Copy)]
+pub enum RouteSegment {
+    Static(&'static str),
+    /// A path parameter, with the regular expression of its constraint.
+    Param(Option<&'static str>),
+    /// A catch-all path parameter, which matches the remaining path.
+    Tail,
+}
+
+/// Used by `#[get]`, `#[post]`, ... to let an enclosing `#[router]` know the
+/// methods allowed for the path with `segments`.
+pub fn register_route(segments: &[RouteSegment], method: Method) {
+    ROUTES.with(|routes| {
+        if let Some(routes) = &mut *routes.borrow_mut() {
+            routes.push((segments.to_vec(), method));
+        }
+    })
+}
+
+/// Routes of a `#[router]`, used to respond to requests with a wrong method.
+#[derive(Clone)]
+pub struct Routes(Arc<Vec<Route>>);
+
+struct Route {
+    segments: Vec<PatternSegment>,
+    method: Method,
+}
+
+enum PatternSegment {
+    Static(&'static str),
+    Param(Option<Regex>),
+    Tail,
+}
+
+impl Route {
+    fn new(segments: &[RouteSegment], method: Method) -> Self {
+        let segments = segments
+            .iter()
+            .map(|segment| match *segment {
+                RouteSegment::Static(s) => PatternSegment::Static(s),
+                RouteSegment::Param(pattern) => PatternSegment::Param(
+                    pattern.map(|p| Regex::new(p).expect("invalid path constraint")),
+                ),
+                RouteSegment::Tail => PatternSegment::Tail,
+            })
+            .collect();
+
+        Route { segments, method }
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        let mut segments = path.split('/').filter(|s| !s.is_empty());
+
+        for pattern in &self.segments {
+            let segment = match pattern {
+                PatternSegment::Tail => return true,
+                _ => match segments.next() {
+                    Some(segment) => segment,
+                    None => return false,
+                },
+            };
+
+            let matched = match pattern {
+                PatternSegment::Static(s) => *s == segment,
+                PatternSegment::Param(Some(pattern)) => pattern.is_match(segment),
+                PatternSegment::Param(None) | PatternSegment::Tail => true,
+            };
+            if !matched {
+                return false;
+            }
+        }
+
+        segments.next().is_none()
+    }
+}
+
+/// Used by `#[router]` to collect routes created by `op`.
+///
+/// Collected routes are also registered to the enclosing router, with
+/// `prefix` prepended.
+pub fn collect_routes<F, Ret>(prefix: &[RouteSegment], op: F) -> (Ret, Routes)
+where
+    F: FnOnce() -> Ret,
+{
+    struct Restore(Option<Vec<RouteEntry>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let orig = self.0.take();
+            ROUTES.with(|routes| *routes.borrow_mut() = orig);
+        }
+    }
+
+    let restore = Restore(ROUTES.with(|routes| routes.borrow_mut().replace(vec![])));
+    let ret = op();
+    let collected = ROUTES
+        .with(|routes| routes.borrow_mut().take())
+        .unwrap_or_default();
+    drop(restore);
+
+    for (segments, method) in &collected {
+        register_route(&[prefix, segments].concat(), method.clone());
+    }
+
+    let routes = collected
+        .into_iter()
+        .map(|(segments, method)| Route::new(&segments, method))
+        .collect();
+
+    (ret, Routes(Arc::new(routes)))
+}
+
+/// Handles requests rejected by `services`.
+///
+/// If the remaining path matches one of `routes` but the method doesn't, this
+/// responds with `405 Method Not Allowed`, or `204 No Content` for `OPTIONS`
+/// requests. The `Allow` header contains the methods of the matching routes.
+/// Otherwise, the rejection of `services` is kept.
+pub fn method_not_allowed<F, R>(
+    routes: Routes,
+    services: F,
+) -> impl Filter<Extract = (Response,), Error = Rejection> + Clone
+where
+    F: Filter<Extract = (R,), Error = Rejection> + Clone + Send + Sync + 'static,
+    R: Reply,
+{
+    let services = services
+        .map(|reply: R| Ok(reply.into_response()))
+        .or_else(|rejection| ready(Ok::<_, Rejection>((Err(rejection),))));
+
+    warp::method()
+        .and(warp::path::peek())
+        .and(services)
+        .and_then(
+            move |method: Method, rest: Peek, res: Result<Response, Rejection>| {
+                let rejection = match res {
+                    Ok(res) => return ready(Ok(res)),
+                    Err(rejection) => rejection,
+                };
+
+                let mut allowed: Vec<&Method> = vec![];
+                for route in routes.0.iter() {
+                    if route.matches(rest.as_str()) && !allowed.contains(&&route.method) {
+                        allowed.push(&route.method);
+                    }
+                }
+
+                if allowed.is_empty() || allowed.contains(&&method) {
+                    return ready(Err(rejection));
+                }
+
+                let status = if method == Method::OPTIONS {
+                    StatusCode::NO_CONTENT
+                } else {
+                    StatusCode::METHOD_NOT_ALLOWED
+                };
+                let mut allow: Vec<_> = allowed.iter().map(|m| m.as_str()).collect();
+                allow.push(Method::OPTIONS.as_str());
+
+                let mut res = Response::new(Default::default());
+                *res.status_mut() = status;
+                res.headers_mut().insert(
+                    ALLOW,
+                    HeaderValue::from_str(&allow.join(", ")).expect("invalid method"),
+                );
+                ready(Ok(res))
+            },
+        )
+}
+
+/// Used by `#[get]`, `#[post]`, ... to convert the result of a handler
+/// returning `Result<T, E>` into a response.
+///
+/// If `E` implements [ResponseError], methods of [ViaResponseError] are used.
+/// Otherwise, methods of [ViaReply] are used.
+pub struct Tag<T>(PhantomData<T>);
+
+impl<T> Tag<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Tag(PhantomData)
+    }
+}
+
+pub fn tag<T>(_: &T) -> Tag<T> {
+    Tag::new()
+}
+
+pub trait ViaResponseError {
+    type Result;
+    type Ok;
+    type Err;
+
+    fn reply(&self, res: Self::Result) -> Response;
+
+    fn try_reply(&self, res: Self::Result) -> Result<Response, Rejection>;
+}
+
+impl<T, E> ViaResponseError for Tag<Result<T, E>>
+where
+    T: Reply,
+    E: ResponseError,
+{
+    type Result = Result<T, E>;
+    type Ok = T;
+    type Err = E;
+
+    fn reply(&self, res: Self::Result) -> Response {
+        match res {
+            Ok(v) => v.into_response(),
+            Err(err) => err.error_response(),
+        }
+    }
+
+    fn try_reply(&self, res: Self::Result) -> Result<Response, Rejection> {
+        Ok(self.reply(res))
+    }
+}
+
+pub trait ViaReply {
+    type Result;
+
+    fn reply(&self, res: Self::Result) -> Response
+    where
+        Self::Result: Reply;
+
+    fn try_reply(&self, res: Self::Result) -> Self::Result;
+}
+
+impl<T, E> ViaReply for &Tag<Result<T, E>> {
+    type Result = Result<T, E>;
+
+    fn reply(&self, res: Self::Result) -> Response
+    where
+        Self::Result: Reply,
+    {
+        res.into_response()
+    }
+
+    fn try_reply(&self, res: Self::Result) -> Self::Result {
+        res
+    }
+}
+
+/// Used to document the responses of a handler returning `Result<T, E>`.
+///
+/// Called as `(&&&Tag::<Result<T, E>>::new()).add_responses_to(..)`, so the
+/// first applicable trait of
+///
+///  - [ViaResponseEntity], if `E` implements
+///    [ResponseEntity](crate::openapi::ResponseEntity),
+///  - [ViaErrorEntity], if `E` implements [ResponseError] and
+///    [Entity](crate::openapi::Entity),
+///  - [ViaErrorStatus], if `E` only implements [ResponseError],
+///
+/// is used. In the last case, the error responses are not documented.
+#[cfg(feature = "openapi")]
+pub trait ViaResponseEntity {
+    fn add_responses_to(
+        &self,
+        collector: &mut crate::openapi::Collector,
+        op: &mut crate::openapi::Operation,
+    );
+}
+
+#[cfg(feature = "openapi")]
+impl<T, E> ViaResponseEntity for &&Tag<Result<T, E>>
+where
+    T: crate::openapi::ResponseEntity,
+    E: crate::openapi::ResponseEntity,
+{
+    fn add_responses_to(
+        &self,
+        collector: &mut crate::openapi::Collector,
+        op: &mut crate::openapi::Operation,
+    ) {
+        collector.add_response_to::<Result<T, E>>(op)
+    }
+}
+
+/// See [ViaResponseEntity].
+#[cfg(feature = "openapi")]
+pub trait ViaErrorEntity {
+    fn add_responses_to(
+        &self,
+        collector: &mut crate::openapi::Collector,
+        op: &mut crate::openapi::Operation,
+    );
+}
+
+#[cfg(feature = "openapi")]
+impl<T, E> ViaErrorEntity for &Tag<Result<T, E>>
+where
+    T: crate::openapi::ResponseEntity,
+    E: ResponseError + crate::openapi::Entity,
+{
+    fn add_responses_to(
+        &self,
+        collector: &mut crate::openapi::Collector,
+        op: &mut crate::openapi::Operation,
+    ) {
+        collector.add_error_responses_to::<T, E>(op)
+    }
+}
+
+/// See [ViaResponseEntity].
+#[cfg(feature = "openapi")]
+pub trait ViaErrorStatus {
+    fn add_responses_to(
+        &self,
+        collector: &mut crate::openapi::Collector,
+        op: &mut crate::openapi::Operation,
+    );
+}
+
+#[cfg(feature = "openapi")]
+impl<T, E> ViaErrorStatus for Tag<Result<T, E>>
+where
+    T: crate::openapi::ResponseEntity,
+    E: ResponseError,
+{
+    fn add_responses_to(
+        &self,
+        collector: &mut crate::openapi::Collector,
+        op: &mut crate::openapi::Operation,
+    ) {
+        collector.add_response_to::<T>(op)
+    }
+}
+
+/// Used by `#[derive(Schema)]` to describe the default value of a field.
+///
+/// If `T` implements [Serialize], methods of [ViaSerialize] are used.
+/// Otherwise, methods of [ViaNoSerialize] are used and the default value is
+/// not documented.
+#[cfg(feature = "openapi")]
+pub trait ViaSerialize {
+    type Value;
+
+    fn to_json(&self, v: &Self::Value) -> Option<serde_json::Value>;
+}
+
+#[cfg(feature = "openapi")]
+impl<T> ViaSerialize for Tag<T>
+where
+    T: Serialize,
+{
+    type Value = T;
+
+    fn to_json(&self, v: &T) -> Option<serde_json::Value> {
+        serde_json::to_value(v).ok()
+    }
+}
+
+#[cfg(feature = "openapi")]
+pub trait ViaNoSerialize {
+    type Value;
+
+    fn to_json(&self, v: &Self::Value) -> Option<serde_json::Value>;
+}
+
+#[cfg(feature = "openapi")]
+impl<T> ViaNoSerialize for &Tag<T> {
+    type Value = T;
+
+    fn to_json(&self, _: &T) -> Option<serde_json::Value> {
+        None
+    }
+}
+
+/// Used by `#[derive(Schema)]` to merge the schema of a `#[serde(flatten)]`
+/// field into the struct.
+///
+/// Properties are merged, maps set `additionalProperties` and enums are added
+/// to `allOf`.
+#[cfg(feature = "openapi")]
+pub fn flatten_schema(
+    comp_d: &crate::openapi::ComponentDescriptor,
+    schema: &crate::openapi::ComponentOrInlineSchema,
+    fields: &mut IndexMap<Cow<'static, str>, crate::openapi::ComponentOrInlineSchema>,
+    required_fields: &mut Vec<Cow<'static, str>>,
+    additional_properties: &mut Option<Box<crate::openapi::ComponentOrInlineSchema>>,
+    all_of: &mut Vec<crate::openapi::ComponentOrInlineSchema>,
+) {
+    let s = comp_d.get_unpack(schema).clone();
+    // Fields of an `Option<T>` are not required even if they are required by `T`.
+    if s.nullable != Some(true) {
+        required_fields.extend(s.required);
+    }
+    for (name, prop) in s.properties {
+        fields.entry(name).or_insert(prop);
+    }
+    if s.additional_properties.is_some() {
+        *additional_properties = s.additional_properties;
+    }
+    if !s.one_of.is_empty() || !s.any_of.is_empty() || !s.all_of.is_empty() {
+        all_of.push(crate::openapi::ComponentOrInlineSchema::Inline(
+            crate::openapi::Schema {
+                one_of: s.one_of,
+                any_of: s.any_of,
+                all_of: s.all_of,
+                ..Default::default()
+            },
+        ));
+    }
+}
+
+/// Used by `#[get]`, `#[post]`, ... to check constraints of parameters.
+///
+/// If `T` implements [Constraints], methods of [ViaConstraints] are used and
+/// requests violating them are rejected with [Violations]. Otherwise, methods
+/// of [ViaNoConstraints] are used.
+pub trait ViaConstraints {
+    type Value;
+
+    fn filter(&self) -> BoxedFilter<(Self::Value,)>;
+}
+
+impl<T> ViaConstraints for Tag<T>
+where
+    T: Constraints + FromRequest + Send + 'static,
+    T::Filter: Filter<Extract = (T,), Error = Rejection> + Send + Sync + 'static,
+{
+    type Value = T;
+
+    fn filter(&self) -> BoxedFilter<(T,)> {
+        T::new()
+            .and_then(|v: T| {
+                let mut violations = vec![];
+                v.check_constraints("", &mut violations);
+
+                ready(if violations.is_empty() {
+                    Ok(v)
+                } else {
+                    Err(reject::custom(Violations { violations }))
+                })
+            })
+            .boxed()
+    }
+}
+
+pub trait ViaNoConstraints {
+    type Filter;
+
+    fn filter(&self) -> Self::Filter;
+}
+
+impl<T> ViaNoConstraints for &Tag<T>
+where
+    T: FromRequest,
+{
+    type Filter = T::Filter;
+
+    fn filter(&self) -> T::Filter {
+        T::new()
+    }
+}
+
+/// Used by `#[derive(Schema)]` to check constraints of fields.
+///
+/// If `T` implements [Constraints], methods of [ViaFieldConstraints] are used.
+/// Otherwise, methods of [ViaNoFieldConstraints] are used.
+pub trait ViaFieldConstraints {
+    type Value;
+
+    fn check_constraints(
+        &self,
+        v: &Self::Value,
+        path: &str,
+        name: &str,
+        violations: &mut Vec<Violation>,
+    );
+}
+
+impl<T> ViaFieldConstraints for Tag<T>
+where
+    T: Constraints,
+{
+    type Value = T;
+
+    fn check_constraints(&self, v: &T, path: &str, name: &str, violations: &mut Vec<Violation>) {
+        v.check_constraints(&join_path(path, name), violations)
+    }
+}
+
+pub trait ViaNoFieldConstraints {
+    type Value;
+
+    fn check_constraints(
+        &self,
+        v: &Self::Value,
+        path: &str,
+        name: &str,
+        violations: &mut Vec<Violation>,
+    );
+}
+
+impl<T> ViaNoFieldConstraints for &Tag<T> {
+    type Value = T;
+
+    fn check_constraints(&self, _: &T, _: &str, _: &str, _: &mut Vec<Violation>) {}
+}
+
+/// Appends `name` to the JSON pointer `path`.
+fn join_path(path: &str, name: &str) -> String {
+    if name.is_empty() {
+        return path.to_string();
+    }
+    format!("{}/{}", path, name.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Value of `min` and `max` constraints.
+pub trait Number {
+    fn to_f64(&self) -> Option<f64>;
+}
+
+macro_rules! number {
+    ($($T:ty),*) => {
+        $(
+            impl Number for $T {
+                fn to_f64(&self) -> Option<f64> {
+                    Some(*self as f64)
+                }
+            }
+        )*
+    };
+}
+
+number!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+impl<T> Number for Option<T>
+where
+    T: Number,
+{
+    fn to_f64(&self) -> Option<f64> {
+        self.as_ref().and_then(Number::to_f64)
+    }
+}
+
+/// Value of `min_length`, `max_length`, `pattern` and `enum_values`
+/// constraints.
+pub trait Text {
+    fn text(&self) -> Option<&str>;
+}
+
+impl Text for str {
+    fn text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl Text for String {
+    fn text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl Text for Box<str> {
+    fn text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl Text for Cow<'_, str> {
+    fn text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl<T> Text for Option<T>
+where
+    T: Text,
+{
+    fn text(&self) -> Option<&str> {
+        self.as_ref().and_then(Text::text)
+    }
+}
+
+/// Value of `min_items` and `max_items` constraints.
+pub trait Items {
+    fn items(&self) -> Option<usize>;
+}
+
+macro_rules! items {
+    ($($T:ty),*) => {
+        $(
+            impl<T> Items for $T {
+                fn items(&self) -> Option<usize> {
+                    Some(self.len())
+                }
+            }
+        )*
+    };
+}
+
+items!(
+    [T],
+    Vec<T>,
+    VecDeque<T>,
+    LinkedList<T>,
+    HashSet<T>,
+    BTreeSet<T>
+);
+
+impl<K, V> Items for HashMap<K, V> {
+    fn items(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<K, V> Items for BTreeMap<K, V> {
+    fn items(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<K, V> Items for IndexMap<K, V> {
+    fn items(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T> Items for Option<T>
+where
+    T: Items,
+{
+    fn items(&self) -> Option<usize> {
+        self.as_ref().and_then(Items::items)
+    }
+}
+
+pub fn check_min<T>(v: &T, min: f64, path: &str, name: &str, violations: &mut Vec<Violation>)
+where
+    T: ?Sized + Number,
+{
+    match v.to_f64() {
+        Some(v) if v < min => violations.push(Violation::new(
+            join_path(path, name),
+            "min",
+            format!("should be at least {}", min),
+        )),
+        _ => {}
+    }
+}
+
+pub fn check_max<T>(v: &T, max: f64, path: &str, name: &str, violations: &mut Vec<Violation>)
+where
+    T: ?Sized + Number,
+{
+    match v.to_f64() {
+        Some(v) if v > max => violations.push(Violation::new(
+            join_path(path, name),
+            "max",
+            format!("should be at most {}", max),
+        )),
+        _ => {}
+    }
+}
+
+pub fn check_min_length<T>(
+    v: &T,
+    min: usize,
+    path: &str,
+    name: &str,
+    violations: &mut Vec<Violation>,
+) where
+    T: ?Sized + Text,
+{
+    match v.text() {
+        Some(v) if v.chars().count() < min => violations.push(Violation::new(
+            join_path(path, name),
+            "min_length",
+            format!("length should be at least {}", min),
+        )),
+        _ => {}
+    }
+}
+
+pub fn check_max_length<T>(
+    v: &T,
+    max: usize,
+    path: &str,
+    name: &str,
+    violations: &mut Vec<Violation>,
+) where
+    T: ?Sized + Text,
+{
+    match v.text() {
+        Some(v) if v.chars().count() > max => violations.push(Violation::new(
+            join_path(path, name),
+            "max_length",
+            format!("length should be at most {}", max),
+        )),
+        _ => {}
+    }
+}
+
+thread_local! {
+    /// Compiled patterns of `pattern` constraints.
+    static PATTERNS: RefCell<HashMap<&'static str, Regex>> = RefCell::new(HashMap::new());
+}
+
+pub fn check_pattern<T>(
+    v: &T,
+    pattern: &'static str,
+    path: &str,
+    name: &str,
+    violations: &mut Vec<Violation>,
+) where
+    T: ?Sized + Text,
+{
+    let v = match v.text() {
+        Some(v) => v,
+        None => return,
+    };
+    let matched = PATTERNS.with(|patterns| {
+        patterns
+            .borrow_mut()
+            .entry(pattern)
+            .or_insert_with(|| Regex::new(pattern).expect("invalid pattern"))
+            .is_match(v)
+    });
+    if !matched {
+        violations.push(Violation::new(
+            join_path(path, name),
+            "pattern",
+            format!("should match `{}`", pattern),
+        ))
+    }
+}
+
+pub fn check_enum_values<T>(
+    v: &T,
+    values: &[&str],
+    path: &str,
+    name: &str,
+    violations: &mut Vec<Violation>,
+) where
+    T: ?Sized + Text,
+{
+    match v.text() {
+        Some(v) if !values.contains(&v) => violations.push(Violation::new(
+            join_path(path, name),
+            "enum_values",
+            format!("should be one of {:?}", values),
+        )),
+        _ => {}
+    }
+}
+
+pub fn check_min_items<T>(
+    v: &T,
+    min: usize,
+    path: &str,
+    name: &str,
+    violations: &mut Vec<Violation>,
+) where
+    T: ?Sized + Items,
+{
+    match v.items() {
+        Some(len) if len < min => violations.push(Violation::new(
+            join_path(path, name),
+            "min_items",
+            format!("should have at least {} items", min),
+        )),
+        _ => {}
+    }
+}
+
+pub fn check_max_items<T>(
+    v: &T,
+    max: usize,
+    path: &str,
+    name: &str,
+    violations: &mut Vec<Violation>,
+) where
+    T: ?Sized + Items,
+{
+    match v.items() {
+        Some(len) if len > max => violations.push(Violation::new(
+            join_path(path, name),
+            "max_items",
+            format!("should have at most {} items", max),
+        )),
+        _ => {}
+    }
+}
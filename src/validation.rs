@@ -0,0 +1,246 @@
+//! Validation of requests.
+//!
+//! Types deriving `Schema` with `#[schema(validate)]` implement [Constraints].
+//! If such a type is extracted with [Json](crate::Json),
+//! [Form](crate::Form) or [Query](crate::Query), the request is rejected with
+//! [Violations] when any constraint is violated.
+//!
+//! Like other rejections, [Violations] should be converted into a response by
+//! [Filter::recover](crate::Filter::recover).
+//! [problem::recover](crate::problem::recover) responds with
+//! `422 Unprocessable Entity` and the list of violations. Without a recover,
+//! warp responds with `500 Internal Server Error`.
+//!
+//! Other rules can be checked by implementing [Validate] and extracting
+//! [Validated](crate::Validated).
+//!
+//! # Example
+//!
+//! ```rust
+//! use rweb::{validation::Violations, *};
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, Deserialize, Schema)]
+//! #[schema(validate)]
+//! struct CreateUser {
+//!     #[schema(min_length = 1, max_length = 32, pattern = "^[a-z0-9_]+$")]
+//!     name: String,
+//!     #[schema(min = 13, max = 150)]
+//!     age: u8,
+//!     #[schema(enum_values("admin", "member"))]
+//!     role: String,
+//!     #[schema(max_items = 8)]
+//!     tags: Vec<String>,
+//! }
+//!
+//! #[post("/users")]
+//! fn create_user(_: Json<CreateUser>) -> String {
+//!     String::new()
+//! }
+//!
+//! async fn recover(r: Rejection) -> Result<impl Reply, Rejection> {
+//!     match r.find::<Violations>() {
+//!         Some(violations) => Ok(violations.clone()),
+//!         None => Err(r),
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let _ = create_user().recover(recover);
+//! }
+//! ```
+//!
+//! # Constraints
+//!
+//! - `min`, `max`: Bounds of a number. Use a string like `min = "-1"` for
+//!   negative numbers.
+//! - `min_length`, `max_length`: Number of characters in a string.
+//! - `pattern`: Regular expression a string should match.
+//! - `enum_values("a", "b")`: Allowed values of a string.
+//! - `min_items`, `max_items`: Number of items in a collection.
+//! - `format`: Format of a value, like `email`. This is only documented.
+//!
+//! Constraints are written to the schema if the `openapi` feature is enabled,
+//! even if the type does not have `#[schema(validate)]`. Fields of optional
+//! types are checked only if they are present.
+
+use http::StatusCode;
+use serde::Serialize;
+use std::fmt::{self, Display, Formatter};
+use warp::{
+    reject::Reject,
+    reply::{json, with_status, Response},
+    Reply,
+};
+
+/// A type with constraints, which is usually implemented by
+/// `#[derive(Schema)]` with `#[schema(validate)]`.
+pub trait Constraints {
+    /// Adds violated constraints of `self` to `violations`.
+    ///
+    /// `path` is a JSON pointer to `self`, like `/user`.
+    fn check_constraints(&self, path: &str, violations: &mut Vec<Violation>);
+}
+
+impl<T> Constraints for Option<T>
+where
+    T: Constraints,
+{
+    fn check_constraints(&self, path: &str, violations: &mut Vec<Violation>) {
+        if let Some(v) = self {
+            v.check_constraints(path, violations)
+        }
+    }
+}
+
+impl<T> Constraints for Box<T>
+where
+    T: ?Sized + Constraints,
+{
+    fn check_constraints(&self, path: &str, violations: &mut Vec<Violation>) {
+        (**self).check_constraints(path, violations)
+    }
+}
+
+impl<T> Constraints for Vec<T>
+where
+    T: Constraints,
+{
+    fn check_constraints(&self, path: &str, violations: &mut Vec<Violation>) {
+        for (idx, v) in self.iter().enumerate() {
+            v.check_constraints(&format!("{}/{}", path, idx), violations)
+        }
+    }
+}
+
+/// Validation of a request beyond its constraints, used by
+/// [Validated](crate::Validated).
+///
+/// # Example
+///
+/// ```rust
+/// use rweb::{
+///     validation::{Validate, Violation, Violations},
+///     *,
+/// };
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize, Schema)]
+/// struct Transfer {
+///     from: String,
+///     to: String,
+/// }
+///
+/// impl Validate for Transfer {
+///     fn validate(&self) -> Result<(), Violations> {
+///         if self.from == self.to {
+///             return Err(Violation::new("/to", "different", "should differ from `from`").into());
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// #[post("/transfers")]
+/// fn transfer(_: Validated<Json<Transfer>>) -> String {
+///     String::new()
+/// }
+/// ```
+pub trait Validate {
+    fn validate(&self) -> Result<(), Violations>;
+}
+
+impl<T> Validate for Option<T>
+where
+    T: Validate,
+{
+    fn validate(&self) -> Result<(), Violations> {
+        match self {
+            Some(v) => v.validate(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T> Validate for Box<T>
+where
+    T: ?Sized + Validate,
+{
+    fn validate(&self) -> Result<(), Violations> {
+        (**self).validate()
+    }
+}
+
+/// A violated constraint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    /// JSON pointer to the invalid value, like `/user/name`.
+    pub path: String,
+    /// Name of the constraint, like `max_length`.
+    pub constraint: String,
+    pub message: String,
+}
+
+impl Violation {
+    pub fn new(
+        path: impl Into<String>,
+        constraint: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Violation {
+            path: path.into(),
+            constraint: constraint.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Rejection for a request violating constraints.
+///
+/// As a [Reply], this is a `422 Unprocessable Entity` response with the
+/// violations serialized as json, like
+///
+/// ```json
+/// {
+///   "violations": [
+///     { "path": "/name", "constraint": "max_length", "message": "length should be at most 32" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violations {
+    pub violations: Vec<Violation>,
+}
+
+impl From<Violation> for Violations {
+    fn from(v: Violation) -> Self {
+        Violations {
+            violations: vec![v],
+        }
+    }
+}
+
+impl From<Vec<Violation>> for Violations {
+    fn from(violations: Vec<Violation>) -> Self {
+        Violations { violations }
+    }
+}
+
+impl Display for Violations {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (idx, v) in self.violations.iter().enumerate() {
+            if idx != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", v.path, v.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl Reject for Violations {}
+
+impl Reply for Violations {
+    fn into_response(self) -> Response {
+        with_status(json(&self), StatusCode::UNPROCESSABLE_ENTITY).into_response()
+    }
+}
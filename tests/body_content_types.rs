@@ -0,0 +1,96 @@
+use http::StatusCode;
+use rweb::{problem::recover, *};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Schema)]
+struct Login {
+    name: String,
+    password: String,
+}
+
+#[post("/login")]
+fn login(body: Body<Login>) -> String {
+    let body = body.into_inner();
+    format!("{}:{}", body.name, body.password)
+}
+
+#[tokio::test]
+async fn json() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/login")
+        .header("content-type", "application/json")
+        .body(r#"{"name":"a","password":"b"}"#)
+        .reply(&login())
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), "a:b");
+}
+
+#[tokio::test]
+async fn form() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/login")
+        .header(
+            "content-type",
+            "application/x-www-form-urlencoded; charset=utf-8",
+        )
+        .body("name=a&password=b")
+        .reply(&login())
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), "a:b");
+}
+
+#[tokio::test]
+async fn without_content_type() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/login")
+        .body(r#"{"name":"a","password":"b"}"#)
+        .reply(&login())
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), "a:b");
+}
+
+#[tokio::test]
+async fn unsupported() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/login")
+        .header("content-type", "text/plain")
+        .body("a:b")
+        .reply(&login().recover(recover))
+        .await;
+    assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    let res = warp::test::request()
+        .method("POST")
+        .path("/login")
+        .header("content-type", "application/x-www-form-urlencoded")
+        .body("name=a")
+        .reply(&login().recover(recover))
+        .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[cfg(feature = "openapi")]
+#[test]
+fn spec() {
+    let (spec, _) = openapi::spec().build(login);
+
+    let op = spec.paths["/login"].post.as_ref().unwrap();
+    let body = match op.request_body.as_ref().unwrap() {
+        openapi::ObjectOrReference::Object(body) => body,
+        _ => unreachable!(),
+    };
+    assert_eq!(body.content.len(), negotiation::Format::all().len());
+    let schema = &body.content["application/json"].schema;
+    assert!(schema.is_some());
+    assert_eq!(
+        &body.content["application/x-www-form-urlencoded"].schema,
+        schema
+    );
+}
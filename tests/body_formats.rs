@@ -0,0 +1,172 @@
+#![cfg(any(
+    feature = "cbor",
+    feature = "msgpack",
+    feature = "xml",
+    feature = "yaml"
+))]
+
+use http::StatusCode;
+use rweb::{problem::recover, *};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Schema)]
+struct Item {
+    name: String,
+    count: u32,
+}
+
+fn item() -> Item {
+    Item {
+        name: "apple".into(),
+        count: 3,
+    }
+}
+
+#[cfg(feature = "msgpack")]
+#[post("/msgpack")]
+fn msgpack(body: MsgPack<Item>) -> MsgPack<Item> {
+    body
+}
+
+#[cfg(feature = "msgpack")]
+#[tokio::test]
+async fn msgpack_body() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/msgpack")
+        .header("content-type", "application/msgpack")
+        .body(rmp_serde::to_vec_named(&item()).unwrap())
+        .reply(&msgpack())
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], "application/msgpack");
+    let body: Item = rmp_serde::from_slice(res.body()).unwrap();
+    assert_eq!(body, item());
+}
+
+#[cfg(feature = "msgpack")]
+#[tokio::test]
+async fn unsupported_media_type() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/msgpack")
+        .header("content-type", "application/json")
+        .body(r#"{"name":"apple","count":3}"#)
+        .reply(&msgpack().recover(recover))
+        .await;
+    assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[cfg(feature = "msgpack")]
+#[tokio::test]
+async fn invalid_body() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/msgpack")
+        .body("invalid")
+        .reply(&msgpack().recover(recover))
+        .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[cfg(feature = "cbor")]
+#[post("/cbor")]
+fn cbor(body: Cbor<Item>) -> Cbor<Item> {
+    body
+}
+
+#[cfg(feature = "cbor")]
+#[tokio::test]
+async fn cbor_body() {
+    let mut buf = vec![];
+    ciborium::ser::into_writer(&item(), &mut buf).unwrap();
+    let res = warp::test::request()
+        .method("POST")
+        .path("/cbor")
+        .header("content-type", "application/cbor")
+        .body(buf)
+        .reply(&cbor())
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], "application/cbor");
+    let body: Item = ciborium::de::from_reader(&res.body()[..]).unwrap();
+    assert_eq!(body, item());
+}
+
+#[cfg(feature = "yaml")]
+#[post("/yaml")]
+fn yaml(body: Yaml<Item>) -> Yaml<Item> {
+    body
+}
+
+#[cfg(feature = "yaml")]
+#[tokio::test]
+async fn yaml_body() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/yaml")
+        .header("content-type", "application/x-yaml")
+        .body("name: apple\ncount: 3\n")
+        .reply(&yaml())
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], "application/yaml");
+    let body: Item = serde_yaml::from_slice(res.body()).unwrap();
+    assert_eq!(body, item());
+}
+
+#[cfg(feature = "xml")]
+#[post("/xml")]
+fn xml(body: Xml<Item>) -> Xml<Item> {
+    body
+}
+
+#[cfg(feature = "xml")]
+#[tokio::test]
+async fn xml_body() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/xml")
+        .header("content-type", "text/xml; charset=utf-8")
+        .body("<Item><name>apple</name><count>3</count></Item>")
+        .reply(&xml())
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], "application/xml");
+    assert_eq!(
+        res.body(),
+        "<Item><name>apple</name><count>3</count></Item>"
+    );
+}
+
+#[cfg(all(
+    feature = "openapi",
+    feature = "cbor",
+    feature = "msgpack",
+    feature = "xml",
+    feature = "yaml"
+))]
+#[test]
+fn request_bodies() {
+    let (spec, _) = openapi::spec().build(|| msgpack().or(cbor()).or(yaml()).or(xml()));
+
+    for (path, media_type) in &[
+        ("/msgpack", "application/msgpack"),
+        ("/cbor", "application/cbor"),
+        ("/yaml", "application/yaml"),
+        ("/xml", "application/xml"),
+    ] {
+        let op = spec.paths[*path].post.as_ref().unwrap();
+        let body = match op.request_body.as_ref().unwrap() {
+            openapi::ObjectOrReference::Object(body) => body,
+            _ => unreachable!(),
+        };
+        assert_eq!(body.required, Some(true));
+        assert!(body.content[*media_type].schema.is_some(), "{}", path);
+        assert!(
+            op.responses["200"].content[*media_type].schema.is_some(),
+            "{}",
+            path
+        );
+    }
+}
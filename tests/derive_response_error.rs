@@ -0,0 +1,86 @@
+use http::StatusCode;
+use rweb::*;
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Schema, ResponseError)]
+enum Error {
+    #[response(status = 404, description = "Item not found")]
+    NotFound { id: u32 },
+    #[response(status = 409)]
+    Locked,
+    #[response(status = 500)]
+    Internal(String),
+}
+
+#[derive(Debug, Serialize, Schema, ResponseError)]
+#[response(status = 400)]
+struct BadRequest {
+    message: String,
+}
+
+/// Does not derive `Schema`.
+#[derive(Debug, Serialize, ResponseError)]
+enum PlainError {
+    #[response(status = 403)]
+    Forbidden,
+}
+
+#[get("/plain")]
+fn plain() -> Result<String, PlainError> {
+    Err(PlainError::Forbidden)
+}
+
+#[get("/items/{id}")]
+fn item(id: u32) -> Result<Json<u32>, Error> {
+    match id {
+        0 => Err(Error::Locked),
+        1 => Ok(Json::from(id)),
+        2 => Err(Error::Internal("oops".into())),
+        _ => Err(Error::NotFound { id }),
+    }
+}
+
+#[test]
+fn status_codes() {
+    assert_eq!(
+        Error::status_codes(),
+        vec![
+            StatusCode::NOT_FOUND,
+            StatusCode::CONFLICT,
+            StatusCode::INTERNAL_SERVER_ERROR
+        ]
+    );
+    assert_eq!(Error::Locked.status_code(), StatusCode::CONFLICT);
+    assert_eq!(BadRequest::status_codes(), vec![StatusCode::BAD_REQUEST]);
+}
+
+#[tokio::test]
+async fn handler() {
+    let value = warp::test::request().path("/items/3").reply(&item()).await;
+    assert_eq!(value.status(), StatusCode::NOT_FOUND);
+    assert_eq!(value.into_body(), br#"{"NotFound":{"id":3}}"#[..]);
+
+    let value = warp::test::request().path("/items/0").reply(&item()).await;
+    assert_eq!(value.status(), StatusCode::CONFLICT);
+    assert_eq!(value.into_body(), br#""Locked""#[..]);
+
+    let value = warp::test::request().path("/items/2").reply(&item()).await;
+    assert_eq!(value.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[tokio::test]
+async fn reply() {
+    let value = BadRequest {
+        message: "invalid".into(),
+    }
+    .into_response();
+    assert_eq!(value.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(value.headers()["content-type"], "application/json");
+}
+
+#[tokio::test]
+async fn without_schema() {
+    let value = warp::test::request().path("/plain").reply(&plain()).await;
+    assert_eq!(value.status(), StatusCode::FORBIDDEN);
+    assert_eq!(value.into_body(), br#""Forbidden""#[..]);
+}
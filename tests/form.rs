@@ -0,0 +1,140 @@
+use http::StatusCode;
+use rweb::{form::Brackets, *};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Schema)]
+struct Address {
+    city: String,
+}
+
+#[derive(Debug, Deserialize, Schema)]
+struct Search {
+    name: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Schema)]
+struct Signup {
+    name: String,
+    tags: Vec<String>,
+    address: Address,
+}
+
+#[post("/search")]
+fn search(body: Form<Search>) -> String {
+    let body = body.into_inner();
+    format!("{}:{}", body.name, body.tags.join(","))
+}
+
+#[post("/signup")]
+fn signup(body: Form<Signup, Brackets>) -> String {
+    let body = body.into_inner();
+    format!(
+        "{}:{}:{}",
+        body.name,
+        body.tags.join(","),
+        body.address.city
+    )
+}
+
+async fn post<F>(path: &str, body: &str, filter: &F) -> http::Response<bytes::Bytes>
+where
+    F: Filter + 'static,
+    F::Extract: Reply + Send,
+{
+    warp::test::request()
+        .method("POST")
+        .path(path)
+        .header("content-type", "application/x-www-form-urlencoded")
+        .body(body)
+        .reply(filter)
+        .await
+}
+
+#[tokio::test]
+async fn repeated_keys() {
+    let res = post("/search", "name=a&tags=b&tags=c", &search()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), "a:b,c");
+
+    let res = post("/search", "name=a", &search()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), "a:");
+}
+
+#[tokio::test]
+async fn brackets() {
+    let res = post(
+        "/signup",
+        "name=a&tags[0]=b&tags[1]=c&address[city]=d",
+        &signup(),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), "a:b,c:d");
+}
+
+#[tokio::test]
+async fn percent_encoded_brackets() {
+    let res = post(
+        "/signup",
+        "name=a&tags%5B%5D=b&address%5Bcity%5D=d",
+        &signup(),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), "a:b:d");
+}
+
+#[tokio::test]
+async fn invalid_body() {
+    let res = post("/signup", "name=a", &signup().recover(problem::recover)).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn unsupported_media_type() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/search")
+        .header("content-type", "application/json")
+        .body(r#"{"name":"a"}"#)
+        .reply(&search().recover(problem::recover))
+        .await;
+    assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[cfg(feature = "openapi")]
+mod openapi_spec {
+    use super::*;
+
+    fn encoding(path: &str) -> openapi::MediaType {
+        let (spec, _) = openapi::spec().build(|| search().or(signup()));
+        let op = spec.paths[path].post.as_ref().unwrap();
+        match op.request_body.as_ref().unwrap() {
+            openapi::ObjectOrReference::Object(body) => {
+                assert_eq!(body.content.len(), 1);
+                body.content["application/x-www-form-urlencoded"].clone()
+            }
+            _ => panic!("request body should be inlined"),
+        }
+    }
+
+    #[test]
+    fn repeated_keys() {
+        let media = encoding("/search");
+        assert_eq!(media.encoding.len(), 1);
+        assert_eq!(media.encoding["tags"].style, "form");
+        assert_eq!(media.encoding["tags"].explode, Some(true));
+    }
+
+    #[test]
+    fn brackets() {
+        let media = encoding("/signup");
+        assert_eq!(media.encoding.len(), 2);
+        assert_eq!(media.encoding["tags"].style, "deepObject");
+        assert_eq!(media.encoding["address"].style, "deepObject");
+        assert_eq!(media.encoding["address"].explode, Some(true));
+    }
+}
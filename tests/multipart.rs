@@ -0,0 +1,234 @@
+#![cfg(feature = "multipart")]
+
+use http::StatusCode;
+use rweb::{
+    multipart::TempFile,
+    problem::{recover, Problem},
+    *,
+};
+
+#[derive(Multipart)]
+struct Upload {
+    /// Title of the file.
+    #[multipart(max_size = 16)]
+    title: String,
+    count: Option<u32>,
+    #[multipart(rename = "raw")]
+    data: Vec<u8>,
+    #[multipart(max_size = 32)]
+    file: TempFile,
+}
+
+#[post("/upload")]
+async fn upload(form: Upload) -> Result<String, Rejection> {
+    let contents = tokio::fs::read(form.file.path()).await.unwrap();
+    Ok(format!(
+        "{} {:?} {} {} {:?} {}",
+        form.title,
+        form.count,
+        String::from_utf8_lossy(&form.data),
+        form.file.size(),
+        form.file.file_name(),
+        String::from_utf8_lossy(&contents),
+    ))
+}
+
+const BOUNDARY: &str = "----rweb-boundary";
+
+fn body(parts: &[(&str, Option<&str>, &str)]) -> String {
+    let mut body = String::new();
+    for (name, file_name, value) in parts {
+        body.push_str(&format!("--{}\r\n", BOUNDARY));
+        match file_name {
+            Some(file_name) => body.push_str(&format!(
+                "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+                 Content-Type: text/plain\r\n\r\n",
+                name, file_name
+            )),
+            None => body.push_str(&format!(
+                "Content-Disposition: form-data; name=\"{}\"\r\n\r\n",
+                name
+            )),
+        }
+        body.push_str(value);
+        body.push_str("\r\n");
+    }
+    body.push_str(&format!("--{}--\r\n", BOUNDARY));
+    body
+}
+
+async fn request(parts: &[(&str, Option<&str>, &str)]) -> http::Response<warp::hyper::body::Bytes> {
+    warp::test::request()
+        .method("POST")
+        .path("/upload")
+        .header(
+            "content-type",
+            format!("multipart/form-data; boundary={}", BOUNDARY),
+        )
+        .body(body(parts))
+        .reply(&upload().recover(recover))
+        .await
+}
+
+#[tokio::test]
+async fn parts() {
+    let res = request(&[
+        ("title", None, "hello"),
+        ("count", None, "3"),
+        ("raw", None, "bytes"),
+        ("ignored", None, "value"),
+        ("file", Some("a.txt"), "file contents"),
+    ])
+    .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.body(),
+        "hello Some(3) bytes 13 Some(\"a.txt\") file contents"
+    );
+}
+
+#[tokio::test]
+async fn optional_part() {
+    let res = request(&[
+        ("title", None, "hello"),
+        ("raw", None, ""),
+        ("file", Some("a.txt"), "x"),
+    ])
+    .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), "hello None  1 Some(\"a.txt\") x");
+}
+
+#[tokio::test]
+async fn missing_part() {
+    let res = request(&[("title", None, "hello"), ("raw", None, "")]).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let problem: Problem = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        problem.detail.as_deref(),
+        Some("Invalid part \"file\": missing part")
+    );
+}
+
+#[tokio::test]
+async fn invalid_part() {
+    let res = request(&[
+        ("title", None, "hello"),
+        ("count", None, "three"),
+        ("raw", None, ""),
+        ("file", Some("a.txt"), "x"),
+    ])
+    .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn text_too_large() {
+    let res = request(&[
+        ("title", None, "a title longer than sixteen bytes"),
+        ("raw", None, ""),
+        ("file", Some("a.txt"), "x"),
+    ])
+    .await;
+    assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let problem: Problem = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        problem.detail.as_deref(),
+        Some("Part \"title\" is larger than 16 bytes")
+    );
+}
+
+#[tokio::test]
+async fn file_too_large() {
+    let res = request(&[
+        ("title", None, "hello"),
+        ("raw", None, ""),
+        ("file", Some("a.txt"), &"x".repeat(64)),
+    ])
+    .await;
+    assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn temp_file_is_removed() {
+    #[derive(Multipart)]
+    struct Form {
+        file: TempFile,
+    }
+
+    #[post("/file")]
+    async fn file(form: Form) -> Result<String, Rejection> {
+        Ok(form.file.path().display().to_string())
+    }
+
+    let res = warp::test::request()
+        .method("POST")
+        .path("/file")
+        .header(
+            "content-type",
+            format!("multipart/form-data; boundary={}", BOUNDARY),
+        )
+        .body(body(&[("file", Some("a.txt"), "contents")]))
+        .reply(&file())
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let path = std::str::from_utf8(res.body()).unwrap();
+    assert!(!std::path::Path::new(path).exists());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn temp_file_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    #[derive(Multipart)]
+    struct Form {
+        file: TempFile,
+    }
+
+    #[post("/file")]
+    async fn file(form: Form) -> Result<String, Rejection> {
+        let metadata = std::fs::metadata(form.file.path()).unwrap();
+        Ok(format!("{:o}", metadata.permissions().mode() & 0o777))
+    }
+
+    let res = warp::test::request()
+        .method("POST")
+        .path("/file")
+        .header(
+            "content-type",
+            format!("multipart/form-data; boundary={}", BOUNDARY),
+        )
+        .body(body(&[("file", Some("a.txt"), "contents")]))
+        .reply(&file())
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), "600");
+}
+
+#[cfg(feature = "openapi")]
+#[test]
+fn request_body() {
+    let (spec, _) = openapi::spec().build(|| upload());
+    let op = spec.paths.get("/upload").unwrap().post.as_ref().unwrap();
+    let body = match op.request_body.as_ref().unwrap() {
+        openapi::ObjectOrReference::Object(body) => body,
+        _ => panic!("request body should be inlined"),
+    };
+    assert_eq!(body.required, Some(true));
+    let schema = match &body.content["multipart/form-data"].schema {
+        Some(openapi::ComponentOrInlineSchema::Inline(s)) => s,
+        _ => panic!("schema of the form should be inlined"),
+    };
+    assert_eq!(schema.required, vec!["title", "raw", "file"]);
+
+    let property = |name: &str| match &schema.properties[name] {
+        openapi::ComponentOrInlineSchema::Inline(s) => s.clone(),
+        _ => panic!("{} should be inlined", name),
+    };
+    assert_eq!(property("title").description, "Title of the file.");
+    assert_eq!(property("count").schema_type, Some(openapi::Type::Integer));
+    assert_eq!(property("raw").format, "binary");
+    assert_eq!(property("file").format, "binary");
+    assert_eq!(property("file").schema_type, Some(openapi::Type::String));
+}
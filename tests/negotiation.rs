@@ -0,0 +1,114 @@
+use http::StatusCode;
+use rweb::{negotiation::Format, *};
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Serialize, Schema)]
+struct Product {
+    id: String,
+    price: usize,
+}
+
+#[get("/products/{id}")]
+fn product(id: String, accept: Accept) -> Negotiated<Product> {
+    Negotiated::new(&accept, Product { id, price: 100 })
+}
+
+async fn get(accept: Option<&str>) -> http::Response<bytes::Bytes> {
+    let mut req = warp::test::request().path("/products/a");
+    if let Some(accept) = accept {
+        req = req.header("accept", accept);
+    }
+    req.reply(&product()).await
+}
+
+#[test]
+fn select() {
+    let select = |v: &str| Accept::from(v.to_string()).select();
+
+    assert_eq!(Accept::default().select(), Some(Format::Json));
+    assert_eq!(select("*/*"), Some(Format::Json));
+    assert_eq!(select("application/*"), Some(Format::Json));
+    assert_eq!(
+        select("application/json;q=0.5, application/x-www-form-urlencoded"),
+        Some(Format::Form)
+    );
+    assert_eq!(
+        select("application/json;q=0, */*;q=0.1"),
+        Some(Format::Form)
+    );
+    assert_eq!(select("text/html"), None);
+}
+
+#[tokio::test]
+async fn json() {
+    let res = get(None).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], "application/json");
+    assert_eq!(res.headers()["vary"], "accept");
+    assert_eq!(res.body(), r#"{"id":"a","price":100}"#);
+}
+
+#[tokio::test]
+async fn form() {
+    let res = get(Some("application/x-www-form-urlencoded")).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers()["content-type"],
+        "application/x-www-form-urlencoded"
+    );
+    assert_eq!(res.body(), "id=a&price=100");
+}
+
+#[tokio::test]
+async fn not_acceptable() {
+    let res = get(Some("text/html")).await;
+    assert_eq!(res.status(), StatusCode::NOT_ACCEPTABLE);
+    assert_eq!(res.headers()["content-type"], "application/problem+json");
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(body["title"], "Not Acceptable");
+}
+
+#[cfg(feature = "yaml")]
+#[tokio::test]
+async fn yaml() {
+    let res = get(Some("text/yaml")).await;
+    assert_eq!(res.headers()["content-type"], "application/yaml");
+    let body: Value = serde_yaml::from_slice(res.body()).unwrap();
+    assert_eq!(body["price"], 100);
+}
+
+#[cfg(feature = "msgpack")]
+#[tokio::test]
+async fn msgpack() {
+    let res = get(Some("application/x-msgpack")).await;
+    assert_eq!(res.headers()["content-type"], "application/msgpack");
+    let body: Value = rmp_serde::from_slice(res.body()).unwrap();
+    assert_eq!(body["price"], 100);
+}
+
+#[cfg(feature = "cbor")]
+#[tokio::test]
+async fn cbor() {
+    let res = get(Some("application/cbor")).await;
+    assert_eq!(res.headers()["content-type"], "application/cbor");
+    let body: Value = ciborium::de::from_reader(&res.body()[..]).unwrap();
+    assert_eq!(body["price"], 100);
+}
+
+#[cfg(feature = "openapi")]
+#[test]
+fn spec() {
+    let (spec, _) = openapi::spec().build(product);
+
+    let op = spec.paths["/products/{id}"].get.as_ref().unwrap();
+    let content = &op.responses["200"].content;
+    let schema = &content["application/json"].schema;
+    assert!(schema.is_some());
+    for format in Format::all() {
+        assert_eq!(&content[format.media_type()].schema, schema);
+    }
+    assert_eq!(content.len(), Format::all().len());
+    assert_eq!(op.responses["406"].description, "Not Acceptable");
+    assert_eq!(op.parameters.len(), 1);
+}
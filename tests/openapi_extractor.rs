@@ -0,0 +1,94 @@
+#![cfg(feature = "openapi")]
+
+use rweb::{
+    filters::BoxedFilter,
+    openapi::{
+        Collector, ComponentDescriptor, ComponentOrInlineSchema, Entity, Location,
+        ObjectOrReference, Operation, Parameter, ParameterRepresentation,
+    },
+    *,
+};
+use serde::Deserialize;
+use std::borrow::Cow;
+
+#[derive(Schema)]
+struct User {
+    id: String,
+}
+
+impl FromRequest for User {
+    type Filter = BoxedFilter<(User,)>;
+
+    fn describe_operation(
+        collector: &mut Collector,
+        op: &mut Operation,
+        _: fn(&mut ComponentDescriptor) -> ComponentOrInlineSchema,
+    ) {
+        op.parameters.push(ObjectOrReference::Object(Parameter {
+            name: Cow::Borrowed("x-user-id"),
+            location: Location::Header,
+            required: Some(true),
+            representation: Some(ParameterRepresentation::Simple {
+                schema: <String as Entity>::describe(collector.components()),
+            }),
+            ..Default::default()
+        }));
+    }
+
+    fn new() -> Self::Filter {
+        header::<String>("x-user-id").map(|id| User { id }).boxed()
+    }
+}
+
+#[get("/user")]
+fn user(user: User) -> String {
+    user.id
+}
+
+#[get("/optional")]
+fn optional(user: Option<User>) -> String {
+    user.map(|u| u.id).unwrap_or_default()
+}
+
+#[derive(Debug, Deserialize, Schema)]
+struct Search {
+    query: String,
+}
+
+#[get("/search")]
+fn search(q: Query<Search>) -> String {
+    q.into_inner().query
+}
+
+fn parameters(spec: &openapi::Spec, path: &str) -> Vec<(String, Location, Option<bool>)> {
+    spec.paths[path]
+        .get
+        .as_ref()
+        .unwrap()
+        .parameters
+        .iter()
+        .map(|p| match p {
+            ObjectOrReference::Object(p) => (p.name.to_string(), p.location, p.required),
+            _ => panic!("parameter should be inlined"),
+        })
+        .collect()
+}
+
+#[test]
+fn custom_extractor() {
+    let (spec, _) = openapi::spec().build(|| user().or(optional()).or(search()));
+
+    assert_eq!(
+        parameters(&spec, "/user"),
+        vec![("x-user-id".to_string(), Location::Header, Some(true))]
+    );
+    assert_eq!(
+        parameters(&spec, "/optional"),
+        vec![("x-user-id".to_string(), Location::Header, Some(false))]
+    );
+    // The default documents queries.
+    assert_eq!(
+        parameters(&spec, "/search"),
+        vec![("query".to_string(), Location::Query, Some(true))]
+    );
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "openapi")]
+
+use rweb::*;
+
+#[get("/items/{id}")]
+fn item(
+    #[param(description = "Id of the item", example = 3, deprecated)] id: u32,
+    /// Id of the request
+    #[header = "x-request-id"]
+    request_id: String,
+    #[header = "x-retries"]
+    #[param(example = "2")]
+    retries: u8,
+    /// Filters of the items
+    #[query]
+    filters: String,
+    #[cookie = "sess"]
+    #[param(description = "Session of the user")]
+    session: String,
+) -> String {
+    format!("{}{}{}{}{}", id, request_id, retries, filters, session)
+}
+
+fn param<'a>(op: &'a openapi::Operation, name: &str) -> &'a openapi::Parameter {
+    op.parameters
+        .iter()
+        .find_map(|p| match p {
+            openapi::ObjectOrReference::Object(p) if p.name == name => Some(p),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("parameter `{}` is not documented", name))
+}
+
+#[test]
+fn params_are_documented() {
+    let (spec, _) = openapi::spec().build(|| item());
+
+    let op = spec.paths.get("/items/{id}").unwrap().get.as_ref().unwrap();
+
+    let id = param(op, "id");
+    assert_eq!(id.location, openapi::Location::Path);
+    assert_eq!(id.description, "Id of the item");
+    assert_eq!(id.deprecated, Some(true));
+    assert_eq!(
+        id.example,
+        Some(openapi::ParameterExamples::One {
+            example: Some(serde_json::json!(3))
+        })
+    );
+
+    let request_id = param(op, "x-request-id");
+    assert_eq!(request_id.location, openapi::Location::Header);
+    assert_eq!(request_id.description, "Id of the request");
+    assert_eq!(request_id.required, Some(true));
+    match &request_id.representation {
+        Some(openapi::ParameterRepresentation::Simple {
+            schema: openapi::ComponentOrInlineSchema::Inline(s),
+        }) => assert_eq!(s.schema_type, Some(openapi::Type::String)),
+        r => panic!("unexpected representation: {:?}", r),
+    }
+
+    let retries = param(op, "x-retries");
+    assert_eq!(retries.deprecated, None);
+    assert_eq!(
+        retries.example,
+        Some(openapi::ParameterExamples::One {
+            example: Some(serde_json::json!("2"))
+        })
+    );
+
+    let filters = param(op, "filters");
+    assert_eq!(filters.location, openapi::Location::Query);
+    assert_eq!(filters.description, "Filters of the items");
+    match &filters.representation {
+        Some(openapi::ParameterRepresentation::Simple {
+            schema: openapi::ComponentOrInlineSchema::Inline(s),
+        }) => {
+            assert_eq!(s.schema_type, Some(openapi::Type::Object));
+            assert!(s.additional_properties.is_some());
+        }
+        r => panic!("unexpected representation: {:?}", r),
+    }
+
+    let session = param(op, "sess");
+    assert_eq!(session.location, openapi::Location::Cookie);
+    assert_eq!(session.description, "Session of the user");
+    assert_eq!(session.required, Some(true));
+    let session = serde_json::to_value(session).unwrap();
+    assert_eq!(session["in"], "cookie");
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "openapi")]
+
+use rweb::*;
+
+#[get("/users/{id:[0-9]+}")]
+fn user(id: u64) -> String {
+    id.to_string()
+}
+
+#[test]
+fn pattern_is_documented() {
+    let (spec, _) = openapi::spec().build(|| user());
+
+    assert!(spec.paths.get("/users/{id:[0-9]+}").is_none());
+    let op = spec.paths.get("/users/{id}").unwrap().get.as_ref().unwrap();
+    let param = match &op.parameters[0] {
+        openapi::ObjectOrReference::Object(p) => p,
+        _ => unreachable!(),
+    };
+    assert_eq!(param.name, "id");
+    let schema = match &param.representation {
+        Some(openapi::ParameterRepresentation::Simple {
+            schema: openapi::ComponentOrInlineSchema::Inline(s),
+        }) => s,
+        _ => unreachable!(),
+    };
+    assert_eq!(schema.pattern, "^(?:[0-9]+)$");
+    assert_eq!(schema.schema_type, Some(openapi::Type::Integer));
+}
+
+#[get("/files/{*path}")]
+fn file(path: String) -> String {
+    path
+}
+
+#[test]
+fn tail_is_documented() {
+    let (spec, _) = openapi::spec().build(|| file());
+
+    let op = spec
+        .paths
+        .get("/files/{path}")
+        .unwrap()
+        .get
+        .as_ref()
+        .unwrap();
+    let param = match &op.parameters[0] {
+        openapi::ObjectOrReference::Object(p) => p,
+        _ => unreachable!(),
+    };
+    assert_eq!(param.name, "path");
+    assert_eq!(param.location, openapi::Location::Path);
+    assert!(!param.description.is_empty());
+}
+
+#[get("/items/{id}")]
+fn item(
+    /// Id of the item
+    id: u64,
+) -> String {
+    id.to_string()
+}
+
+#[test]
+fn doc_comment_is_description() {
+    let (spec, _) = openapi::spec().build(|| item());
+
+    let op = spec
+        .paths
+        .get("/items/{id}")
+        .unwrap()
+        .get
+        .as_ref()
+        .unwrap();
+    let param = match &op.parameters[0] {
+        openapi::ObjectOrReference::Object(p) => p,
+        _ => unreachable!(),
+    };
+    assert_eq!(param.description, "Id of the item");
+}
@@ -81,7 +81,7 @@ fn description() {
         .unwrap()
     {
         ObjectOrReference::Object(request_body) => {
-            assert!(request_body.content.contains_key("x-www-form-urlencoded"));
+            assert!(request_body.content.contains_key("application/x-www-form-urlencoded"));
         }
         ObjectOrReference::Ref { .. } => {
             panic!("Struct Product dont have `#[schema(component = \"...\")]`")
@@ -10,6 +10,12 @@ use serde::Serialize;
 #[derive(Debug, Schema)]
 enum Error {}
 
+impl Reply for Error {
+    fn into_response(self) -> reply::Response {
+        match self {}
+    }
+}
+
 impl openapi::ResponseEntity for Error {
     fn describe_responses(_: &mut openapi::ComponentDescriptor) -> openapi::Responses {
         let mut map = IndexMap::new();
@@ -107,3 +113,77 @@ fn response_code_in_response() {
         .get("application/json")
         .is_some())
 }
+
+#[get("/created")]
+#[openapi(response(
+    code = 201,
+    description = "Created",
+    schema = "Json<Product>",
+    headers(location = "String", x_rate_limit = "u32"),
+    example = r#"{"id": 1}"#,
+    links(get_product(
+        operation_id = "product",
+        parameters(id = "$response.body#/id"),
+        description = "The created product"
+    ))
+))]
+#[openapi(response(
+    code = 200,
+    description = "Ok",
+    example = "text",
+    links(self_link(operation_ref = "#/paths/~1created/get"))
+))]
+fn created() -> String {
+    unimplemented!()
+}
+
+#[test]
+fn response_headers_examples_and_links() {
+    use rweb::openapi::{MediaTypeExample, ObjectOrReference, Type};
+
+    let (spec, _) = openapi::spec().build(|| created());
+    let op = spec.paths.get("/created").unwrap().get.as_ref().unwrap();
+
+    let created = &op.responses["201"];
+    assert_eq!(
+        created.headers.keys().collect::<Vec<_>>(),
+        vec!["location", "x-rate-limit"]
+    );
+    let rate_limit = match &created.headers["x-rate-limit"] {
+        ObjectOrReference::Object(h) => h,
+        _ => panic!("header should be inlined"),
+    };
+    assert_eq!(rate_limit.required, Some(true));
+    match &rate_limit.schema {
+        Some(openapi::ComponentOrInlineSchema::Inline(s)) => {
+            assert_eq!(s.schema_type, Some(Type::Integer))
+        }
+        _ => panic!("schema of u32 should be inlined"),
+    }
+    assert_eq!(
+        created.content["application/json"].examples,
+        Some(MediaTypeExample::Example {
+            example: serde_json::json!({"id": 1})
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(&created.links["get_product"]).unwrap(),
+        serde_json::json!({
+            "operationId": "product",
+            "parameters": {"id": "$response.body#/id"},
+            "description": "The created product",
+        })
+    );
+
+    // Examples are merged into the body documented by the return type.
+    let ok = &op.responses["200"];
+    assert_eq!(ok.description, "Ok");
+    assert_eq!(ok.content.keys().collect::<Vec<_>>(), vec!["text/plain"]);
+    assert!(ok.links.contains_key("self_link"));
+    assert_eq!(
+        ok.content["text/plain"].examples,
+        Some(MediaTypeExample::Example {
+            example: serde_json::json!("text")
+        })
+    );
+}
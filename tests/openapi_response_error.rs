@@ -0,0 +1,173 @@
+#![cfg(feature = "openapi")]
+
+use http::StatusCode;
+use rweb::*;
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Schema)]
+struct Error {
+    message: String,
+}
+
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::NOT_FOUND
+    }
+
+    fn status_codes() -> Vec<StatusCode> {
+        vec![StatusCode::NOT_FOUND, StatusCode::CONFLICT]
+    }
+}
+
+#[derive(Debug, Serialize, Schema)]
+struct Item {
+    id: u32,
+}
+
+#[get("/items/{id}")]
+#[openapi(response(code = "409", description = "item is locked"))]
+fn item(id: u32) -> Result<Json<Item>, Error> {
+    Ok(Json::from(Item { id }))
+}
+
+#[test]
+fn error_responses_are_documented() {
+    let (spec, _) = openapi::spec().build(|| item());
+
+    let op = spec.paths.get("/items/{id}").unwrap().get.as_ref().unwrap();
+    assert!(op.responses.contains_key("200"));
+
+    let not_found = &op.responses["404"];
+    assert_eq!(not_found.description, "Not Found");
+    assert!(not_found.content.contains_key("application/json"));
+
+    let conflict = &op.responses["409"];
+    assert_eq!(conflict.description, "item is locked");
+}
+
+/// Does not implement `Entity`.
+#[derive(Debug, Serialize)]
+struct PlainError {
+    message: String,
+}
+
+impl ResponseError for PlainError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+
+    fn status_codes() -> Vec<StatusCode> {
+        vec![StatusCode::BAD_REQUEST]
+    }
+}
+
+#[get("/plain/{id}")]
+fn plain(id: u32) -> Result<Json<Item>, PlainError> {
+    match id {
+        0 => Err(PlainError {
+            message: "invalid id".into(),
+        }),
+        _ => Ok(Json::from(Item { id })),
+    }
+}
+
+#[test]
+fn error_without_entity_is_not_documented() {
+    let (spec, _) = openapi::spec().build(|| plain());
+
+    let op = spec.paths.get("/plain/{id}").unwrap().get.as_ref().unwrap();
+    assert!(op.responses.contains_key("200"));
+    assert!(!op.responses.contains_key("400"));
+}
+
+#[tokio::test]
+async fn error_without_entity_is_converted() {
+    let value = warp::test::request().path("/plain/0").reply(&plain()).await;
+    assert_eq!(value.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(value.into_body(), br#"{"message":"invalid id"}"#[..]);
+}
+
+#[derive(Debug, Serialize, Schema, ResponseError)]
+#[serde(tag = "error")]
+#[allow(dead_code)]
+enum DerivedError {
+    #[response(status = 404, description = "Item not found")]
+    NotFound { id: u32 },
+    /// The item is locked.
+    #[response(status = 409)]
+    Locked,
+    #[response(status = 409)]
+    Conflict { reason: String },
+    #[response(status = 503)]
+    Unavailable,
+}
+
+#[get("/derived/{id}")]
+fn derived(id: u32) -> Result<Json<Item>, DerivedError> {
+    Ok(Json::from(Item { id }))
+}
+
+#[test]
+fn derived_error_responses_are_documented() {
+    let (spec, _) = openapi::spec().build(|| derived());
+
+    let op = spec
+        .paths
+        .get("/derived/{id}")
+        .unwrap()
+        .get
+        .as_ref()
+        .unwrap();
+    assert!(op.responses.contains_key("200"));
+
+    let schema = |code: &str| {
+        op.responses[code].content["application/json"]
+            .schema
+            .clone()
+            .unwrap()
+    };
+
+    let not_found = &op.responses["404"];
+    assert_eq!(not_found.description, "Item not found");
+    match schema("404") {
+        openapi::ComponentOrInlineSchema::Inline(s) => {
+            assert!(s.properties.contains_key("id"));
+            assert!(s.properties.contains_key("error"));
+        }
+        s => panic!("expected an inline schema, got {:?}", s),
+    }
+
+    assert_eq!(op.responses["409"].description, "The item is locked.");
+    match schema("409") {
+        openapi::ComponentOrInlineSchema::Inline(s) => assert_eq!(s.one_of.len(), 2),
+        s => panic!("expected an inline schema, got {:?}", s),
+    }
+
+    assert_eq!(op.responses["503"].description, "Service Unavailable");
+}
+
+#[derive(Debug, Serialize, ResponseError)]
+enum DerivedPlainError {
+    #[response(status = 403)]
+    Forbidden,
+}
+
+#[get("/derived-plain")]
+fn derived_plain() -> Result<Json<Item>, DerivedPlainError> {
+    Err(DerivedPlainError::Forbidden)
+}
+
+#[test]
+fn derived_error_without_schema_is_not_documented() {
+    let (spec, _) = openapi::spec().build(|| derived_plain());
+
+    let op = spec
+        .paths
+        .get("/derived-plain")
+        .unwrap()
+        .get
+        .as_ref()
+        .unwrap();
+    assert!(op.responses.contains_key("200"));
+    assert!(!op.responses.contains_key("403"));
+}
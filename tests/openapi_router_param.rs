@@ -0,0 +1,37 @@
+#![cfg(feature = "openapi")]
+
+use rweb::*;
+
+#[get("/repos/{name}")]
+fn repo(#[router_param] org_id: u64, name: String) -> String {
+    format!("{}/{}", org_id, name)
+}
+
+#[router("/orgs/{org_id:int}", services(repo))]
+fn org(org_id: u64) {}
+
+#[test]
+fn router_param_is_documented() {
+    let (spec, _) = openapi::spec().build(|| org());
+
+    assert!(spec.paths.get("/orgs/{org_id:int}/repos/{name}").is_none());
+    let op = spec
+        .paths
+        .get("/orgs/{org_id}/repos/{name}")
+        .unwrap()
+        .get
+        .as_ref()
+        .unwrap();
+    let names: Vec<_> = op
+        .parameters
+        .iter()
+        .map(|p| match p {
+            openapi::ObjectOrReference::Object(p) => {
+                assert_eq!(p.location, openapi::Location::Path);
+                &*p.name
+            }
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(names, vec!["org_id", "name"]);
+}
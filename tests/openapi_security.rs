@@ -0,0 +1,167 @@
+#![cfg(feature = "openapi")]
+
+use rweb::{
+    openapi::{
+        security::{self, ApiKeyLocation, OAuth2},
+        ObjectOrReference, SecurityScheme,
+    },
+    *,
+};
+use std::convert::TryFrom;
+
+#[get("/public")]
+fn public() -> String {
+    String::new()
+}
+
+#[get("/items")]
+#[openapi(security("oauth", scopes("read", "write")))]
+#[openapi(security("api_key"))]
+fn items() -> String {
+    String::new()
+}
+
+#[get("/both")]
+#[openapi(security("bearer", "api_key"))]
+fn both() -> String {
+    String::new()
+}
+
+#[get("/inherited")]
+fn inherited() -> String {
+    String::new()
+}
+
+#[router("/admin", services(items, inherited, inner))]
+#[openapi(security("bearer"))]
+fn admin() {}
+
+#[get("/nested")]
+fn nested() -> String {
+    String::new()
+}
+
+#[get("/token")]
+fn token(token: auth::BearerToken) -> String {
+    token.as_str().to_string()
+}
+
+#[router("/inner", services(nested, token))]
+#[openapi(security("basic"))]
+fn inner() {}
+
+fn security(spec: &openapi::Spec, path: &str) -> Vec<Vec<(String, Vec<String>)>> {
+    spec.paths[path]
+        .get
+        .as_ref()
+        .unwrap()
+        .security
+        .iter()
+        .map(|requirement| {
+            requirement
+                .iter()
+                .map(|(name, scopes)| {
+                    (
+                        name.to_string(),
+                        scopes.iter().map(|s| s.to_string()).collect(),
+                    )
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn spec() -> openapi::Spec {
+    openapi::spec()
+        .security_scheme("bearer", security::jwt())
+        .security_scheme("basic", security::basic())
+        .security_scheme(
+            "api_key",
+            security::api_key(ApiKeyLocation::Header, "x-api-key"),
+        )
+        .security_scheme(
+            "oauth",
+            SecurityScheme::try_from(OAuth2::new().authorization_code(
+                "https://example.com/authorize",
+                "https://example.com/token",
+                &[("read", "Read items"), ("write", "Write items")],
+            ))
+            .unwrap(),
+        )
+        .build(|| public().or(both()).or(admin()))
+        .0
+}
+
+#[test]
+fn operation_security() {
+    let spec = spec();
+
+    assert!(security(&spec, "/public").is_empty());
+    assert_eq!(
+        security(&spec, "/admin/items"),
+        vec![
+            vec![
+                ("oauth".into(), vec!["read".into(), "write".into()]),
+                ("bearer".into(), vec![]),
+            ],
+            vec![("api_key".into(), vec![]), ("bearer".into(), vec![])],
+        ]
+    );
+    assert_eq!(
+        security(&spec, "/both"),
+        vec![vec![("bearer".into(), vec![]), ("api_key".into(), vec![])]]
+    );
+}
+
+#[test]
+fn router_security() {
+    let spec = spec();
+
+    assert_eq!(
+        security(&spec, "/admin/inherited"),
+        vec![vec![("bearer".into(), vec![])]]
+    );
+    assert_eq!(
+        security(&spec, "/admin/inner/nested"),
+        vec![vec![("basic".into(), vec![])]]
+    );
+    // Requirements documented by extractors are merged too.
+    assert_eq!(
+        security(&spec, "/admin/inner/token"),
+        vec![vec![("bearer".into(), vec![]), ("basic".into(), vec![])]]
+    );
+}
+
+#[test]
+fn invalid_oauth2_url() {
+    let oauth = OAuth2::new().implicit("/authorize", &[]);
+    assert!(SecurityScheme::try_from(oauth).is_err());
+}
+
+#[test]
+fn security_schemes() {
+    let spec = spec();
+    let schemes = spec.components.unwrap().security_schemes;
+
+    assert_eq!(
+        schemes["bearer"],
+        ObjectOrReference::Object(SecurityScheme::Http {
+            scheme: "bearer".into(),
+            bearer_format: "JWT".into(),
+        })
+    );
+    assert_eq!(
+        schemes["api_key"],
+        ObjectOrReference::Object(SecurityScheme::ApiKey {
+            name: "x-api-key".into(),
+            location: "header".into(),
+        })
+    );
+
+    let oauth = serde_json::to_value(&schemes["oauth"]).unwrap();
+    assert_eq!(oauth["type"], "oauth2");
+    let flow = &oauth["flows"]["authorizationCode"];
+    assert_eq!(flow["authorizationUrl"], "https://example.com/authorize");
+    assert_eq!(flow["tokenUrl"], "https://example.com/token");
+    assert_eq!(flow["scopes"]["write"], "Write items");
+}
@@ -0,0 +1,128 @@
+#![cfg(feature = "openapi")]
+
+use rweb::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize, Deserialize, Schema)]
+struct Pagination {
+    page: u32,
+    per_page: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Schema)]
+#[serde(tag = "kind")]
+enum Filter {
+    ByName { name: String },
+    ById { id: u64 },
+}
+
+fn default_limit() -> u32 {
+    20
+}
+
+#[derive(Debug, Default, Deserialize, Schema)]
+struct NotSerialize {
+    value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Schema)]
+#[schema(component = "Search")]
+struct Search {
+    query: String,
+    #[serde(flatten)]
+    pagination: Pagination,
+    #[serde(flatten)]
+    filter: Filter,
+    #[serde(flatten)]
+    extra: HashMap<String, String>,
+    #[serde(default)]
+    exact: bool,
+    #[serde(default = "default_limit")]
+    limit: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Schema)]
+#[schema(component = "Optional")]
+struct Optional {
+    #[serde(flatten)]
+    pagination: Option<Pagination>,
+    #[serde(default)]
+    not_serialize: NotSerialize,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Schema)]
+#[serde(default)]
+#[schema(component = "Defaults")]
+struct Defaults {
+    a: String,
+    b: u32,
+}
+
+#[get("/")]
+fn index(_: Json<Search>, optional: Query<Optional>, _: Query<Defaults>) -> String {
+    let optional = optional.into_inner();
+    let page = optional.pagination.map_or(0, |p| p.page);
+    format!("{}:{}", page, optional.not_serialize.value)
+}
+
+#[test]
+fn serde_attrs() {
+    let (spec, _) = openapi::spec().build(|| {
+        //
+        index()
+    });
+
+    let schemas = &spec.components.as_ref().unwrap().schemas;
+    macro_rules! component {
+        ($cn:expr) => {
+            match schemas.get($cn) {
+                Some(openapi::ObjectOrReference::Object(s)) => s,
+                Some(..) => panic!("Component schema can't be a reference"),
+                None => panic!("No component schema for {}", $cn),
+            }
+        };
+    }
+
+    let schema = component!("Search");
+    let properties: Vec<_> = schema.properties.keys().map(|k| &**k).collect();
+    assert_eq!(
+        properties,
+        vec!["query", "page", "per_page", "exact", "limit", "tags"]
+    );
+    assert_eq!(schema.required, vec!["query", "page", "per_page"]);
+    assert_eq!(
+        schema
+            .additional_properties
+            .as_ref()
+            .unwrap()
+            .unwrap()
+            .unwrap()
+            .schema_type,
+        Some(openapi::Type::String)
+    );
+    assert_eq!(schema.all_of.len(), 1);
+    assert_eq!(schema.all_of[0].unwrap().unwrap().one_of.len(), 2);
+    assert_eq!(
+        schema.properties["exact"].unwrap().unwrap().default,
+        Some(serde_json::json!(false))
+    );
+    assert_eq!(
+        schema.properties["limit"].unwrap().unwrap().default,
+        Some(serde_json::json!(20))
+    );
+    assert_eq!(schema.properties["tags"].unwrap().unwrap().default, None);
+
+    let schema = component!("Optional");
+    assert!(schema.properties.contains_key("page"));
+    assert!(schema.required.is_empty());
+    assert_eq!(
+        schema.properties["not_serialize"].unwrap().unwrap().default,
+        None
+    );
+
+    let schema = component!("Defaults");
+    assert!(schema.required.is_empty());
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "openapi")]
+
+use rweb::*;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+/// Id of a user
+#[derive(Debug, Serialize, Deserialize, Schema)]
+struct UserId(u64);
+
+#[derive(Debug, Serialize, Deserialize, Schema)]
+#[serde(transparent)]
+struct Name {
+    value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Schema)]
+#[serde(transparent)]
+struct Marked<T> {
+    value: u32,
+    #[serde(skip)]
+    _marker: PhantomData<T>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Schema)]
+struct Point(i32, i32);
+
+#[derive(Debug, Serialize, Deserialize, Schema)]
+struct Pair(String, u8);
+
+#[derive(Debug, Serialize, Deserialize, Schema)]
+#[schema(component = "Event")]
+enum Event {
+    Moved(i32, i32),
+    Renamed(Name),
+}
+
+#[derive(Debug, Serialize, Deserialize, Schema)]
+#[serde(tag = "tag", content = "content")]
+#[schema(component = "AdjEvent")]
+enum AdjEvent {
+    Moved(i32, i32),
+}
+
+#[derive(Debug, Serialize, Deserialize, Schema)]
+#[serde(untagged)]
+#[schema(component = "UntEvent")]
+enum UntEvent {
+    Moved(i32, i32),
+}
+
+#[derive(Debug, Serialize, Deserialize, Schema)]
+#[schema(component = "Tuples")]
+struct Tuples {
+    id: UserId,
+    name: Name,
+    marked: Marked<String>,
+    point: Point,
+    pair: Pair,
+    ext: Event,
+    adj: AdjEvent,
+    unt: UntEvent,
+}
+
+#[get("/")]
+fn index(_: Json<Tuples>) -> String {
+    String::new()
+}
+
+#[test]
+fn tuples() {
+    let (spec, _) = openapi::spec().build(|| {
+        //
+        index()
+    });
+
+    let schemas = &spec.components.as_ref().unwrap().schemas;
+    macro_rules! component {
+        ($cn:expr) => {
+            match schemas.get($cn) {
+                Some(openapi::ObjectOrReference::Object(s)) => s,
+                Some(..) => panic!("Component schema can't be a reference"),
+                None => panic!("No component schema for {}", $cn),
+            }
+        };
+    }
+
+    let tuples = component!("Tuples");
+    macro_rules! property {
+        ($name:expr) => {
+            tuples.properties[$name].unwrap().unwrap()
+        };
+    }
+
+    let schema = property!("id");
+    assert_eq!(schema.schema_type, Some(openapi::Type::Integer));
+    assert_eq!(schema.description.trim(), "Id of a user");
+
+    assert_eq!(property!("name").schema_type, Some(openapi::Type::String));
+    assert_eq!(
+        property!("marked").schema_type,
+        Some(openapi::Type::Integer)
+    );
+
+    let schema = property!("point");
+    assert_eq!(schema.schema_type, Some(openapi::Type::Array));
+    assert_eq!(schema.min_items, Some(2));
+    assert_eq!(schema.max_items, Some(2));
+    assert_eq!(
+        schema.items.as_ref().unwrap().unwrap().unwrap().schema_type,
+        Some(openapi::Type::Integer)
+    );
+
+    let schema = property!("pair");
+    assert_eq!(
+        schema
+            .items
+            .as_ref()
+            .unwrap()
+            .unwrap()
+            .unwrap()
+            .one_of
+            .len(),
+        2
+    );
+
+    let schema = component!("Event");
+    assert_eq!(schema.one_of.len(), 2);
+    let moved = schema.one_of[0].unwrap().unwrap();
+    assert_eq!(
+        moved.properties["Moved"].unwrap().unwrap().schema_type,
+        Some(openapi::Type::Array)
+    );
+    let renamed = schema.one_of[1].unwrap().unwrap();
+    assert!(renamed.properties.contains_key("Renamed"));
+
+    let schema = component!("AdjEvent");
+    let moved = schema.one_of[0].unwrap().unwrap();
+    assert_eq!(
+        moved.properties["content"].unwrap().unwrap().max_items,
+        Some(2)
+    );
+
+    let schema = component!("UntEvent");
+    assert_eq!(schema.one_of[0].unwrap().unwrap().max_items, Some(2));
+}
@@ -0,0 +1,72 @@
+use http::StatusCode;
+use rweb::*;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Schema)]
+struct Params {
+    name: String,
+}
+
+#[get("/{id}")]
+fn documented(
+    /// Id of the item
+    id: u32,
+    /// Value of the `accept` header
+    #[header = "accept"]
+    #[allow(unused_variables)]
+    accept: String,
+) -> String {
+    id.to_string()
+}
+
+#[get("/")]
+fn cfg_params(
+    #[cfg(test)]
+    #[header = "accept"]
+    accept: String,
+    #[cfg(not(test))]
+    #[header = "x-disabled"]
+    disabled: String,
+    #[cfg(not(test))] query: Query<Params>,
+) -> String {
+    accept
+}
+
+#[get("/")]
+async fn cfg_result(
+    #[cfg(not(test))]
+    #[header = "x-disabled"]
+    disabled: String,
+    #[allow(unused_mut)] mut query: Query<Params>,
+) -> Result<impl Reply, Rejection> {
+    Ok(query.into_inner().name)
+}
+
+#[tokio::test]
+async fn forwarded_attrs() {
+    let value = warp::test::request()
+        .path("/3")
+        .header("accept", "foo")
+        .reply(&documented())
+        .await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"3"[..]);
+}
+
+#[tokio::test]
+async fn disabled_params_are_not_extracted() {
+    let value = warp::test::request()
+        .path("/")
+        .header("accept", "foo")
+        .reply(&cfg_params())
+        .await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"foo"[..]);
+
+    let value = warp::test::request()
+        .path("/?name=rweb")
+        .reply(&cfg_result())
+        .await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"rweb"[..]);
+}
@@ -0,0 +1,69 @@
+use http::StatusCode;
+use rweb::*;
+
+#[get("/users/{id:int}")]
+fn user_by_id(id: String) -> String {
+    format!("id:{}", id)
+}
+
+#[get("/users/{name}")]
+fn user_by_name(name: String) -> String {
+    format!("name:{}", name)
+}
+
+#[get("/posts/{slug:[a-z-]+}")]
+fn post(slug: String) -> String {
+    slug
+}
+
+#[get("/items/{id:uuid}")]
+fn item(id: String) -> String {
+    id
+}
+
+#[tokio::test]
+async fn constraint_selects_route() {
+    let filter = user_by_id().or(user_by_name());
+
+    let value = warp::test::request().path("/users/42").reply(&filter).await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"id:42"[..]);
+
+    let value = warp::test::request()
+        .path("/users/kdy1")
+        .reply(&filter)
+        .await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"name:kdy1"[..]);
+}
+
+#[tokio::test]
+async fn regex_constraint() {
+    let value = warp::test::request()
+        .path("/posts/hello-world")
+        .reply(&post())
+        .await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"hello-world"[..]);
+
+    let value = warp::test::request()
+        .path("/posts/Hello_World")
+        .reply(&post())
+        .await;
+    assert_eq!(value.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn uuid_constraint() {
+    let value = warp::test::request()
+        .path("/items/67e55044-10b1-426f-9247-bb680e5fe0c8")
+        .reply(&item())
+        .await;
+    assert_eq!(value.status(), StatusCode::OK);
+
+    let value = warp::test::request()
+        .path("/items/67e55044")
+        .reply(&item())
+        .await;
+    assert_eq!(value.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,66 @@
+use http::StatusCode;
+use rweb::*;
+use std::path::PathBuf;
+
+#[get("/files/{*path}")]
+fn file(path: String) -> String {
+    path
+}
+
+#[get("/raw/{id}/{*path}")]
+fn raw(id: u32, path: PathBuf) -> String {
+    format!("{}:{}", id, path.display())
+}
+
+#[tokio::test]
+async fn tail() {
+    let value = warp::test::request()
+        .path("/files/a/b/c.txt")
+        .reply(&file())
+        .await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"a/b/c.txt"[..]);
+}
+
+#[tokio::test]
+async fn tail_is_normalized() {
+    let value = warp::test::request()
+        .path("/files/a/./b/%20c.txt")
+        .reply(&file())
+        .await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"a/b/ c.txt"[..]);
+}
+
+#[tokio::test]
+async fn tail_rejects_traversal() {
+    for path in &[
+        "/files/../etc/passwd",
+        "/files/a/%2e%2e/b",
+        "/files/a%2F..%2Fb",
+        "/files/a%5Cb",
+    ] {
+        let value = warp::test::request().path(path).reply(&file()).await;
+        assert_eq!(value.status(), StatusCode::NOT_FOUND, "{}", path);
+    }
+}
+
+#[tokio::test]
+async fn tail_allows_dot_prefixed_segments() {
+    let value = warp::test::request()
+        .path("/files/..config/...")
+        .reply(&file())
+        .await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"..config/..."[..]);
+}
+
+#[tokio::test]
+async fn tail_after_param() {
+    let value = warp::test::request()
+        .path("/raw/3/dir/file")
+        .reply(&raw())
+        .await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"3:dir/file"[..]);
+}
@@ -0,0 +1,170 @@
+#![allow(clippy::result_large_err)]
+
+use http::StatusCode;
+use rweb::{
+    problem::{recover, Problem},
+    *,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[derive(Debug, Deserialize, Schema)]
+#[schema(validate)]
+struct Item {
+    #[schema(max_length = 4)]
+    name: String,
+}
+
+#[post("/items")]
+fn create(body: Json<Item>) -> String {
+    body.into_inner().name
+}
+
+#[get("/search")]
+fn search(q: Query<Item>) -> String {
+    q.into_inner().name
+}
+
+#[get("/whoami")]
+fn whoami(#[header = "x-user"] user: String) -> String {
+    user
+}
+
+#[get("/items/{id}")]
+fn item(id: u32) -> Result<String, Problem> {
+    Err(Problem::new(StatusCode::NOT_FOUND)
+        .with_type("https://example.com/problems/no-item")
+        .with_detail(format!("item {} does not exist", id))
+        .with_extension("id", id))
+}
+
+async fn problem<F>(req: warp::test::RequestBuilder, filter: &F) -> (StatusCode, Value)
+where
+    F: Filter + 'static,
+    F::Extract: Reply + Send,
+{
+    let res = req.reply(filter).await;
+    assert_eq!(res.headers()["content-type"], "application/problem+json");
+    (res.status(), serde_json::from_slice(res.body()).unwrap())
+}
+
+#[tokio::test]
+async fn warp_rejections() {
+    let routes = create().or(search()).or(whoami()).recover(recover);
+
+    let (status, body) = problem(
+        warp::test::request().path("/nope"),
+        &whoami().recover(recover),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body, json!({ "title": "Not Found", "status": 404 }));
+
+    let (status, body) = problem(warp::test::request().path("/whoami"), &routes).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["detail"], "Missing request header \"x-user\"");
+
+    let (status, _) = problem(warp::test::request().path("/search?foo=1"), &routes).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = problem(
+        warp::test::request()
+            .method("POST")
+            .path("/items")
+            .header("content-type", "application/json")
+            .body("{"),
+        &routes,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["title"], "Bad Request");
+
+    let (status, body) = problem(
+        warp::test::request().method("DELETE").path("/items"),
+        &routes,
+    )
+    .await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(body["status"], 405);
+}
+
+#[tokio::test]
+async fn violations() {
+    let routes = create().recover(recover);
+
+    let (status, body) = problem(
+        warp::test::request()
+            .method("POST")
+            .path("/items")
+            .json(&json!({ "name": "too long" })),
+        &routes,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["title"], "Unprocessable Entity");
+    assert_eq!(body["violations"][0]["path"], "/name");
+    assert_eq!(body["violations"][0]["constraint"], "max_length");
+}
+
+#[tokio::test]
+async fn custom_rejection_is_hidden() {
+    #[derive(Debug)]
+    struct Secret;
+
+    impl reject::Reject for Secret {}
+
+    let routes = warp::any()
+        .and_then(|| async { Err::<String, _>(reject::custom(Secret)) })
+        .recover(recover);
+
+    let (status, body) = problem(warp::test::request(), &routes).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(
+        body,
+        json!({ "title": "Internal Server Error", "status": 500 })
+    );
+}
+
+#[tokio::test]
+async fn handler_error() {
+    let (status, body) = problem(warp::test::request().path("/items/3"), &item()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(
+        body,
+        json!({
+            "type": "https://example.com/problems/no-item",
+            "title": "Not Found",
+            "status": 404,
+            "detail": "item 3 does not exist",
+            "id": 3,
+        })
+    );
+}
+
+#[cfg(feature = "openapi")]
+#[test]
+fn default_response() {
+    let (spec, _) = openapi::spec()
+        .default_response::<Problem>()
+        .build(|| create().or(item()));
+
+    for (path, op) in &[
+        ("/items", spec.paths["/items"].post.as_ref()),
+        ("/items/{id}", spec.paths["/items/{id}"].get.as_ref()),
+    ] {
+        let resp = &op.unwrap().responses["default"];
+        assert_eq!(resp.description, "Error", "{}", path);
+        assert_eq!(
+            resp.content["application/problem+json"].schema,
+            Some(openapi::ComponentOrInlineSchema::Component {
+                name: "Problem".into()
+            })
+        );
+    }
+
+    let schemas = &spec.components.as_ref().unwrap().schemas;
+    match &schemas["Problem"] {
+        openapi::ObjectOrReference::Object(s) => assert_eq!(s.required, vec!["title", "status"]),
+        _ => unreachable!(),
+    }
+}
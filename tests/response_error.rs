@@ -0,0 +1,103 @@
+use http::StatusCode;
+use rweb::*;
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Schema)]
+#[serde(tag = "error")]
+enum Error {
+    NotFound { id: u32 },
+    Forbidden,
+}
+
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Error::NotFound { .. } => StatusCode::NOT_FOUND,
+            Error::Forbidden => StatusCode::FORBIDDEN,
+        }
+    }
+}
+
+#[get("/items/{id}")]
+fn item(id: u32) -> Result<Json<u32>, Error> {
+    match id {
+        0 => Err(Error::Forbidden),
+        1 => Ok(Json::from(id)),
+        _ => Err(Error::NotFound { id }),
+    }
+}
+
+#[get("/async/{id}")]
+async fn async_item(id: u32) -> Result<String, Error> {
+    if id == 1 {
+        Ok(id.to_string())
+    } else {
+        Err(Error::NotFound { id })
+    }
+}
+
+#[get("/rejection/{id}")]
+async fn rejection(id: u32) -> Result<String, Rejection> {
+    if id == 1 {
+        Ok(id.to_string())
+    } else {
+        Err(reject::not_found())
+    }
+}
+
+#[get("/http/{id}")]
+fn http_error(id: u32) -> Result<String, http::Error> {
+    Ok(id.to_string())
+}
+
+#[test]
+fn sync_handler_extracts_response() {
+    fn extracts_response<F: Filter<Extract = (reply::Response,)>>(_: F) {}
+
+    extracts_response(item());
+    extracts_response(http_error());
+}
+
+#[tokio::test]
+async fn sync_handler() {
+    let value = warp::test::request().path("/items/1").reply(&item()).await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"1"[..]);
+
+    let value = warp::test::request().path("/items/2").reply(&item()).await;
+    assert_eq!(value.status(), StatusCode::NOT_FOUND);
+    assert_eq!(value.headers()["content-type"], "application/json");
+    assert_eq!(value.into_body(), br#"{"error":"NotFound","id":2}"#[..]);
+
+    let value = warp::test::request().path("/items/0").reply(&item()).await;
+    assert_eq!(value.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn async_handler() {
+    let value = warp::test::request()
+        .path("/async/1")
+        .reply(&async_item())
+        .await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"1"[..]);
+
+    let value = warp::test::request()
+        .path("/async/3")
+        .reply(&async_item())
+        .await;
+    assert_eq!(value.status(), StatusCode::NOT_FOUND);
+    assert_eq!(value.into_body(), br#"{"error":"NotFound","id":3}"#[..]);
+}
+
+#[tokio::test]
+async fn rejection_is_not_converted() {
+    let filter = rejection().or(async_item());
+
+    let value = warp::test::request()
+        .path("/rejection/2")
+        .reply(&filter)
+        .await;
+    assert_eq!(value.status(), StatusCode::NOT_FOUND);
+    assert_eq!(value.into_body(), b""[..]);
+}
@@ -76,3 +76,231 @@ fn admin_users() -> String {
 #[router("/admin", services(admin_index, admin_users))]
 #[header("X-User-Admin", "1")]
 fn admin() {}
+
+#[get("/repos")]
+fn org_repos(#[router_param] org_id: u64) -> String {
+    format!("repos of {}", org_id)
+}
+
+#[get("/repos/{name}")]
+fn org_repo(name: String, #[router_param] org_id: u64) -> String {
+    format!("{}/{}", org_id, name)
+}
+
+#[router("/orgs/{org_id:int}", services(org_repos, org_repo))]
+fn org(org_id: u64) {}
+
+#[tokio::test]
+async fn path_param_test() {
+    let value = warp::test::request()
+        .path("/orgs/42/repos")
+        .reply(&org())
+        .await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"repos of 42"[..]);
+
+    let value = warp::test::request()
+        .path("/orgs/42/repos/rweb")
+        .reply(&org())
+        .await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"42/rweb"[..]);
+
+    let value = warp::test::request()
+        .path("/orgs/kdy1/repos")
+        .reply(&org())
+        .await;
+    assert_eq!(value.status(), StatusCode::NOT_FOUND);
+}
+
+#[get("/members/{member_id}")]
+fn team_member(
+    #[router_param] org_id: u64,
+    #[router_param] team_id: String,
+    member_id: u64,
+) -> String {
+    format!("{}/{}/{}", org_id, team_id, member_id)
+}
+
+#[router("/teams/{team_id}", services(team_member))]
+fn team(team_id: String, #[router_param] org_id: u64) {}
+
+#[router("/api/orgs/{org_id}", services(team))]
+fn nested_org(org_id: u64) {}
+
+#[tokio::test]
+async fn nested_path_param_test() {
+    let value = warp::test::request()
+        .path("/api/orgs/1/teams/core/members/3")
+        .reply(&nested_org())
+        .await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"1/core/3"[..]);
+
+    let value = warp::test::request()
+        .path("/api/orgs/1/teams/core%20team/members/3")
+        .reply(&nested_org())
+        .await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"1/core team/3"[..]);
+}
+
+#[get("/users")]
+fn api_users() -> String {
+    String::from("users")
+}
+
+#[post("/users")]
+fn api_create_user() -> String {
+    String::from("created")
+}
+
+async fn api_not_found(_: Rejection) -> Result<impl Reply, Rejection> {
+    Ok(reply::with_status(
+        reply::json(&"no route"),
+        StatusCode::NOT_FOUND,
+    ))
+}
+
+#[router("/api", services(api_users, api_create_user), fallback(api_not_found))]
+fn api() {}
+
+#[get("/other")]
+fn other() -> String {
+    String::from("other")
+}
+
+#[tokio::test]
+async fn fallback_test() {
+    let value = warp::test::request().path("/api/users").reply(&api()).await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"users"[..]);
+
+    let value = warp::test::request()
+        .path("/api/unknown")
+        .reply(&api())
+        .await;
+    assert_eq!(value.status(), StatusCode::NOT_FOUND);
+    assert_eq!(value.headers()["content-type"], "application/json");
+    assert_eq!(value.into_body(), b"\"no route\""[..]);
+}
+
+#[tokio::test]
+async fn fallback_does_not_hide_other_routes() {
+    let value = warp::test::request()
+        .path("/other")
+        .reply(&api().or(other()))
+        .await;
+    assert_eq!(value.status(), StatusCode::OK);
+    assert_eq!(value.into_body(), b"other"[..]);
+}
+
+#[get("/items/{id:int}")]
+fn get_item(id: i64) -> String {
+    id.to_string()
+}
+
+#[delete("/items/{id:int}")]
+fn delete_item(id: i64) -> String {
+    id.to_string()
+}
+
+#[post("/items")]
+fn create_item() -> String {
+    String::new()
+}
+
+#[router("/store", services(get_item, delete_item, create_item))]
+fn store() {}
+
+#[tokio::test]
+async fn method_not_allowed_test() {
+    let value = warp::test::request()
+        .method("PUT")
+        .path("/store/items/1")
+        .reply(&store())
+        .await;
+    assert_eq!(value.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(value.headers()["allow"], "GET, DELETE, OPTIONS");
+
+    let value = warp::test::request()
+        .method("GET")
+        .path("/store/items")
+        .reply(&store())
+        .await;
+    assert_eq!(value.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(value.headers()["allow"], "POST, OPTIONS");
+
+    // Constraint of `{id:int}` does not match.
+    let value = warp::test::request()
+        .method("PUT")
+        .path("/store/items/foo")
+        .reply(&store())
+        .await;
+    assert_eq!(value.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn options_test() {
+    let value = warp::test::request()
+        .method("OPTIONS")
+        .path("/store/items/1")
+        .reply(&store())
+        .await;
+    assert_eq!(value.status(), StatusCode::NO_CONTENT);
+    assert_eq!(value.headers()["allow"], "GET, DELETE, OPTIONS");
+}
+
+#[router("/api", services(store))]
+fn nested_store() {}
+
+#[tokio::test]
+async fn nested_method_not_allowed_test() {
+    let value = warp::test::request()
+        .method("PATCH")
+        .path("/api/store/items/1")
+        .reply(&nested_store())
+        .await;
+    assert_eq!(value.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(value.headers()["allow"], "GET, DELETE, OPTIONS");
+}
+
+#[get("/files/{name:[^/]+}")]
+fn get_file(name: String) -> String {
+    name
+}
+
+fn upload() -> impl Clone + Filter<Extract = (String,), Error = Rejection> {
+    warp::path!("upload").and(warp::post()).map(String::new)
+}
+
+#[router("/docs", services(get_file, upload))]
+fn docs() {}
+
+#[tokio::test]
+async fn constraint_with_slash_test() {
+    let value = warp::test::request()
+        .method("PUT")
+        .path("/docs/files/a.txt")
+        .reply(&docs())
+        .await;
+    assert_eq!(value.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(value.headers()["allow"], "GET, OPTIONS");
+}
+
+#[tokio::test]
+async fn filter_method_not_allowed_test() {
+    let value = warp::test::request()
+        .method("GET")
+        .path("/docs/upload")
+        .reply(&docs())
+        .await;
+    assert_eq!(value.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+    let value = warp::test::request()
+        .method("POST")
+        .path("/docs/unknown")
+        .reply(&docs())
+        .await;
+    assert_eq!(value.status(), StatusCode::NOT_FOUND);
+}
@@ -15,3 +15,13 @@ async fn index(#[data] db: Db) -> Result<String, Rejection> {
 
     Ok(items.len().to_string())
 }
+
+#[tokio::test]
+async fn data() {
+    let db = Db {
+        items: Arc::new(Mutex::new(vec![String::from("item")])),
+    };
+
+    let value = warp::test::request().path("/").reply(&index(db)).await;
+    assert_eq!(value.into_body(), b"1"[..]);
+}
@@ -0,0 +1,168 @@
+use http::{HeaderValue, StatusCode};
+use rweb::{
+    headers::{
+        Authorization, ByteRange, ContentType, ETag, Header, HeaderMap, IfNoneMatch, Location,
+        Range, TypedHeader, UserAgent, WithHeader,
+    },
+    problem::{recover, Problem},
+    *,
+};
+
+#[get("/resource")]
+fn resource(
+    user_agent: TypedHeader<UserAgent>,
+    if_none_match: Option<TypedHeader<IfNoneMatch>>,
+) -> WithHeader<String, ETag> {
+    let etag = ETag::strong("v1");
+    let cached = match if_none_match {
+        Some(h) => h.into_inner().matches(&etag),
+        None => false,
+    };
+    let body = format!("{} {}", user_agent.into_inner().as_str(), cached);
+    WithHeader::new(body, etag)
+}
+
+#[get("/headers")]
+fn headers(headers: HeaderMap) -> String {
+    headers.len().to_string()
+}
+
+fn decode<H: Header>(value: &'static str) -> Option<H> {
+    H::decode(&HeaderValue::from_static(value)).ok()
+}
+
+#[tokio::test]
+async fn typed_header() {
+    let res = warp::test::request()
+        .path("/resource")
+        .header("user-agent", "test")
+        .reply(&resource().recover(recover))
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["etag"], "\"v1\"");
+    assert_eq!(res.body(), "test false");
+}
+
+#[tokio::test]
+async fn multiple_lines() {
+    let res = warp::test::request()
+        .path("/resource")
+        .header("user-agent", "test")
+        .header("if-none-match", "\"v0\"")
+        .header("if-none-match", "W/\"v1\"")
+        .reply(&resource().recover(recover))
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), "test true");
+}
+
+#[tokio::test]
+async fn missing_header() {
+    let res = warp::test::request()
+        .path("/resource")
+        .reply(&resource().recover(recover))
+        .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let problem: Problem = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        problem.detail.as_deref(),
+        Some("Invalid header \"user-agent\": missing header")
+    );
+}
+
+#[tokio::test]
+async fn header_map() {
+    let res = warp::test::request()
+        .path("/headers")
+        .header("a", "1")
+        .header("b", "2")
+        .reply(&headers())
+        .await;
+    assert_eq!(res.body(), "2");
+}
+
+#[test]
+fn decode_headers() {
+    let auth: Authorization = decode("Basic dXNlcjpwYXNz").unwrap();
+    assert_eq!(auth.scheme(), "Basic");
+    assert_eq!(auth.credentials(), "dXNlcjpwYXNz");
+
+    let content_type: ContentType = decode("application/json; charset=utf-8").unwrap();
+    assert_eq!(content_type.media_type(), "application/json");
+
+    assert_eq!(decode::<IfNoneMatch>("*"), Some(IfNoneMatch::Any));
+    assert_eq!(
+        decode::<IfNoneMatch>("\"a\", W/\"b,c\""),
+        Some(IfNoneMatch::Tags(vec![
+            ETag::strong("a"),
+            ETag::weak("b,c")
+        ]))
+    );
+    assert_eq!(decode::<IfNoneMatch>("a"), None);
+    assert_eq!(decode::<ETag>("\"a\", \"b\""), None);
+
+    assert_eq!(
+        decode::<Range>("bytes=0-499, 500-, -100"),
+        Some(Range::bytes(vec![
+            ByteRange::FromTo(0, 499),
+            ByteRange::From(500),
+            ByteRange::Last(100),
+        ]))
+    );
+    assert_eq!(decode::<Range>("items=0-1"), None);
+    assert_eq!(decode::<Range>("bytes=2-1"), None);
+    assert_eq!(decode::<Location>("/a").unwrap().as_str(), "/a");
+}
+
+#[test]
+fn encode_headers() {
+    let range = Range::bytes(vec![ByteRange::FromTo(0, 1), ByteRange::Last(5)]);
+    assert_eq!(range.encode().unwrap(), "bytes=0-1,-5");
+    assert_eq!(
+        IfNoneMatch::Tags(vec![ETag::strong("a"), ETag::weak("b")])
+            .encode()
+            .unwrap(),
+        "\"a\", W/\"b\""
+    );
+    assert_eq!(ByteRange::Last(5).bounds(3), Some(0..3));
+    assert_eq!(ByteRange::FromTo(2, 10).bounds(5), Some(2..5));
+    assert_eq!(ByteRange::From(5).bounds(5), None);
+    assert_eq!(ByteRange::FromTo(0, u64::MAX).bounds(5), Some(0..5));
+    assert_eq!(
+        decode::<Range>("bytes=0-18446744073709551615"),
+        Some(Range::bytes(vec![ByteRange::FromTo(0, u64::MAX)]))
+    );
+}
+
+#[cfg(feature = "openapi")]
+#[test]
+fn spec() {
+    use rweb::openapi::{Location as ParamLocation, ObjectOrReference};
+
+    let (spec, _) = openapi::spec().build(|| resource().or(headers()));
+
+    let op = spec.paths["/resource"].get.as_ref().unwrap();
+    let params = op
+        .parameters
+        .iter()
+        .map(|p| match p {
+            ObjectOrReference::Object(p) => (p.name.to_string(), p.location, p.required),
+            _ => panic!("parameter should be inlined"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        params,
+        vec![
+            ("user-agent".to_string(), ParamLocation::Header, Some(true)),
+            (
+                "if-none-match".to_string(),
+                ParamLocation::Header,
+                Some(false)
+            ),
+        ]
+    );
+    assert!(op.responses["200"].headers.contains_key("etag"));
+
+    let op = spec.paths["/headers"].get.as_ref().unwrap();
+    assert!(op.parameters.is_empty());
+}
@@ -0,0 +1,23 @@
+use rweb::*;
+use serde::Serialize;
+
+#[derive(Serialize, ResponseError)]
+enum MissingStatus {
+    #[response(status = 404)]
+    NotFound,
+    Locked,
+}
+
+#[derive(Serialize, ResponseError)]
+enum InvalidStatus {
+    #[response(status = 42)]
+    NotFound,
+}
+
+#[derive(Serialize, ResponseError)]
+enum UnknownConfig {
+    #[response(status = 404, code = "404")]
+    NotFound,
+}
+
+fn main() {}
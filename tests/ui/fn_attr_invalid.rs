@@ -0,0 +1,11 @@
+use rweb::*;
+
+#[get("/")]
+#[header("accept")]
+#[body_size(min = "10")]
+#[cors(origins("*"), credentials, max_age = 600, foo = 1)]
+fn handler() -> String {
+    String::new()
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use rweb::*;
+
+#[get("/")]
+fn handler() {}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use rweb::*;
+
+#[derive(Clone)]
+struct Db;
+
+#[get("/")]
+fn handler(#[data] (_db, _other): (Db, Db)) -> String {
+    String::new()
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use rweb::*;
+
+#[get("/")]
+fn handler(
+    #[header] accept: String,
+    #[cookie("sess")] sess: String,
+    #[filter = "not a path!"] filtered: String,
+) -> String {
+    format!("{}{}{}", accept, sess, filtered)
+}
+
+fn main() {}
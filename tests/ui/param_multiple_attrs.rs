@@ -0,0 +1,13 @@
+use rweb::*;
+
+#[get("/")]
+fn handler(#[header = "accept"] #[cookie = "sess"] value: String) -> String {
+    value
+}
+
+#[get("/{id}")]
+fn cfg_path(#[cfg(all())] id: String) -> String {
+    id
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use rweb::*;
+
+#[get("/")]
+fn handler(#[param(description = "body")] #[body] body: bytes::Bytes) -> String {
+    format!("{:?}", body)
+}
+
+fn main() {}
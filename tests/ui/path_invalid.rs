@@ -0,0 +1,33 @@
+use rweb::*;
+
+#[get("no-slash")]
+fn no_slash() -> String {
+    String::new()
+}
+
+#[get("/double//slash")]
+fn double_slash() -> String {
+    String::new()
+}
+
+#[get("/{id")]
+fn unclosed(id: String) -> String {
+    id
+}
+
+#[get("/{id:[0-9}")]
+fn invalid_constraint(id: String) -> String {
+    id
+}
+
+#[get("/{*rest}/raw")]
+fn tail_not_last(rest: String) -> String {
+    rest
+}
+
+#[get("/{*rest:int}")]
+fn tail_constraint(rest: String) -> String {
+    rest
+}
+
+fn main() {}
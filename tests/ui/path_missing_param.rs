@@ -0,0 +1,8 @@
+use rweb::*;
+
+#[get("/{id}/{name}")]
+fn handler() -> String {
+    String::new()
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use rweb::*;
+
+#[get("/io")]
+fn io() -> std::io::Result<String> {
+    Ok(String::new())
+}
+
+type Result = std::result::Result<String, std::io::Error>;
+
+#[get("/alias")]
+fn alias() -> Result {
+    Ok(String::new())
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+use rweb::*;
+
+#[get("/")]
+fn index() -> String {
+    String::new()
+}
+
+#[router("/api", services(index))]
+fn with_body() {
+    let _ = 1;
+}
+
+#[router("/api", handlers(index))]
+fn unknown_list() {}
+
+#[router("/api", services = "index")]
+fn not_a_list() {}
+
+#[router("/orgs/{org_id}", services(index))]
+fn path_param() {}
+
+#[router("/api", services(index), fallback("handler"))]
+fn invalid_fallback() {}
+
+fn main() {}
@@ -0,0 +1,22 @@
+use rweb::*;
+
+#[get("/repos")]
+fn repos(#[router_param] org_id: u64) -> String {
+    org_id.to_string()
+}
+
+#[router("/orgs/{org_id}", services(repos))]
+fn org_with_u32(org_id: u32) {}
+
+#[router("/users/{user_id}", services(repos))]
+fn user(user_id: u64) {}
+
+#[router("/teams/{team_id}", services(repos))]
+fn team(team_id: u64) {}
+
+#[router("/orgs/{org_id}", services(team))]
+fn org(org_id: u64) {}
+
+fn main() {
+    let _ = repos();
+}
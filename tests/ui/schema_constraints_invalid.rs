@@ -0,0 +1,33 @@
+use rweb::*;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Schema)]
+#[schema(validate)]
+enum NotStruct {
+    A,
+}
+
+#[derive(Debug, Deserialize, Schema)]
+struct Name {
+    value: String,
+}
+
+#[derive(Debug, Deserialize, Schema)]
+#[schema(validate)]
+struct NotNumber {
+    #[schema(min = 1)]
+    name: Name,
+}
+
+#[derive(Debug, Deserialize, Schema)]
+#[schema(validate)]
+struct InvalidOptions {
+    #[schema(max_lenght = 8)]
+    typo: String,
+    #[schema(min_length = "1", pattern = "(")]
+    invalid_values: String,
+    #[schema(enum_values("a", 1))]
+    not_string: String,
+}
+
+fn main() {}
@@ -0,0 +1,147 @@
+use http::StatusCode;
+use rweb::{
+    validation::{Validate, Violation, Violations},
+    *,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[derive(Debug, Deserialize, Schema)]
+struct Transfer {
+    from: String,
+    to: String,
+}
+
+impl Validate for Transfer {
+    fn validate(&self) -> Result<(), Violations> {
+        if self.from == self.to {
+            return Err(Violation::new("/to", "different", "should differ from `from`").into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, Schema)]
+#[schema(validate)]
+struct Range {
+    #[schema(max = 10)]
+    start: u32,
+    end: u32,
+}
+
+impl Validate for Range {
+    fn validate(&self) -> Result<(), Violations> {
+        if self.start > self.end {
+            return Err(Violation::new("/end", "after_start", "should be after start").into());
+        }
+        Ok(())
+    }
+}
+
+#[post("/transfers")]
+fn transfer(body: Validated<Json<Transfer>>) -> String {
+    body.into_inner().into_inner().to
+}
+
+#[get("/range")]
+fn range(q: Validated<Query<Range>>) -> String {
+    let q = q.into_inner().into_inner();
+    format!("{}..{}", q.start, q.end)
+}
+
+async fn recover(r: Rejection) -> Result<impl Reply, Rejection> {
+    match r.find::<Violations>() {
+        Some(violations) => Ok(violations.clone()),
+        None => Err(r),
+    }
+}
+
+#[tokio::test]
+async fn valid() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/transfers")
+        .json(&json!({ "from": "a", "to": "b" }))
+        .reply(&transfer())
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), "b");
+
+    let res = warp::test::request()
+        .path("/range?start=1&end=3")
+        .reply(&range())
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), "1..3");
+}
+
+#[tokio::test]
+async fn invalid() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/transfers")
+        .json(&json!({ "from": "a", "to": "a" }))
+        .reply(&transfer().recover(recover))
+        .await;
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        body,
+        json!({
+            "violations": [{
+                "path": "/to",
+                "constraint": "different",
+                "message": "should differ from `from`",
+            }]
+        })
+    );
+}
+
+#[tokio::test]
+async fn constraints_are_checked() {
+    let res = warp::test::request()
+        .path("/range?start=11&end=20")
+        .reply(&range().recover(recover))
+        .await;
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(body["violations"][0]["path"], "/start");
+
+    let res = warp::test::request()
+        .path("/range?start=5&end=3")
+        .reply(&range().recover(recover))
+        .await;
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(body["violations"][0]["path"], "/end");
+}
+
+#[cfg(feature = "openapi")]
+#[test]
+fn response_is_documented() {
+    let (spec, _) = openapi::spec().build(|| {
+        //
+        transfer().or(range())
+    });
+
+    for path in &["/transfers", "/range"] {
+        let item = &spec.paths[*path];
+        let op = item.post.as_ref().or(item.get.as_ref()).unwrap();
+        let resp = &op.responses["422"];
+        assert_eq!(resp.description, "Unprocessable Entity");
+        assert_eq!(
+            resp.content["application/json"].schema,
+            Some(openapi::ComponentOrInlineSchema::Component {
+                name: "Violations".into()
+            })
+        );
+    }
+
+    let transfer = spec.paths["/transfers"].post.as_ref().unwrap();
+    assert!(transfer.request_body.is_some());
+    let range = spec.paths["/range"].get.as_ref().unwrap();
+    assert_eq!(range.parameters.len(), 2);
+
+    let schemas = &spec.components.as_ref().unwrap().schemas;
+    assert!(schemas.contains_key("Violation"));
+}
@@ -0,0 +1,233 @@
+use http::StatusCode;
+use rweb::{validation::Violations, *};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[derive(Debug, Deserialize, Schema)]
+#[schema(validate)]
+struct Address {
+    #[schema(min_length = 1)]
+    city: String,
+}
+
+#[derive(Debug, Deserialize, Schema)]
+#[schema(validate, component = "CreateUser")]
+#[serde(rename_all = "camelCase")]
+struct CreateUser {
+    #[schema(min_length = 1, max_length = 8, pattern = "^[a-z]+$")]
+    user_name: String,
+    #[schema(min = 13, max = 150)]
+    age: u8,
+    #[schema(min = "-1.5")]
+    score: Option<f64>,
+    #[schema(enum_values("admin", "member"))]
+    role: String,
+    #[schema(max_items = 2)]
+    tags: Vec<String>,
+    address: Address,
+}
+
+#[derive(Debug, Deserialize, Schema)]
+#[schema(validate)]
+struct Page(#[schema(max = 100)] u32);
+
+#[derive(Debug, Deserialize, Schema)]
+#[schema(validate)]
+struct Search {
+    #[schema(min_length = 3)]
+    q: String,
+    #[serde(default)]
+    page: Option<u32>,
+}
+
+/// Not checked at all.
+#[derive(Debug, Deserialize, Schema)]
+struct Unchecked {
+    #[schema(min_length = 3)]
+    q: String,
+}
+
+#[post("/users")]
+fn create_user(body: Json<CreateUser>) -> String {
+    body.into_inner().user_name
+}
+
+#[get("/search")]
+fn search(q: Query<Search>) -> String {
+    q.into_inner().q
+}
+
+#[get("/unchecked")]
+fn unchecked(q: Query<Unchecked>) -> String {
+    q.into_inner().q
+}
+
+async fn recover(r: Rejection) -> Result<impl Reply, Rejection> {
+    match r.find::<Violations>() {
+        Some(violations) => Ok(violations.clone()),
+        None => Err(r),
+    }
+}
+
+fn user(overrides: Value) -> Value {
+    let mut v = json!({
+        "userName": "alice",
+        "age": 20,
+        "score": null,
+        "role": "admin",
+        "tags": [],
+        "address": { "city": "Seoul" },
+    });
+    for (k, value) in overrides.as_object().unwrap() {
+        v[k] = value.clone();
+    }
+    v
+}
+
+async fn violations(
+    filter: impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone + 'static,
+    body: Value,
+) -> Value {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/users")
+        .json(&body)
+        .reply(&filter.recover(recover))
+        .await;
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    serde_json::from_slice(res.body()).unwrap()
+}
+
+#[tokio::test]
+async fn valid() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/users")
+        .json(&user(json!({})))
+        .reply(&create_user())
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), "alice");
+}
+
+#[tokio::test]
+async fn without_recover() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/users")
+        .json(&user(json!({ "age": 3 })))
+        .reply(&create_user())
+        .await;
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[tokio::test]
+async fn problem_recover() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/users")
+        .json(&user(json!({ "age": 3 })))
+        .reply(&create_user().recover(problem::recover))
+        .await;
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn invalid() {
+    let body = violations(
+        create_user(),
+        user(json!({
+            "userName": "Alice_Long",
+            "age": 3,
+            "score": -2,
+            "role": "owner",
+            "tags": ["a", "b", "c"],
+            "address": { "city": "" },
+        })),
+    )
+    .await;
+
+    let violations: Vec<(&str, &str)> = body["violations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| {
+            (
+                v["path"].as_str().unwrap(),
+                v["constraint"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        violations,
+        vec![
+            ("/userName", "max_length"),
+            ("/userName", "pattern"),
+            ("/age", "min"),
+            ("/score", "min"),
+            ("/role", "enum_values"),
+            ("/tags", "max_items"),
+            ("/address/city", "min_length"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn query() {
+    let res = warp::test::request()
+        .path("/search?q=ab")
+        .reply(&search().recover(recover))
+        .await;
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let res = warp::test::request()
+        .path("/search?q=abc")
+        .reply(&search())
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = warp::test::request()
+        .path("/unchecked?q=ab")
+        .reply(&unchecked())
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[test]
+fn newtype() {
+    use rweb::validation::Constraints;
+
+    let mut violations = vec![];
+    Page(101).check_constraints("/page", &mut violations);
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].path, "/page");
+    assert_eq!(violations[0].message, "should be at most 100");
+}
+
+#[cfg(feature = "openapi")]
+#[test]
+fn schema() {
+    let (spec, _) = openapi::spec().build(|| {
+        //
+        create_user()
+    });
+    let schema = match &spec.components.as_ref().unwrap().schemas["CreateUser"] {
+        openapi::ObjectOrReference::Object(s) => s,
+        _ => unreachable!(),
+    };
+    macro_rules! property {
+        ($name:expr) => {
+            schema.properties[$name].unwrap().unwrap()
+        };
+    }
+
+    let user_name = property!("userName");
+    assert_eq!(user_name.min_length, Some(1));
+    assert_eq!(user_name.max_length, Some(8));
+    assert_eq!(user_name.pattern, "^[a-z]+$");
+    assert_eq!(property!("age").minimum, Some(json!(13)));
+    assert_eq!(property!("age").maximum, Some(json!(150)));
+    assert_eq!(property!("score").minimum, Some(json!(-1.5)));
+    assert_eq!(property!("role").enum_values, vec!["admin", "member"]);
+    assert_eq!(property!("tags").max_items, Some(2));
+}